@@ -13,11 +13,21 @@ use account::{
   store::AccountStore,
 };
 use profiles::commands::{
-  instance_list, instance_logs, instance_stop, profile_cancel_download, profile_clear_logs,
-  profile_create, profile_favorites_list, profile_favorites_set, profile_get_icon,
-  profile_history_list, profile_launch, profile_list, profile_logs, profile_open_path,
+  instance_list, instance_logs, instance_stats, instance_stop, mod_bisect_get, mod_bisect_report,
+  mod_bisect_start, mod_bisect_stop, mod_check, mod_list, mod_set_enabled, pack_import,
+  pack_install, pack_list, pack_remove, pack_set_enabled, profile_backup, profile_backup_list,
+  profile_backup_list_removed, profile_backup_remove, profile_backup_settings_set,
+  profile_cancel_download, profile_clear_logs, profile_crash_info, profile_create,
+  profile_duplicate, profile_favorites_list, profile_favorites_set, profile_get_icon,
+  profile_groups_list, profile_history_list, profile_import, profile_import_scan, profile_launch,
+  profile_list, profile_loader_update_check, profile_logs, profile_mods_check_updates,
+  profile_mods_set_pinned, profile_mods_update, profile_move_group, profile_open_path,
   profile_quick_play_icon, profile_quick_play_list, profile_quick_play_remove, profile_remove,
-  profile_repair, profile_runs_list, profile_update, profile_update_icon,
+  profile_reorder, profile_repair, profile_restore, profile_restore_preview, profile_run_summary,
+  profile_runs_list, profile_set_pinned, profile_set_tags, profile_stats, profile_switch_loader,
+  profile_switch_loader_check, profile_update, profile_update_icon, server_add, server_list,
+  server_move, server_ping, server_remove, server_update, world_copy, world_export, world_import,
+  world_info_get, world_list, world_newer_get, world_remove,
 };
 use settings::{settings_get, settings_set};
 use tauri::{AppHandle, Emitter, Manager, Url, webview::PageLoadEvent};
@@ -95,8 +105,54 @@ pub fn run() {
       loader_version_list,
       //profiles
       profile_create,
+      profile_duplicate,
+      profile_import_scan,
+      profile_import,
+      profile_backup,
+      profile_backup_list,
+      profile_backup_list_removed,
+      profile_backup_remove,
+      profile_backup_settings_set,
+      profile_restore_preview,
+      profile_restore,
+      world_list,
+      world_info_get,
+      world_newer_get,
+      world_export,
+      world_import,
+      world_copy,
+      world_remove,
+      mod_list,
+      mod_check,
+      mod_set_enabled,
+      mod_bisect_get,
+      mod_bisect_start,
+      mod_bisect_report,
+      mod_bisect_stop,
+      profile_mods_check_updates,
+      profile_mods_update,
+      profile_mods_set_pinned,
+      pack_list,
+      pack_set_enabled,
+      pack_import,
+      pack_install,
+      pack_remove,
+      server_list,
+      server_add,
+      server_update,
+      server_move,
+      server_remove,
+      server_ping,
+      profile_groups_list,
+      profile_move_group,
+      profile_reorder,
+      profile_set_tags,
+      profile_set_pinned,
       profile_remove,
       profile_update,
+      profile_switch_loader_check,
+      profile_switch_loader,
+      profile_loader_update_check,
       profile_get_icon,
       profile_open_path,
       profile_update_icon,
@@ -107,6 +163,9 @@ pub fn run() {
       profile_runs_list,
       profile_clear_logs,
       profile_logs,
+      profile_crash_info,
+      profile_run_summary,
+      profile_stats,
       profile_quick_play_list,
       profile_quick_play_remove,
       profile_quick_play_icon,
@@ -117,6 +176,7 @@ pub fn run() {
       //instances
       instance_list,
       instance_logs,
+      instance_stats,
       instance_stop,
       //settings
       settings_get,
@@ -141,6 +201,8 @@ pub fn run() {
       let handle = app.handle().clone();
       app.manage(tauri::async_runtime::spawn(async move {
         handle.manage(MaxMem::new());
+        let profiles = handle.state::<Mutex<ProfileStore>>();
+        let _ = profiles.lock().await.reattach_instances().await.log();
         if let Err(err) = async_online_check(&handle).await.log() {
           log::error!("Error: {err}");
           let _ = handle.emit(MANIFEST_REFRESH_ERROR, ()).log();
@@ -209,10 +271,11 @@ async fn async_setup_refresh(handle: &AppHandle) -> Result<()> {
   version_store.update_manifests(mc_manifest, java_manifest)?;
   drop(version_store);
 
+  let data_dir = handle.path().app_data_dir()?;
+  let version_path = MCVersionPath::new(&data_dir, "");
   for loader in LoaderType::mod_loaders() {
-    let data_dir = handle.path().app_data_dir()?;
-    let version_path = MCVersionPath::new(&data_dir, "");
-    loader.download_metadata(&client, &version_path).await?;
+    // one unreachable meta host should not prevent the others from refreshing
+    let _ = loader.download_metadata(&client, &version_path).await.log();
   }
 
   Ok(())
@@ -0,0 +1,529 @@
+use std::{
+  collections::{HashMap, HashSet},
+  path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use async_zip::{
+  Compression, ZipEntryBuilder, base::write::ZipFileWriter, tokio::read::fs::ZipFileReader,
+};
+use chrono::{DateTime, Datelike, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::{fs, io};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+use crate::{
+  path,
+  profiles::{
+    PROFILE_DIR,
+    config::Profile,
+    profile::{parse_timestamp_file_stem, timestamp_file_stem},
+  },
+  utils::{
+    dir::list_files_in_dir,
+    file::create_or_open_file,
+    log::ResultLogExt,
+    zip::{archive_name, safe_path, write_files_to_zip},
+  },
+};
+
+const BACKUPS_DIR: &str = "backups";
+const BACKUP_MANIFEST: &str = "backup.json";
+const BACKUP_EXTENSION: &str = ".zip";
+// all backed up files are stored below this prefix in the archive
+const FILES_PREFIX: &str = "files/";
+// held open by a running game and can not be read on some platforms
+const SESSION_LOCK: &str = "session.lock";
+// folders next to the profile used while a backup is restored
+const STAGING_SUFFIX: &str = ".restore";
+const PREVIOUS_SUFFIX: &str = ".previous";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupSettings {
+  // files and folders relative to the profile directory
+  pub folders: Vec<String>,
+  pub before_launch: bool,
+  pub before_remove: bool,
+  pub retention: BackupRetention,
+}
+
+impl Default for BackupSettings {
+  fn default() -> Self {
+    Self {
+      folders: vec!["saves".into(), "config".into(), "options.txt".into()],
+      before_launch: false,
+      before_remove: true,
+      retention: BackupRetention::default(),
+    }
+  }
+}
+
+/// A backup is kept if any of the rules matches
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BackupRetention {
+  pub keep_last: usize,
+  // keeps the newest backup of each of the last n days
+  pub keep_daily: usize,
+  // keeps the newest backup of each of the last n weeks
+  pub keep_weekly: usize,
+}
+
+impl Default for BackupRetention {
+  fn default() -> Self {
+    Self {
+      keep_last: 5,
+      keep_daily: 7,
+      keep_weekly: 4,
+    }
+  }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupReason {
+  Manual,
+  Launch,
+  Remove,
+  // taken before a world is opened in an older version
+  Downgrade,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct BackupManifest {
+  created_at: DateTime<Utc>,
+  reason: BackupReason,
+  folders: Vec<String>,
+  // needed to restore a removed profile
+  profile: Profile,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BackupInfo {
+  pub profile_id: String,
+  pub profile_name: String,
+  pub created_at: DateTime<Utc>,
+  pub reason: BackupReason,
+  pub folders: Vec<String>,
+  // in bytes
+  pub size: u64,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+  Added,
+  Modified,
+  Removed,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RestoreChange {
+  pub path: String,
+  pub kind: ChangeKind,
+}
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+  #[error("NotFound")]
+  NotFound,
+  #[error("InvalidBackup")]
+  InvalidBackup,
+  #[error("ProfileRunning")]
+  ProfileRunning,
+}
+
+pub fn backups_dir(data_dir: &Path) -> PathBuf {
+  path!(data_dir, BACKUPS_DIR)
+}
+
+fn backup_file(data_dir: &Path, profile: &str, created_at: DateTime<Utc>) -> PathBuf {
+  path!(
+    backups_dir(data_dir),
+    profile,
+    format!("{}{BACKUP_EXTENSION}", timestamp_file_stem(created_at))
+  )
+}
+
+/// Writes the configured folders of the profile into a new zip and rotates the old backups
+pub async fn create_backup(
+  data_dir: &Path,
+  profile: &Profile,
+  reason: BackupReason,
+) -> Result<BackupInfo> {
+  create_backup_of(data_dir, profile, reason, &profile.backup.folders).await
+}
+
+/// Same as [`create_backup`] but only backs up the given files and folders
+pub async fn create_backup_of(
+  data_dir: &Path,
+  profile: &Profile,
+  reason: BackupReason,
+  folders: &[String],
+) -> Result<BackupInfo> {
+  let profile_dir = path!(data_dir, profile.relative_to_data());
+  let manifest = BackupManifest {
+    created_at: Utc::now(),
+    reason,
+    folders: folders.to_vec(),
+    profile: profile.clone(),
+  };
+
+  let path = backup_file(data_dir, &profile.id, manifest.created_at);
+  let mut writer = ZipFileWriter::with_tokio(create_or_open_file(&path).await?);
+  writer
+    .write_entry_whole(
+      ZipEntryBuilder::new(BACKUP_MANIFEST.into(), Compression::Deflate),
+      &serde_json::to_vec(&manifest)?,
+    )
+    .await?;
+
+  let files = backed_up_files(&profile_dir, folders)
+    .await?
+    .into_iter()
+    .filter(|f| !f.ends_with(SESSION_LOCK))
+    .collect::<Vec<_>>();
+  write_files_to_zip(&mut writer, &profile_dir, &files, FILES_PREFIX).await?;
+  writer.close().await?;
+  debug!(
+    "Created backup of profile {} at {}",
+    profile.id,
+    path.display()
+  );
+
+  rotate_backups(data_dir, &profile.id, &profile.backup.retention).await?;
+
+  Ok(BackupInfo {
+    profile_id: profile.id.clone(),
+    profile_name: profile.name.clone(),
+    created_at: manifest.created_at,
+    reason,
+    folders: manifest.folders,
+    size: fs::metadata(&path).await?.len(),
+  })
+}
+
+pub async fn list_backups(data_dir: &Path, profile: &str) -> Result<Vec<BackupInfo>> {
+  let mut backups = Vec::new();
+  for created_at in backup_timestamps(data_dir, profile).await? {
+    let path = backup_file(data_dir, profile, created_at);
+    let Ok(manifest) = read_manifest(&path).await else {
+      debug!("Skipping invalid backup {}", path.display());
+      continue;
+    };
+
+    backups.push(BackupInfo {
+      profile_id: profile.to_string(),
+      profile_name: manifest.profile.name,
+      created_at,
+      reason: manifest.reason,
+      folders: manifest.folders,
+      size: fs::metadata(&path).await?.len(),
+    });
+  }
+  backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+
+  Ok(backups)
+}
+
+/// Ids of all profiles that have backups
+pub async fn backed_up_profiles(data_dir: &Path) -> Result<Vec<String>> {
+  let dir = backups_dir(data_dir);
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut profiles = Vec::new();
+  let mut stream = fs::read_dir(dir).await?;
+  while let Some(entry) = stream.next_entry().await? {
+    if entry.file_type().await?.is_dir()
+      && let Some(name) = entry.file_name().to_str()
+    {
+      profiles.push(name.to_string());
+    }
+  }
+
+  Ok(profiles)
+}
+
+pub async fn remove_backup(
+  data_dir: &Path,
+  profile: &str,
+  created_at: DateTime<Utc>,
+) -> Result<()> {
+  let path = backup_file(data_dir, profile, created_at);
+  if !path.exists() {
+    return Err(BackupError::NotFound.into());
+  }
+
+  fs::remove_file(path).await?;
+  Ok(())
+}
+
+/// Lists the files that would be added, overwritten or removed by restoring the backup
+pub async fn preview_restore(
+  data_dir: &Path,
+  profile: &str,
+  created_at: DateTime<Utc>,
+) -> Result<Vec<RestoreChange>> {
+  let path = backup_file(data_dir, profile, created_at);
+  if !path.exists() {
+    return Err(BackupError::NotFound.into());
+  }
+  let manifest = read_manifest(&path).await?;
+  let profile_dir = path!(data_dir, PROFILE_DIR, profile);
+
+  let zip = ZipFileReader::new(&path).await?;
+  let mut archived: HashMap<String, (u64, u32)> = HashMap::new();
+  for entry in zip.file().entries() {
+    if let Some(name) = entry
+      .filename()
+      .as_str()
+      .ok()
+      .and_then(|n| n.strip_prefix(FILES_PREFIX))
+    {
+      archived.insert(name.to_string(), (entry.uncompressed_size(), entry.crc32()));
+    }
+  }
+
+  let mut changes = Vec::new();
+  let mut existing = HashSet::new();
+  for relative in backed_up_files(&profile_dir, &manifest.folders).await? {
+    let name = archive_name(&relative);
+    match archived.get(&name) {
+      Some((size, crc)) => {
+        let data = fs::read(path!(&profile_dir, &relative)).await?;
+        if data.len() as u64 != *size || crc32fast::hash(&data) != *crc {
+          changes.push(RestoreChange {
+            path: name.clone(),
+            kind: ChangeKind::Modified,
+          });
+        }
+      }
+      None => changes.push(RestoreChange {
+        path: name.clone(),
+        kind: ChangeKind::Removed,
+      }),
+    }
+    existing.insert(name);
+  }
+  for name in archived.into_keys() {
+    if !existing.contains(&name) {
+      changes.push(RestoreChange {
+        path: name,
+        kind: ChangeKind::Added,
+      });
+    }
+  }
+  changes.sort_by(|a, b| a.path.cmp(&b.path));
+
+  Ok(changes)
+}
+
+/// Replaces the backed up folders of the profile with the content of the backup.
+/// Returns the profile as it was when the backup was created.
+pub async fn restore_backup(
+  data_dir: &Path,
+  profile: &str,
+  created_at: DateTime<Utc>,
+) -> Result<Profile> {
+  let path = backup_file(data_dir, profile, created_at);
+  if !path.exists() {
+    return Err(BackupError::NotFound.into());
+  }
+  let manifest = read_manifest(&path).await?;
+  let folders = manifest
+    .folders
+    .iter()
+    .map(|f| checked_path(f))
+    .collect::<Result<Vec<_>>>()?;
+  let profile_dir = path!(data_dir, PROFILE_DIR, profile);
+
+  // the backup is extracted next to the profile first, so a failed extraction leaves it untouched
+  let staging = path!(data_dir, PROFILE_DIR, format!(".{profile}{STAGING_SUFFIX}"));
+  let previous = path!(
+    data_dir,
+    PROFILE_DIR,
+    format!(".{profile}{PREVIOUS_SUFFIX}")
+  );
+  remove_path(&staging).await?;
+  remove_path(&previous).await?;
+
+  if let Err(err) = extract_backup(&path, &staging).await {
+    let _ = remove_path(&staging).await;
+    return Err(err);
+  }
+
+  for (i, folder) in folders.iter().enumerate() {
+    if let Err(err) = swap_folder(folder, &profile_dir, &staging, &previous).await {
+      // put back the folders that were already replaced
+      for folder in folders[..=i].iter().rev() {
+        let _ = restore_folder(folder, &profile_dir, &previous).await.log();
+      }
+      let _ = remove_path(&staging).await;
+      return Err(err);
+    }
+  }
+  remove_path(&staging).await?;
+  remove_path(&previous).await?;
+  debug!("Restored backup {} of profile {profile}", path.display());
+
+  Ok(manifest.profile)
+}
+
+async fn extract_backup(path: &Path, target: &Path) -> Result<()> {
+  let zip = ZipFileReader::new(path).await?;
+  for i in 0..zip.file().entries().len() {
+    let reader = zip.reader_with_entry(i).await?;
+    let Some(name) = reader
+      .entry()
+      .filename()
+      .as_str()
+      .ok()
+      .and_then(|n| n.strip_prefix(FILES_PREFIX))
+      .map(String::from)
+    else {
+      continue;
+    };
+
+    let mut file = create_or_open_file(&path!(target, checked_path(&name)?)).await?;
+    io::copy(&mut reader.compat(), &mut file).await?;
+  }
+
+  Ok(())
+}
+
+/// Moves the current folder out of the profile and the restored one into its place
+async fn swap_folder(
+  folder: &Path,
+  profile_dir: &Path,
+  staging: &Path,
+  previous: &Path,
+) -> Result<()> {
+  let current = path!(profile_dir, folder);
+  if current.exists() {
+    move_path(&current, &path!(previous, folder)).await?;
+  }
+  let restored = path!(staging, folder);
+  if restored.exists() {
+    move_path(&restored, &current).await?;
+  }
+
+  Ok(())
+}
+
+async fn restore_folder(folder: &Path, profile_dir: &Path, previous: &Path) -> Result<()> {
+  let old = path!(previous, folder);
+  if old.exists() {
+    let current = path!(profile_dir, folder);
+    remove_path(&current).await?;
+    move_path(&old, &current).await?;
+  }
+
+  Ok(())
+}
+
+async fn move_path(from: &Path, to: &Path) -> Result<()> {
+  if let Some(parent) = to.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+  fs::rename(from, to).await?;
+  Ok(())
+}
+
+async fn remove_path(path: &Path) -> Result<()> {
+  if path.is_dir() {
+    fs::remove_dir_all(path).await?;
+  } else if path.exists() {
+    fs::remove_file(path).await?;
+  }
+  Ok(())
+}
+
+async fn read_manifest(path: &Path) -> Result<BackupManifest> {
+  let zip = ZipFileReader::new(path).await?;
+  let index = zip
+    .file()
+    .entries()
+    .iter()
+    .position(|e| e.filename().as_str().ok() == Some(BACKUP_MANIFEST))
+    .ok_or(BackupError::InvalidBackup)?;
+
+  let mut reader = zip.reader_with_entry(index).await?;
+  let mut data = Vec::new();
+  reader.read_to_end_checked(&mut data).await?;
+
+  Ok(serde_json::from_slice(&data)?)
+}
+
+async fn backup_timestamps(data_dir: &Path, profile: &str) -> Result<Vec<DateTime<Utc>>> {
+  let dir = path!(backups_dir(data_dir), profile);
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut res = Vec::new();
+  let mut stream = fs::read_dir(dir).await?;
+  while let Some(entry) = stream.next_entry().await? {
+    if let Some(name) = entry.file_name().to_str()
+      && let Some(stem) = name.strip_suffix(BACKUP_EXTENSION)
+      && let Some(date) = parse_timestamp_file_stem(stem)
+    {
+      res.push(date);
+    }
+  }
+
+  Ok(res)
+}
+
+async fn rotate_backups(data_dir: &Path, profile: &str, retention: &BackupRetention) -> Result<()> {
+  let mut timestamps = backup_timestamps(data_dir, profile).await?;
+  timestamps.sort_by_key(|t| std::cmp::Reverse(*t));
+
+  let mut days = HashSet::new();
+  let mut weeks = HashSet::new();
+  for (i, timestamp) in timestamps.into_iter().enumerate() {
+    // timestamps are sorted newest first so the first backup of a day or week is the newest
+    let new_day = days.insert(timestamp.date_naive());
+    let week = timestamp.iso_week();
+    let new_week = weeks.insert((week.year(), week.week()));
+
+    let keep = i == 0
+      || i < retention.keep_last
+      || (new_day && days.len() <= retention.keep_daily)
+      || (new_week && weeks.len() <= retention.keep_weekly);
+    if !keep {
+      debug!("Removing old backup {timestamp} of profile {profile}");
+      fs::remove_file(backup_file(data_dir, profile, timestamp)).await?;
+    }
+  }
+
+  Ok(())
+}
+
+/// Lists all files below the given files and folders of the profile
+async fn backed_up_files(profile_dir: &Path, folders: &[String]) -> Result<Vec<PathBuf>> {
+  let mut files = Vec::new();
+  for folder in folders {
+    let relative = checked_path(folder)?;
+    let path = path!(profile_dir, &relative);
+    if path.is_dir() {
+      files.extend(
+        list_files_in_dir(&path)
+          .await?
+          .into_iter()
+          .map(|f| path!(&relative, f)),
+      );
+    } else if path.is_file() {
+      files.push(relative);
+    }
+  }
+
+  Ok(files)
+}
+
+/// Rejects paths that would escape the profile directory
+fn checked_path(path: &str) -> Result<PathBuf> {
+  Ok(safe_path(path).ok_or(BackupError::InvalidBackup)?)
+}
@@ -0,0 +1,210 @@
+use std::{path::Path, slice};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::profiles::{
+  mods::{DependencyKind, ModError, ModInfo, ModManifest, is_enabled, mod_files, set_mod_enabled},
+  sessions::load_sessions,
+};
+
+#[derive(Error, Debug)]
+pub enum BisectError {
+  #[error("NotRunning")]
+  NotRunning,
+  #[error("AlreadyRunning")]
+  AlreadyRunning,
+  #[error("NotEnoughMods")]
+  NotEnoughMods,
+  #[error("NoRun")]
+  NoRun,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BisectStep {
+  pub started_at: DateTime<Utc>,
+  // suspects that stay enabled in this step
+  pub enabled: Vec<String>,
+  // suspects that are disabled in this step
+  pub disabled: Vec<String>,
+  pub crashed: Option<bool>,
+}
+
+/// Searches a crashing mod by disabling half of the suspects per launch.
+/// Mods required by other mods stay enabled, disabling them would make their dependents fail.
+/// All mods are stored with their enabled file name.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bisect {
+  // mods enabled before the bisect, restored when it ends
+  pub original: Vec<String>,
+  pub suspects: Vec<String>,
+  pub steps: Vec<BisectStep>,
+  // set once only one suspect is left
+  pub result: Option<String>,
+}
+
+async fn set_enabled(profile_dir: &Path, files: &[String], enabled: bool) -> Result<()> {
+  for file in files {
+    match set_mod_enabled(profile_dir, file, enabled).await {
+      Ok(_) => (),
+      // the mod was removed while the bisect was running
+      Err(err) if matches!(err.downcast_ref(), Some(ModError::NotFound)) => {
+        debug!("Skipping removed mod {file}");
+      }
+      Err(err) => return Err(err),
+    }
+  }
+  Ok(())
+}
+
+/// The files of enabled mods that provide a required dependency of another enabled mod
+fn required_files(mods: &[ModInfo]) -> Vec<String> {
+  let loaded = mods
+    .iter()
+    .filter(|m| m.enabled && m.loader.is_some() && !m.loader_mismatch)
+    .collect::<Vec<_>>();
+  let provides =
+    |info: &ModInfo, id: &str| info.id == id || info.provides.iter().any(|p| p.id == id);
+
+  loaded
+    .iter()
+    .filter(|provider| {
+      loaded.iter().any(|dependent| {
+        dependent.file != provider.file
+          && dependent
+            .dependencies
+            .iter()
+            .any(|d| d.kind == DependencyKind::Required && provides(provider, &d.id))
+      })
+    })
+    .map(|m| m.file.clone())
+    .collect()
+}
+
+impl Bisect {
+  /// Disables the first half of the suspects and enables everything else
+  async fn next_step(&mut self, profile_dir: &Path) -> Result<()> {
+    let (disabled, enabled) = self.suspects.split_at(self.suspects.len() / 2);
+    let step = BisectStep {
+      started_at: Utc::now(),
+      enabled: enabled.to_vec(),
+      disabled: disabled.to_vec(),
+      crashed: None,
+    };
+
+    set_enabled(profile_dir, &self.original, true).await?;
+    set_enabled(profile_dir, &step.disabled, false).await?;
+    self.steps.push(step);
+
+    Ok(())
+  }
+}
+
+/// `mods` are the scanned mods of the profile, used to keep dependencies enabled
+pub async fn bisect_start(profile_dir: &Path, mods: &[ModInfo]) -> Result<Bisect> {
+  let mut manifest = ModManifest::load(profile_dir).await?;
+  if manifest.bisect.is_some() {
+    return Err(BisectError::AlreadyRunning.into());
+  }
+
+  let mut original = mod_files(profile_dir)
+    .await?
+    .into_iter()
+    .filter(|f| is_enabled(f))
+    .collect::<Vec<_>>();
+  original.sort();
+
+  let required = required_files(mods);
+  let suspects = original
+    .iter()
+    .filter(|f| !required.contains(f))
+    .cloned()
+    .collect::<Vec<_>>();
+  if suspects.len() < 2 {
+    return Err(BisectError::NotEnoughMods.into());
+  }
+
+  let mut bisect = Bisect {
+    suspects,
+    original,
+    steps: Vec::new(),
+    result: None,
+  };
+  bisect.next_step(profile_dir).await?;
+
+  manifest.bisect = Some(bisect.clone());
+  manifest.save(profile_dir).await?;
+
+  Ok(bisect)
+}
+
+/// Records whether the game crashed with the current step and moves to the next one.
+/// Without `crashed` the last session since the step started is used.
+pub async fn bisect_report(profile_dir: &Path, crashed: Option<bool>) -> Result<Bisect> {
+  let mut manifest = ModManifest::load(profile_dir).await?;
+  let Some(bisect) = &mut manifest.bisect else {
+    return Err(BisectError::NotRunning.into());
+  };
+  let Some(step) = bisect.steps.last_mut().filter(|_| bisect.result.is_none()) else {
+    return Err(BisectError::NotRunning.into());
+  };
+
+  let crashed = match crashed {
+    Some(crashed) => crashed,
+    None => {
+      load_sessions(profile_dir)
+        .await?
+        .into_iter()
+        .filter(|s| s.launched_at >= step.started_at)
+        .max_by_key(|s| s.launched_at)
+        .ok_or(BisectError::NoRun)?
+        .crashed
+    }
+  };
+  step.crashed = Some(crashed);
+  bisect.suspects = if crashed {
+    step.enabled.clone()
+  } else {
+    step.disabled.clone()
+  };
+
+  if let [culprit] = bisect.suspects.as_slice() {
+    // everything but the found mod is enabled again
+    bisect.result = Some(culprit.clone());
+    set_enabled(profile_dir, &bisect.original, true).await?;
+    set_enabled(profile_dir, slice::from_ref(culprit), false).await?;
+  } else {
+    bisect.next_step(profile_dir).await?;
+  }
+
+  let bisect = bisect.clone();
+  manifest.save(profile_dir).await?;
+
+  Ok(bisect)
+}
+
+/// Ends the bisect and restores the mods that were enabled before it started,
+/// a found mod stays disabled
+pub async fn bisect_stop(profile_dir: &Path) -> Result<()> {
+  let mut manifest = ModManifest::load(profile_dir).await?;
+  let Some(bisect) = manifest.bisect.take() else {
+    return Err(BisectError::NotRunning.into());
+  };
+
+  let restore = bisect
+    .original
+    .into_iter()
+    .filter(|f| Some(f) != bisect.result.as_ref())
+    .collect::<Vec<_>>();
+  set_enabled(profile_dir, &restore, true).await?;
+  manifest.save(profile_dir).await?;
+
+  Ok(())
+}
+
+pub async fn bisect_get(profile_dir: &Path) -> Result<Option<Bisect>> {
+  Ok(ModManifest::load(profile_dir).await?.bisect)
+}
@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use log::trace;
+use tauri::{Result, State};
+use tokio::sync::Mutex;
+
+use crate::{
+  profiles::{
+    backup::{
+      BackupInfo, BackupReason, BackupSettings, RestoreChange, create_backup, list_backups,
+      preview_restore, remove_backup,
+    },
+    store::ProfileStore,
+  },
+  utils::{log::ResultLogExt, updater::UpdateType},
+};
+
+#[tauri::command]
+pub async fn profile_backup(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<BackupInfo> {
+  trace!("Command profile_backup called with profile {profile}");
+  let store = state.lock().await;
+
+  let profile = store.profile(profile).await.log()?;
+  let info = create_backup(store.data_dir(), &profile, BackupReason::Manual)
+    .await
+    .log()?;
+  store.update_data(UpdateType::ProfileBackups);
+
+  Ok(info)
+}
+
+#[tauri::command]
+pub async fn profile_backup_list(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<Vec<BackupInfo>> {
+  trace!("Command profile_backup_list called with profile {profile}");
+  let store = state.lock().await;
+  Ok(list_backups(store.data_dir(), profile).await.log()?)
+}
+
+#[tauri::command]
+pub async fn profile_backup_list_removed(
+  state: State<'_, Mutex<ProfileStore>>,
+) -> Result<Vec<BackupInfo>> {
+  trace!("Command profile_backup_list_removed called");
+  let store = state.lock().await;
+  Ok(store.list_removed_backups().await.log()?)
+}
+
+#[tauri::command]
+pub async fn profile_backup_remove(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  timestamp: DateTime<Utc>,
+) -> Result<()> {
+  trace!("Command profile_backup_remove called with profile {profile} timestamp {timestamp}");
+  let store = state.lock().await;
+
+  remove_backup(store.data_dir(), profile, timestamp)
+    .await
+    .log()?;
+  store.update_data(UpdateType::ProfileBackups);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn profile_backup_settings_set(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  settings: BackupSettings,
+) -> Result<()> {
+  trace!("Command profile_backup_settings_set called with profile {profile} settings {settings:?}");
+  let store = state.lock().await;
+
+  let mut profile = store.profile(profile).await.log()?;
+  profile.backup = settings;
+  profile.update(store.data_dir()).await.log()?;
+  store.update_data(UpdateType::Profiles);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn profile_restore_preview(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  timestamp: DateTime<Utc>,
+) -> Result<Vec<RestoreChange>> {
+  trace!("Command profile_restore_preview called with profile {profile} timestamp {timestamp}");
+  let store = state.lock().await;
+  Ok(
+    preview_restore(store.data_dir(), profile, timestamp)
+      .await
+      .log()?,
+  )
+}
+
+#[tauri::command]
+pub async fn profile_restore(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  timestamp: DateTime<Utc>,
+) -> Result<()> {
+  trace!("Command profile_restore called with profile {profile} timestamp {timestamp}");
+  let mut store = state.lock().await;
+
+  store.restore_profile(profile, timestamp).await.log()?;
+  store.update_data(UpdateType::Profiles);
+  store.update_data(UpdateType::ProfileQuickPlay);
+
+  Ok(())
+}
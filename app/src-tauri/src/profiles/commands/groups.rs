@@ -0,0 +1,80 @@
+use log::trace;
+use tauri::{Result, State};
+use tokio::sync::Mutex;
+
+use crate::{
+  profiles::store::ProfileStore,
+  utils::{log::ResultLogExt, updater::UpdateType},
+};
+
+#[tauri::command]
+pub async fn profile_groups_list(state: State<'_, Mutex<ProfileStore>>) -> Result<Vec<String>> {
+  trace!("Command profile_groups_list called");
+  let store = state.lock().await;
+  Ok(store.list_groups().await.log()?)
+}
+
+#[tauri::command]
+pub async fn profile_move_group(
+  state: State<'_, Mutex<ProfileStore>>,
+  profiles: Vec<String>,
+  group: Option<String>,
+) -> Result<()> {
+  trace!("Command profile_move_group called with profiles {profiles:?} group {group:?}");
+  let store = state.lock().await;
+
+  store.move_profiles(&profiles, group).await.log()?;
+  store.update_data(UpdateType::Profiles);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn profile_reorder(
+  state: State<'_, Mutex<ProfileStore>>,
+  profiles: Vec<String>,
+) -> Result<()> {
+  trace!("Command profile_reorder called with profiles {profiles:?}");
+  let store = state.lock().await;
+
+  store.reorder_profiles(&profiles).await.log()?;
+  store.update_data(UpdateType::Profiles);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn profile_set_tags(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  tags: Vec<String>,
+) -> Result<()> {
+  trace!("Command profile_set_tags called with profile {profile} tags {tags:?}");
+  let store = state.lock().await;
+
+  let mut profile = store.profile(profile).await.log()?;
+  profile.tags = tags;
+  profile.tags.sort();
+  profile.tags.dedup();
+  profile.update(store.data_dir()).await.log()?;
+  store.update_data(UpdateType::Profiles);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn profile_set_pinned(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  pinned: bool,
+) -> Result<()> {
+  trace!("Command profile_set_pinned called with profile {profile} pinned {pinned}");
+  let store = state.lock().await;
+
+  let mut profile = store.profile(profile).await.log()?;
+  profile.pinned = pinned;
+  profile.update(store.data_dir()).await.log()?;
+  store.update_data(UpdateType::Profiles);
+
+  Ok(())
+}
@@ -0,0 +1,49 @@
+use std::path::PathBuf;
+
+use log::trace;
+use tauri::{Result, State};
+use tokio::sync::Mutex;
+
+use crate::{
+  profiles::{
+    import::{ImportCandidate, ImportSource},
+    store::ProfileStore,
+  },
+  utils::{log::ResultLogExt, updater::UpdateType},
+  versions::store::McVersionStore,
+};
+
+#[tauri::command]
+pub async fn profile_import_scan(
+  versions: State<'_, Mutex<McVersionStore>>,
+  source: ImportSource,
+  path: PathBuf,
+) -> Result<Vec<ImportCandidate>> {
+  trace!(
+    "Command profile_import_scan called with source {source:?} path {}",
+    path.display()
+  );
+  let latest = versions.lock().await.latest().clone();
+
+  Ok(source.scan(&path, &latest).await.log()?)
+}
+
+#[tauri::command]
+pub async fn profile_import(
+  state: State<'_, Mutex<ProfileStore>>,
+  candidates: Vec<ImportCandidate>,
+  link: bool,
+) -> Result<()> {
+  trace!(
+    "Command profile_import called with {} candidates link {link}",
+    candidates.len()
+  );
+  let mut store = state.lock().await;
+
+  let res = store.import_profiles(&candidates, link).await;
+  // some profiles may have been imported before the error
+  store.update_data(UpdateType::Profiles);
+  res.log()?;
+
+  Ok(())
+}
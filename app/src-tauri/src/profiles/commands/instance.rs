@@ -3,7 +3,7 @@ use tauri::{Result, State};
 use tokio::sync::Mutex;
 
 use crate::{
-  profiles::{instance::InstanceInfo, store::ProfileStore},
+  profiles::{instance::InstanceInfo, stats::InstanceSample, store::ProfileStore},
   utils::log::ResultLogExt,
 };
 
@@ -26,6 +26,18 @@ pub async fn instance_logs(
   Ok(lines)
 }
 
+#[tauri::command]
+pub async fn instance_stats(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  id: &str,
+) -> Result<Vec<InstanceSample>> {
+  trace!("Command instance_stats called with profile {profile} id {id}");
+  let store = state.lock().await;
+  let stats = store.get_instance_stats(profile, id).await.log()?;
+  Ok(stats)
+}
+
 #[tauri::command]
 pub async fn instance_stop(
   state: State<'_, Mutex<ProfileStore>>,
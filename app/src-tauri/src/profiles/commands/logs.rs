@@ -4,7 +4,7 @@ use tauri::{Result, State};
 use tokio::sync::Mutex;
 
 use crate::{
-  profiles::store::ProfileStore,
+  profiles::{crash::CrashInfo, sessions::ProfileStats, stats::RunSummary, store::ProfileStore},
   utils::{log::ResultLogExt, updater::UpdateType},
 };
 
@@ -47,3 +47,41 @@ pub async fn profile_logs(
   let info = store.profile_info(profile).log()?;
   Ok(info.logs(store.data_dir(), timestamp).await.log()?)
 }
+
+#[tauri::command]
+pub async fn profile_crash_info(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  timestamp: DateTime<Utc>,
+) -> Result<Option<CrashInfo>> {
+  trace!("Command profile_crash_info called with profile {profile} timestamp {timestamp}");
+  let store = state.lock().await;
+
+  let info = store.profile_info(profile).log()?;
+  Ok(info.crash_info(store.data_dir(), timestamp).await.log()?)
+}
+
+#[tauri::command]
+pub async fn profile_run_summary(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  timestamp: DateTime<Utc>,
+) -> Result<Option<RunSummary>> {
+  trace!("Command profile_run_summary called with profile {profile} timestamp {timestamp}");
+  let store = state.lock().await;
+
+  let info = store.profile_info(profile).log()?;
+  Ok(info.run_summary(store.data_dir(), timestamp).await.log()?)
+}
+
+#[tauri::command]
+pub async fn profile_stats(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<ProfileStats> {
+  trace!("Command profile_stats called with profile {profile}");
+  let store = state.lock().await;
+
+  let info = store.profile_info(profile).log()?;
+  Ok(info.stats(store.data_dir()).await.log()?)
+}
@@ -1,11 +1,25 @@
+mod backup;
+mod groups;
 mod home;
+mod import;
 mod instance;
 mod logs;
+mod mods;
+mod packs;
 mod profile;
 mod quick_play;
+mod servers;
+mod worlds;
 
+pub use backup::*;
+pub use groups::*;
 pub use home::*;
+pub use import::*;
 pub use instance::*;
 pub use logs::*;
+pub use mods::*;
+pub use packs::*;
 pub use profile::*;
 pub use quick_play::*;
+pub use servers::*;
+pub use worlds::*;
@@ -0,0 +1,191 @@
+use log::trace;
+use tauri::{Result, State};
+use tokio::sync::Mutex;
+
+use crate::{
+  profiles::{
+    bisect::{Bisect, bisect_get, bisect_report, bisect_start, bisect_stop},
+    mod_check::{ModReport, check_mods},
+    mod_updates::{ModUpdate, ModUpdateTarget, apply_mod_updates, check_mod_updates},
+    mods::{ModInfo, scan_mods, set_mods_enabled},
+    store::ProfileStore,
+  },
+  utils::{
+    log::ResultLogExt,
+    updater::{UpdateType, update_data},
+  },
+  versions::store::McVersionStore,
+};
+
+#[tauri::command]
+pub async fn mod_list(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<Vec<ModInfo>> {
+  trace!("Command mod_list called with profile {profile}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let loader = store.profile(profile).await.log()?.loader;
+  drop(store);
+
+  Ok(scan_mods(&path, loader).await.log()?)
+}
+
+#[tauri::command]
+pub async fn mod_check(
+  state: State<'_, Mutex<ProfileStore>>,
+  versions: State<'_, Mutex<McVersionStore>>,
+  profile: &str,
+) -> Result<ModReport> {
+  trace!("Command mod_check called with profile {profile}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let data_dir = store.data_dir().clone();
+  let profile = store.profile(profile).await.log()?;
+  drop(store);
+
+  let game_version = versions
+    .lock()
+    .await
+    .mod_version(&data_dir, &profile.version)
+    .await;
+  let mods = scan_mods(&path, profile.loader).await.log()?;
+  Ok(check_mods(&profile, &mods, game_version.as_deref()))
+}
+
+#[tauri::command]
+pub async fn profile_mods_check_updates(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<Vec<ModUpdate>> {
+  trace!("Command profile_mods_check_updates called with profile {profile}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let profile = store.profile(profile).await.log()?;
+  let client = store.client().clone();
+  drop(store);
+
+  Ok(check_mod_updates(&client, &path, &profile).await.log()?)
+}
+
+#[tauri::command]
+pub async fn profile_mods_update(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  updates: Vec<ModUpdateTarget>,
+) -> Result<()> {
+  trace!("Command profile_mods_update called with profile {profile} updates {updates:?}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let client = store.client().clone();
+  let handle = store.handle().clone();
+  drop(store);
+
+  let result = apply_mod_updates(&client, &path, &updates).await.log();
+  // a failed batch is rolled back, but the list may still have changed
+  update_data(&handle, UpdateType::ProfileMods);
+
+  Ok(result?)
+}
+
+#[tauri::command]
+pub async fn profile_mods_set_pinned(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  file: String,
+  pinned: bool,
+) -> Result<()> {
+  trace!(
+    "Command profile_mods_set_pinned called with profile {profile} file {file} pinned {pinned}"
+  );
+  let store = state.lock().await;
+
+  let mut profile = store.profile(profile).await.log()?;
+  profile.pinned_mods.retain(|f| f != &file);
+  if pinned {
+    profile.pinned_mods.push(file);
+    profile.pinned_mods.sort();
+  }
+  profile.update(store.data_dir()).await.log()?;
+  store.update_data(UpdateType::Profiles);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn mod_set_enabled(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  files: Vec<String>,
+  enabled: bool,
+) -> Result<Vec<String>> {
+  trace!("Command mod_set_enabled called with profile {profile} files {files:?} enabled {enabled}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let renamed = set_mods_enabled(&path, &files, enabled).await;
+  store.update_data(UpdateType::ProfileMods);
+
+  Ok(renamed.log()?)
+}
+
+#[tauri::command]
+pub async fn mod_bisect_get(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<Option<Bisect>> {
+  trace!("Command mod_bisect_get called with profile {profile}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  Ok(bisect_get(&path).await.log()?)
+}
+
+#[tauri::command]
+pub async fn mod_bisect_start(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<Bisect> {
+  trace!("Command mod_bisect_start called with profile {profile}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let profile = store.profile(profile).await.log()?;
+  let mods = scan_mods(&path, profile.loader).await.log()?;
+  let bisect = bisect_start(&path, &mods).await.log()?;
+  store.update_data(UpdateType::ProfileMods);
+
+  Ok(bisect)
+}
+
+#[tauri::command]
+pub async fn mod_bisect_report(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  crashed: Option<bool>,
+) -> Result<Bisect> {
+  trace!("Command mod_bisect_report called with profile {profile} crashed {crashed:?}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let bisect = bisect_report(&path, crashed).await.log()?;
+  store.update_data(UpdateType::ProfileMods);
+
+  Ok(bisect)
+}
+
+#[tauri::command]
+pub async fn mod_bisect_stop(state: State<'_, Mutex<ProfileStore>>, profile: &str) -> Result<()> {
+  trace!("Command mod_bisect_stop called with profile {profile}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  bisect_stop(&path).await.log()?;
+  store.update_data(UpdateType::ProfileMods);
+
+  Ok(())
+}
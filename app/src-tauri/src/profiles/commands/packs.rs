@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use log::trace;
+use tauri::{Result, State};
+use tokio::sync::Mutex;
+
+use crate::{
+  profiles::{
+    packs::{
+      PackInfo, PackType, import_pack, install_pack, list_packs, remove_pack, set_pack_enabled,
+    },
+    store::ProfileStore,
+  },
+  utils::{
+    log::ResultLogExt,
+    updater::{UpdateType, update_data},
+  },
+  versions::paths::MCVersionPath,
+};
+
+#[tauri::command]
+pub async fn pack_list(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  r#type: PackType,
+) -> Result<Vec<PackInfo>> {
+  trace!("Command pack_list called with profile {profile} type {type:?}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let version = store.profile(profile).await.log()?.version;
+  let version_path = MCVersionPath::new(store.data_dir(), &version);
+
+  Ok(list_packs(&path, r#type, &version_path).await.log()?)
+}
+
+#[tauri::command]
+pub async fn pack_set_enabled(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  r#type: PackType,
+  pack: &str,
+  enabled: bool,
+) -> Result<()> {
+  trace!(
+    "Command pack_set_enabled called with profile {profile} type {type:?} pack {pack} enabled {enabled}"
+  );
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let version = store.profile(profile).await.log()?.version;
+  let version_path = MCVersionPath::new(store.data_dir(), &version);
+  set_pack_enabled(&path, r#type, pack, enabled, &version_path)
+    .await
+    .log()?;
+  store.update_data(UpdateType::ProfilePacks);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn pack_import(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  r#type: PackType,
+  source: PathBuf,
+) -> Result<String> {
+  trace!(
+    "Command pack_import called with profile {profile} type {type:?} source {}",
+    source.display()
+  );
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let id = import_pack(&path, r#type, &source).await.log()?;
+  store.update_data(UpdateType::ProfilePacks);
+
+  Ok(id)
+}
+
+#[tauri::command]
+pub async fn pack_install(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  r#type: PackType,
+  project: &str,
+  version: Option<String>,
+) -> Result<String> {
+  trace!(
+    "Command pack_install called with profile {profile} type {type:?} project {project} version {version:?}"
+  );
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let game_version = store.profile(profile).await.log()?.version;
+  let client = store.client().clone();
+  let handle = store.handle().clone();
+  drop(store);
+
+  let id = install_pack(
+    &client,
+    &path,
+    r#type,
+    project,
+    version.as_deref(),
+    &game_version,
+  )
+  .await
+  .log()?;
+  update_data(&handle, UpdateType::ProfilePacks);
+
+  Ok(id)
+}
+
+#[tauri::command]
+pub async fn pack_remove(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  r#type: PackType,
+  pack: &str,
+) -> Result<()> {
+  trace!("Command pack_remove called with profile {profile} type {type:?} pack {pack}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let version = store.profile(profile).await.log()?.version;
+  let version_path = MCVersionPath::new(store.data_dir(), &version);
+  remove_pack(&path, r#type, pack, &version_path)
+    .await
+    .log()?;
+  store.update_data(UpdateType::ProfilePacks);
+
+  Ok(())
+}
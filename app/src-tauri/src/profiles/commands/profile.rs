@@ -1,6 +1,6 @@
 use base64::prelude::*;
 use chrono::Utc;
-use log::trace;
+use log::{debug, trace};
 use tauri::{AppHandle, Result, State};
 use tauri_plugin_opener::OpenerExt;
 use thiserror::Error;
@@ -9,12 +9,21 @@ use tokio::sync::Mutex;
 use crate::{
   account::store::AccountStore,
   offline::OfflineResultExt,
+  path,
   profiles::{
-    config::{Profile, ProfileUpdate, QuickPlayInfo},
+    SAVES_DIR,
+    backup::{BackupReason, create_backup, create_backup_of},
+    config::{
+      DuplicateOptions, Profile, ProfileFilter, ProfileSort, ProfileUpdate, QuickPlayInfo,
+      QuickPlayType,
+    },
+    mod_check::{ModCheckError, check_mods},
+    mods::{ModInfo, scan_mods},
     store::ProfileStore,
+    worlds::{WorldError, newer_world},
   },
   utils::{log::ResultLogExt, updater::UpdateType},
-  versions::{loader::LoaderType, store::McVersionStore},
+  versions::{loader::LoaderType, paths::MCVersionPath, store::McVersionStore},
 };
 
 #[derive(Error, Debug)]
@@ -46,6 +55,28 @@ pub async fn profile_create(
   Ok(())
 }
 
+#[tauri::command]
+pub async fn profile_duplicate(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  name: String,
+  options: DuplicateOptions,
+) -> Result<()> {
+  trace!(
+    "Command profile_duplicate called with profile {profile} name {name} options {:?}",
+    options
+  );
+  let mut store = state.lock().await;
+
+  store
+    .duplicate_profile(profile, name, &options)
+    .await
+    .log()?;
+  store.update_data(UpdateType::Profiles);
+
+  Ok(())
+}
+
 #[tauri::command]
 pub async fn profile_update(
   state: State<'_, Mutex<ProfileStore>>,
@@ -68,6 +99,7 @@ pub async fn profile_update(
   current_profile.game = profile.game;
   current_profile.use_local_jvm = profile.use_local_jvm;
   current_profile.jvm = profile.jvm;
+  current_profile.auto_update_loader = profile.auto_update_loader;
 
   current_profile.update(store.data_dir()).await.log()?;
   store.update_data(UpdateType::Profiles);
@@ -75,6 +107,63 @@ pub async fn profile_update(
   Ok(())
 }
 
+#[tauri::command]
+pub async fn profile_switch_loader_check(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  loader: LoaderType,
+) -> Result<Vec<ModInfo>> {
+  trace!("Command profile_switch_loader_check called with profile {profile} loader {loader:?}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  drop(store);
+
+  // mods that the new loader would skip
+  let mods = scan_mods(&path, loader).await.log()?;
+  Ok(
+    mods
+      .into_iter()
+      .filter(|m| m.enabled && m.loader_mismatch)
+      .collect(),
+  )
+}
+
+#[tauri::command]
+pub async fn profile_switch_loader(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  loader: LoaderType,
+  loader_version: Option<String>,
+) -> Result<()> {
+  trace!(
+    "Command profile_switch_loader called with profile {profile} loader {loader:?} loader_version {loader_version:?}"
+  );
+  let store = state.lock().await;
+
+  let mut profile = store.profile(profile).await.log()?;
+  profile
+    .switch_loader(store.data_dir(), loader, loader_version)
+    .await
+    .log()?;
+  profile.update(store.data_dir()).await.log()?;
+  store.update_data(UpdateType::Profiles);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn profile_loader_update_check(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<Option<String>> {
+  trace!("Command profile_loader_update_check called with profile {profile}");
+  let store = state.lock().await;
+
+  let profile = store.profile(profile).await.log()?;
+  Ok(profile.loader_update(store.data_dir()).await.log()?)
+}
+
 #[tauri::command]
 pub async fn profile_get_icon(
   state: State<'_, Mutex<ProfileStore>>,
@@ -137,13 +226,23 @@ pub async fn profile_remove(state: State<'_, Mutex<ProfileStore>>, profile: &str
 }
 
 #[tauri::command]
-pub async fn profile_list(state: State<'_, Mutex<ProfileStore>>) -> Result<Vec<Profile>> {
-  trace!("Command profile_list called");
+pub async fn profile_list(
+  state: State<'_, Mutex<ProfileStore>>,
+  filter: Option<ProfileFilter>,
+  sort: Option<ProfileSort>,
+) -> Result<Vec<Profile>> {
+  trace!("Command profile_list called with filter {filter:?} sort {sort:?}");
   let store = state.lock().await;
-  Ok(store.list_profiles().await.log()?)
+  Ok(
+    store
+      .list_profiles(&filter.unwrap_or_default(), &sort.unwrap_or_default())
+      .await
+      .log()?,
+  )
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn profile_launch(
   state: State<'_, Mutex<ProfileStore>>,
   versions: State<'_, Mutex<McVersionStore>>,
@@ -151,9 +250,18 @@ pub async fn profile_launch(
   profile: &str,
   id: usize,
   quick_play: Option<QuickPlayInfo>,
+  // launch even if the world was saved by a newer version
+  force: Option<bool>,
+  // back up the world before it is opened in an older version
+  backup: Option<bool>,
+  // launch even if the mods have missing dependencies or conflicts
+  skip_mod_check: Option<bool>,
 ) -> Result<()> {
-  trace!("Command profile_launch called with profile {profile} id {id}");
+  trace!(
+    "Command profile_launch called with profile {profile} id {id} force {force:?} backup {backup:?} skip_mod_check {skip_mod_check:?}"
+  );
   let store = state.lock().await;
+  let data_dir = store.data_dir().clone();
   // clone so the lock is dropped before the download
   let mc_store = versions.lock().await.clone();
   let auth_store = auth.lock().await;
@@ -167,6 +275,19 @@ pub async fn profile_launch(
   let mut profile = store.profile(profile).await.log()?;
   drop(store);
 
+  let mut changed = false;
+  if profile.auto_update_loader
+    && let Ok(Some(loader_version)) = profile.loader_update(&data_dir).await.log()
+  {
+    debug!(
+      "Updating loader of profile {} to {loader_version}",
+      profile.id
+    );
+    profile.loader_version = Some(loader_version);
+    profile.downloaded = false;
+    changed = true;
+  }
+
   if !profile.downloaded {
     if !mc_store
       .check_or_download(
@@ -182,6 +303,7 @@ pub async fn profile_launch(
       return Ok(());
     }
     profile.downloaded = true;
+    changed = true;
   } else if !mc_store.check_meta(&profile.version, id).await.log()?
     && !mc_store
       .check_or_download(
@@ -197,6 +319,56 @@ pub async fn profile_launch(
     return Ok(());
   }
 
+  // save the download and loader update so they are not redone if a check below stops the launch
+  if changed {
+    profile.update(&data_dir).await.log()?;
+    state.lock().await.update_data(UpdateType::Profiles);
+  }
+
+  if let Some(quick_play) = &quick_play
+    && quick_play.r#type == QuickPlayType::Singleplayer
+  {
+    if !force.unwrap_or_default() {
+      if newer_world(
+        &path!(&data_dir, profile.relative_to_data()),
+        &quick_play.id,
+        &MCVersionPath::new(&data_dir, &profile.version),
+      )
+      .await
+      .is_some()
+      {
+        let err: anyhow::Result<()> = Err(WorldError::WorldNewer.into()).log();
+        return Ok(err?);
+      }
+    } else if backup.unwrap_or_default() {
+      create_backup_of(
+        &data_dir,
+        &profile,
+        BackupReason::Downgrade,
+        &[format!("{SAVES_DIR}/{}", quick_play.id)],
+      )
+      .await
+      .log()?;
+      state.lock().await.update_data(UpdateType::ProfileBackups);
+    }
+  }
+
+  if profile.loader != LoaderType::Vanilla && !skip_mod_check.unwrap_or_default() {
+    let mods = scan_mods(
+      &path!(&data_dir, profile.relative_to_data()),
+      profile.loader,
+    )
+    .await
+    .log()?;
+    let game_version = mc_store.mod_version(&data_dir, &profile.version).await;
+    let report = check_mods(&profile, &mods, game_version.as_deref());
+    if report.has_errors {
+      debug!("Mod check failed for {}: {:?}", profile.id, report.issues);
+      let err: anyhow::Result<()> = Err(ModCheckError::Failed.into()).log();
+      return Ok(err?);
+    }
+  }
+
   profile.last_played = Some(Utc::now());
   if let Some(quick_play) = &quick_play {
     if let Some(item) = profile
@@ -211,6 +383,16 @@ pub async fn profile_launch(
     profile.last_played_non_quick_play = Some(Utc::now());
   }
 
+  // zip before taking the lock so other profile commands are not blocked by the backup
+  if profile.backup.before_launch
+    && create_backup(&data_dir, &profile, BackupReason::Launch)
+      .await
+      .log()
+      .is_ok()
+  {
+    state.lock().await.update_data(UpdateType::ProfileBackups);
+  }
+
   let mut store = state.lock().await;
   profile.update(store.data_dir()).await.log()?;
   store.update_data(UpdateType::Profiles);
@@ -0,0 +1,99 @@
+use log::trace;
+use tauri::{Result, State};
+use tokio::sync::Mutex;
+
+use crate::{
+  profiles::{
+    servers::{ServerEntry, add_server, list_servers, move_server, remove_server, update_server},
+    store::ProfileStore,
+  },
+  utils::{
+    log::ResultLogExt,
+    ping::{ServerStatus, ping_server},
+    updater::UpdateType,
+  },
+};
+
+#[tauri::command]
+pub async fn server_list(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<Vec<ServerEntry>> {
+  trace!("Command server_list called with profile {profile}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  Ok(list_servers(&path).await.log()?)
+}
+
+#[tauri::command]
+pub async fn server_add(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  server: ServerEntry,
+) -> Result<()> {
+  trace!("Command server_add called with profile {profile} server {server:?}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  add_server(&path, &server).await.log()?;
+  store.update_data(UpdateType::ProfileServers);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn server_update(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  index: usize,
+  server: ServerEntry,
+) -> Result<()> {
+  trace!("Command server_update called with profile {profile} index {index} server {server:?}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  update_server(&path, index, &server).await.log()?;
+  store.update_data(UpdateType::ProfileServers);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn server_move(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  from: usize,
+  to: usize,
+) -> Result<()> {
+  trace!("Command server_move called with profile {profile} from {from} to {to}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  move_server(&path, from, to).await.log()?;
+  store.update_data(UpdateType::ProfileServers);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn server_remove(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  index: usize,
+) -> Result<()> {
+  trace!("Command server_remove called with profile {profile} index {index}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  remove_server(&path, index).await.log()?;
+  store.update_data(UpdateType::ProfileServers);
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn server_ping(address: &str) -> Result<ServerStatus> {
+  trace!("Command server_ping called with address {address}");
+  Ok(ping_server(address).await.log()?)
+}
@@ -0,0 +1,129 @@
+use std::path::PathBuf;
+
+use log::trace;
+use tauri::{Result, State};
+use tokio::sync::Mutex;
+
+use crate::{
+  profiles::{
+    store::ProfileStore,
+    worlds::{
+      NewerWorld, WorldInfo, copy_world, export_world, import_world, list_worlds, newer_world,
+      world_info,
+    },
+  },
+  utils::{log::ResultLogExt, updater::UpdateType},
+  versions::paths::MCVersionPath,
+};
+
+#[tauri::command]
+pub async fn world_list(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+) -> Result<Vec<WorldInfo>> {
+  trace!("Command world_list called with profile {profile}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  Ok(list_worlds(&path).await.log()?)
+}
+
+#[tauri::command]
+pub async fn world_info_get(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  world: &str,
+) -> Result<WorldInfo> {
+  trace!("Command world_info_get called with profile {profile} world {world}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  Ok(world_info(&path, world).await.log()?)
+}
+
+#[tauri::command]
+pub async fn world_newer_get(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  world: &str,
+) -> Result<Option<NewerWorld>> {
+  trace!("Command world_newer_get called with profile {profile} world {world}");
+  let store = state.lock().await;
+
+  let profile = store.profile(profile).await.log()?;
+  let path = store.get_profile_path(&profile.id).log()?;
+  let version_path = MCVersionPath::new(store.data_dir(), &profile.version);
+  Ok(newer_world(&path, world, &version_path).await)
+}
+
+#[tauri::command]
+pub async fn world_export(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  world: &str,
+  target: PathBuf,
+) -> Result<()> {
+  trace!(
+    "Command world_export called with profile {profile} world {world} target {}",
+    target.display()
+  );
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  export_world(&path, world, &target).await.log()?;
+
+  Ok(())
+}
+
+#[tauri::command]
+pub async fn world_import(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  source: PathBuf,
+) -> Result<String> {
+  trace!(
+    "Command world_import called with profile {profile} source {}",
+    source.display()
+  );
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let id = import_world(&path, &source).await.log()?;
+  store.update_data(UpdateType::ProfileWorlds);
+
+  Ok(id)
+}
+
+#[tauri::command]
+pub async fn world_copy(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  world: &str,
+  target: &str,
+) -> Result<String> {
+  trace!("Command world_copy called with profile {profile} world {world} target {target}");
+  let store = state.lock().await;
+
+  let path = store.get_profile_path(profile).log()?;
+  let target_path = store.get_profile_path(target).log()?;
+  let id = copy_world(&path, world, &target_path).await.log()?;
+  store.update_data(UpdateType::ProfileWorlds);
+
+  Ok(id)
+}
+
+#[tauri::command]
+pub async fn world_remove(
+  state: State<'_, Mutex<ProfileStore>>,
+  profile: &str,
+  world: &str,
+) -> Result<()> {
+  trace!("Command world_remove called with profile {profile} world {world}");
+  let store = state.lock().await;
+
+  store.remove_world(profile, world).await.log()?;
+  store.update_data(UpdateType::ProfileWorlds);
+  store.update_data(UpdateType::ProfileQuickPlay);
+
+  Ok(())
+}
@@ -5,7 +5,10 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::sync::Notify;
 
-use crate::versions::{launch::QuickPlay, loader::LoaderType};
+use crate::{
+  profiles::backup::BackupSettings,
+  versions::{launch::QuickPlay, loader::LoaderType},
+};
 
 #[derive(Clone)]
 pub struct ProfileInfo {
@@ -33,6 +36,50 @@ pub struct Profile {
   pub game: Option<GameSettings>,
   pub use_local_jvm: bool,
   pub jvm: Option<JvmSettings>,
+  #[serde(default)]
+  pub group: Option<String>,
+  #[serde(default)]
+  pub tags: Vec<String>,
+  #[serde(default)]
+  pub sort_index: usize,
+  #[serde(default)]
+  pub pinned: bool,
+  #[serde(default)]
+  pub backup: BackupSettings,
+  // jar names of mods that are never updated
+  #[serde(default)]
+  pub pinned_mods: Vec<String>,
+  // switch to newer stable loader versions on launch
+  #[serde(default)]
+  pub auto_update_loader: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProfileFilter {
+  pub group: Option<String>,
+  // profiles have to contain all of these tags
+  #[serde(default)]
+  pub tags: Vec<String>,
+  pub search: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileSortKey {
+  #[default]
+  Manual,
+  Name,
+  LastPlayed,
+  Created,
+  Version,
+  Loader,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct ProfileSort {
+  pub key: ProfileSortKey,
+  #[serde(default)]
+  pub descending: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -60,7 +107,7 @@ impl PartialEq for QuickPlayInfo {
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum QuickPlayType {
   Singleplayer,
@@ -89,6 +136,16 @@ pub struct ProfileUpdate {
   pub game: Option<GameSettings>,
   pub use_local_jvm: bool,
   pub jvm: Option<JvmSettings>,
+  #[serde(default)]
+  pub auto_update_loader: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DuplicateOptions {
+  pub saves: bool,
+  pub screenshots: bool,
+  pub logs: bool,
+  pub mods: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -132,4 +189,8 @@ pub enum ProfileError {
   NotFound,
   #[error("InvalidImage")]
   InvalidImage,
+  #[error("UnsupportedVersion")]
+  UnsupportedVersion,
+  #[error("InvalidLoaderVersion")]
+  InvalidLoaderVersion,
 }
@@ -0,0 +1,286 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::fs;
+
+use crate::{
+  path,
+  profiles::{
+    CRASH_REPORTS_DIR, PROFILE_DIR,
+    config::ProfileInfo,
+    profile::{crash_file, run_log_file},
+  },
+  utils::file::{read_parse_file, write_file},
+};
+
+const CRASH_REPORT_PREFIX: &str = "crash-";
+const CRASH_REPORT_SUFFIX: &str = ".txt";
+const JVM_CRASH_PREFIX: &str = "hs_err_pid";
+const JVM_CRASH_SUFFIX: &str = ".log";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrashCause {
+  WrongJavaVersion,
+  MissingModDependency,
+  MixinFailure,
+  OutOfMemory,
+  GlDriver,
+  Unknown,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CrashInfo {
+  pub profile_name: String,
+  pub profile_id: String,
+  pub launched_at: DateTime<Utc>,
+  pub exit_code: Option<i32>,
+  pub cause: CrashCause,
+  pub log_file: Option<PathBuf>,
+  pub crash_report: Option<PathBuf>,
+  pub jvm_crash_log: Option<PathBuf>,
+}
+
+/// The rules are checked in order so more specific causes have to come first
+const RULES: &[(CrashCause, &[&str])] = &[
+  (
+    CrashCause::WrongJavaVersion,
+    &[
+      "java.lang.UnsupportedClassVersionError",
+      "has been compiled by a more recent version of the Java Runtime",
+      "Unsupported class file major version",
+      // fabric names the running jvm as the mod with the id java in its dependency errors
+      "' (java) ",
+      "' (java), ",
+    ],
+  ),
+  (
+    CrashCause::MissingModDependency,
+    &[
+      "Incompatible mods found!",
+      "Incompatible mod set!",
+      "Missing or unsupported mandatory dependencies",
+      "net.fabricmc.loader.impl.FormattedException: Mod resolution encountered",
+      "Mod resolution failed",
+      "which is missing!",
+      "ModResolutionException",
+    ],
+  ),
+  (
+    CrashCause::MixinFailure,
+    &[
+      "MixinApplyError",
+      "Mixin apply failed",
+      "Mixin apply for mod",
+      "InvalidInjectionException",
+      "MixinTransformerError",
+      "InvalidMixinException",
+    ],
+  ),
+  (CrashCause::OutOfMemory, &["java.lang.OutOfMemoryError"]),
+  (
+    CrashCause::GlDriver,
+    &[
+      "GLFW error 65542",
+      "GLFW error 65543",
+      "Pixel format not accelerated",
+      "The driver does not appear to support OpenGL",
+      "Could not create context",
+      "atio6axx",
+      "atioglxx",
+      "nvoglv",
+      "ig9icd",
+      "ig75icd",
+      "ig7icd",
+      "libnvidia-glcore",
+    ],
+  ),
+];
+
+impl CrashInfo {
+  /// Collects the crash files written during the run and tries to determine the cause
+  pub async fn analyze(
+    handle: &AppHandle,
+    profile_name: String,
+    profile_id: String,
+    launched_at: DateTime<Utc>,
+    exit_code: Option<i32>,
+    lines: &[String],
+  ) -> Result<Self> {
+    let game_dir = path!(handle.path().app_data_dir()?, PROFILE_DIR, &profile_id);
+
+    let crash_report = newest_file_since(
+      &path!(&game_dir, CRASH_REPORTS_DIR),
+      CRASH_REPORT_PREFIX,
+      CRASH_REPORT_SUFFIX,
+      launched_at,
+    )
+    .await;
+    let jvm_crash_log =
+      newest_file_since(&game_dir, JVM_CRASH_PREFIX, JVM_CRASH_SUFFIX, launched_at).await;
+
+    let mut sources = Vec::new();
+    for file in [&crash_report, &jvm_crash_log].into_iter().flatten() {
+      if let Ok(content) = fs::read(file).await {
+        sources.push(String::from_utf8_lossy(&content).into_owned());
+      }
+    }
+    sources.push(lines.join("\n"));
+
+    let cause = detect_cause(&sources);
+    debug!("Detected crash cause {cause:?} for profile {profile_id}");
+
+    let log_file = ProfileInfo::log_dir(handle, &profile_id)
+      .ok()
+      .map(|dir| run_log_file(&dir, launched_at))
+      .filter(|file| file.exists());
+
+    Ok(CrashInfo {
+      profile_name,
+      profile_id,
+      launched_at,
+      exit_code,
+      cause,
+      log_file,
+      crash_report,
+      jvm_crash_log,
+    })
+  }
+
+  /// Stores the crash info next to the run log so it can be looked up later
+  pub async fn save(&self, handle: &AppHandle) -> Result<()> {
+    let log_dir = ProfileInfo::log_dir(handle, &self.profile_id)?;
+    fs::create_dir_all(&log_dir).await?;
+    write_file(&crash_file(&log_dir, self.launched_at), self).await
+  }
+
+  pub async fn load(log_dir: &Path, launched_at: DateTime<Utc>) -> Result<Option<Self>> {
+    let path = crash_file(log_dir, launched_at);
+    if !path.exists() {
+      return Ok(None);
+    }
+
+    Ok(Some(read_parse_file(&path).await?))
+  }
+}
+
+fn detect_cause(sources: &[String]) -> CrashCause {
+  for (cause, patterns) in RULES {
+    if sources
+      .iter()
+      .any(|source| patterns.iter().any(|pattern| source.contains(pattern)))
+    {
+      return *cause;
+    }
+  }
+
+  CrashCause::Unknown
+}
+
+async fn newest_file_since(
+  dir: &Path,
+  prefix: &str,
+  suffix: &str,
+  since: DateTime<Utc>,
+) -> Option<PathBuf> {
+  let mut stream = fs::read_dir(dir).await.ok()?;
+  let mut newest: Option<(DateTime<Utc>, PathBuf)> = None;
+
+  while let Ok(Some(entry)) = stream.next_entry().await {
+    let Some(name) = entry.file_name().to_str().map(String::from) else {
+      continue;
+    };
+    if !name.starts_with(prefix) || !name.ends_with(suffix) {
+      continue;
+    }
+
+    let Ok(modified) = entry.metadata().await.and_then(|m| m.modified()) else {
+      continue;
+    };
+    let modified: DateTime<Utc> = modified.into();
+    if modified < since {
+      continue;
+    }
+
+    if newest.as_ref().is_none_or(|(time, _)| modified > *time) {
+      newest = Some((modified, entry.path()));
+    }
+  }
+
+  newest.map(|(_, path)| path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn detects_causes() {
+    let cases = [
+      (
+        "Exception in thread \"main\" java.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main has been compiled by a more recent version of the Java Runtime (class file version 65.0), this version of the Java Runtime only recognizes class file versions up to 61.0",
+        CrashCause::WrongJavaVersion,
+      ),
+      (
+        "Caused by: java.lang.IllegalArgumentException: Unsupported class file major version 65",
+        CrashCause::WrongJavaVersion,
+      ),
+      (
+        "net.fabricmc.loader.impl.FormattedException: Mod resolution encountered an incompatible mod set!\nA potential solution has been determined:\n\t - Replace 'OpenJDK 64-Bit Server VM' (java) 17 with version 21 or later.\nUnmet dependency listing:\n\t - Mod 'Sodium' (sodium) 0.5.8 requires version 21 or later of 'OpenJDK 64-Bit Server VM' (java), but only the wrong version is present: 17!",
+        CrashCause::WrongJavaVersion,
+      ),
+      (
+        "net.fabricmc.loader.impl.FormattedException: Mod resolution encountered an incompatible mod set!\nA potential solution has been determined:\n\t - Install fabric-api, any version.\nUnmet dependency listing:\n\t - Mod 'Mod Menu' (modmenu) 7.2.2 requires any version of fabric-api, which is missing!",
+        CrashCause::MissingModDependency,
+      ),
+      (
+        "Missing or unsupported mandatory dependencies:\n\tMod ID: 'geckolib', Requested by: 'mowziesmobs', Expected range: '[4.2,)', Actual version: '[MISSING]'",
+        CrashCause::MissingModDependency,
+      ),
+      (
+        "org.spongepowered.asm.mixin.transformer.throwables.MixinTransformerError: An unexpected critical error was encountered\nCaused by: org.spongepowered.asm.mixin.injection.throwables.InvalidInjectionException: Critical injection failure: @Inject annotation on render could not find any targets matching 'Lnet/minecraft/class_757;method_3192(FJZ)V'",
+        CrashCause::MixinFailure,
+      ),
+      (
+        "org.spongepowered.asm.mixin.throwables.MixinApplyError: Mixin [sodium.mixins.json:core.MixinMinecraftClient] from phase [DEFAULT] in config [sodium.mixins.json] FAILED during APPLY",
+        CrashCause::MixinFailure,
+      ),
+      (
+        "java.lang.OutOfMemoryError: Java heap space\n\tat java.base/java.util.Arrays.copyOf(Arrays.java:3512)",
+        CrashCause::OutOfMemory,
+      ),
+      (
+        "GLFW error 65542: WGL: The driver does not appear to support OpenGL",
+        CrashCause::GlDriver,
+      ),
+      (
+        "# Problematic frame:\n# C  [atio6axx.dll+0x1a2b3c]",
+        CrashCause::GlDriver,
+      ),
+      (
+        "# Problematic frame:\n# C  [libnvidia-glcore.so.535.113.01+0xe1b4f0]",
+        CrashCause::GlDriver,
+      ),
+      // mods mentioning java in their own messages are not a java version problem
+      (
+        "[Netty Server IO #1/WARN]: Option useNativeTransport requires Java 11 or newer, falling back to NIO",
+        CrashCause::Unknown,
+      ),
+      (
+        "[main/INFO]: Loading 57 mods:\n\t- fabricloader 0.15.11\n\t- java 17\n\t- minecraft 1.20.1",
+        CrashCause::Unknown,
+      ),
+      (
+        "java.lang.NullPointerException: Cannot invoke \"net.minecraft.class_1937.method_8320(net.minecraft.class_2338)\" because \"this.field_1687\" is null",
+        CrashCause::Unknown,
+      ),
+    ];
+
+    for (log, expected) in cases {
+      assert_eq!(detect_cause(&[log.to_string()]), expected, "{log}");
+    }
+  }
+}
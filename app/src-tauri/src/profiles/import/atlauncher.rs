@@ -0,0 +1,110 @@
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::{
+  path,
+  utils::{dir::list_dirs_in_dir_path, file::read_parse_file, log::ResultLogExt},
+  versions::loader::{LoaderType, forge::neoforge_version_pair},
+};
+
+use super::{ImportCandidate, ImportSource, parse_jvm_args, read_icon};
+
+const INSTANCES_DIR: &str = "instances";
+const INSTANCE_JSON: &str = "instance.json";
+const INSTANCE_ICON: &str = "instance.png";
+
+#[derive(Deserialize, Debug)]
+struct AtInstance {
+  // the Minecraft version
+  id: String,
+  launcher: AtLauncherInfo,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct AtLauncherInfo {
+  name: String,
+  loader_version: Option<AtLoaderVersion>,
+  // in megabytes
+  maximum_memory: Option<usize>,
+  java_arguments: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AtLoaderVersion {
+  r#type: String,
+  version: String,
+}
+
+/// `path` can either be the launcher root or its instances directory
+pub async fn scan(path: &Path) -> Result<Vec<ImportCandidate>> {
+  let instances_dir = if path!(path, INSTANCES_DIR).exists() {
+    path!(path, INSTANCES_DIR)
+  } else {
+    path.to_path_buf()
+  };
+
+  let mut candidates = Vec::new();
+  for dir in list_dirs_in_dir_path(instances_dir).await? {
+    let json_path = path!(&dir, INSTANCE_JSON);
+    if !json_path.exists() {
+      continue;
+    }
+    // a single broken instance should not hide all the others
+    let Ok(instance) = read_parse_file::<AtInstance>(&json_path).await.log() else {
+      continue;
+    };
+
+    let (loader, loader_version) = match instance.launcher.loader_version {
+      Some(loader) => match loader.r#type.to_lowercase().as_str() {
+        "fabric" => (LoaderType::Fabric, Some(loader.version)),
+        "quilt" => (LoaderType::Quilt, Some(loader.version)),
+        "forge" => (
+          LoaderType::Forge,
+          // some versions are prefixed with the Minecraft version
+          Some(
+            loader
+              .version
+              .trim_start_matches(&format!("{}-", instance.id))
+              .to_string(),
+          ),
+        ),
+        "neoforge" => (
+          LoaderType::NeoForge,
+          neoforge_version_pair(&loader.version).ok().map(|(_, v)| v),
+        ),
+        _ => continue,
+      },
+      None => (LoaderType::Vanilla, None),
+    };
+
+    let jvm = (instance.launcher.maximum_memory.is_some()
+      || instance.launcher.java_arguments.is_some())
+    .then(|| {
+      parse_jvm_args(
+        instance
+          .launcher
+          .java_arguments
+          .as_deref()
+          .unwrap_or_default(),
+        instance.launcher.maximum_memory,
+      )
+    });
+
+    candidates.push(ImportCandidate {
+      source: ImportSource::AtLauncher,
+      name: instance.launcher.name,
+      icon: read_icon(&path!(&dir, INSTANCE_ICON)).await,
+      game_dir: dir,
+      version: instance.id,
+      loader,
+      loader_version,
+      jvm,
+      game: None,
+    });
+  }
+
+  Ok(candidates)
+}
@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use thiserror::Error;
+use tokio::fs;
+use uuid::Uuid;
+
+use crate::{
+  path,
+  profiles::{
+    PROFILE_CONFIG, PROFILE_DIR, PROFILE_IMAGE,
+    config::{GameSettings, JvmSettings, Profile, ProfileInfo},
+    profile::scale_icon,
+    watcher::watch_profile,
+  },
+  utils::{
+    dir::{copy_dir, link_dir},
+    file::write_file,
+  },
+  versions::{loader::LoaderType, meta::minecraft::LatestVersion},
+};
+
+mod atlauncher;
+mod multimc;
+mod vanilla;
+
+// launcher owned content that is shared between all profiles of the launcher
+const LAUNCHER_DIRS: [&str; 5] = ["versions", "libraries", "assets", "runtime", "bin"];
+const LAUNCHER_FILE_PREFIX: &str = "launcher_";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+  Vanilla,
+  MultiMc,
+  AtLauncher,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImportCandidate {
+  pub source: ImportSource,
+  pub name: String,
+  pub game_dir: PathBuf,
+  pub version: String,
+  pub loader: LoaderType,
+  pub loader_version: Option<String>,
+  pub jvm: Option<JvmSettings>,
+  pub game: Option<GameSettings>,
+  // base64 encoded png
+  pub icon: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+  #[error("NoInstancesFound")]
+  NoInstancesFound,
+}
+
+impl ImportSource {
+  /// Lists the instances of the launcher installed at `path`
+  pub async fn scan(self, path: &Path, latest: &LatestVersion) -> Result<Vec<ImportCandidate>> {
+    let candidates = match self {
+      ImportSource::Vanilla => vanilla::scan(path, latest).await?,
+      ImportSource::MultiMc => multimc::scan(path).await?,
+      ImportSource::AtLauncher => atlauncher::scan(path).await?,
+    };
+    if candidates.is_empty() {
+      return Err(ImportError::NoInstancesFound.into());
+    }
+
+    Ok(candidates)
+  }
+}
+
+impl ImportCandidate {
+  /// Creates a new profile from the candidate.
+  /// With `link` the content of the game directory is symlinked instead of copied
+  pub async fn import(
+    &self,
+    data_dir: &PathBuf,
+    app: &AppHandle,
+    link: bool,
+  ) -> Result<(String, ProfileInfo)> {
+    let id = Uuid::new_v4().to_string();
+    let relative_path = path!(PROFILE_DIR, &id);
+    let path = path!(data_dir, &relative_path);
+
+    let mut profile = Profile::new(
+      id.clone(),
+      self.name.clone(),
+      self.version.clone(),
+      self.loader,
+      self.loader_version.clone(),
+    );
+    profile.use_local_jvm = self.jvm.is_some();
+    profile.jvm = self.jvm.clone();
+    profile.use_local_game = self.game.is_some();
+    profile.game = self.game.clone();
+
+    let filter = |relative: &Path| {
+      let Some(top) = relative
+        .components()
+        .next()
+        .and_then(|c| c.as_os_str().to_str())
+      else {
+        return true;
+      };
+      top != PROFILE_CONFIG
+        && !LAUNCHER_DIRS.contains(&top)
+        && !top.starts_with(LAUNCHER_FILE_PREFIX)
+    };
+    if link {
+      link_dir(&self.game_dir, &path, filter).await?;
+    } else {
+      copy_dir(&self.game_dir, &path, filter).await?;
+    }
+
+    let stop = watch_profile(path.clone(), id.clone(), app.clone())?;
+    write_file(&path!(&path, PROFILE_CONFIG), &profile).await?;
+    if let Some(icon) = &self.icon
+      && let Ok(icon) = BASE64_STANDARD.decode(icon)
+      && let Ok(icon) = scale_icon(&icon)
+    {
+      fs::write(&path!(&path, PROFILE_IMAGE), icon).await?;
+    }
+
+    Ok((
+      id,
+      ProfileInfo {
+        path: relative_path,
+        watcher: stop,
+      },
+    ))
+  }
+}
+
+/// Splits java arguments into the max memory and the remaining arguments
+fn parse_jvm_args(args: &str, mem_max: Option<usize>) -> JvmSettings {
+  let mut settings = JvmSettings::default();
+
+  for arg in args.split_whitespace() {
+    if let Some(mem) = arg.strip_prefix("-Xmx").and_then(parse_memory) {
+      settings.mem_max = mem;
+    } else {
+      settings.args.push(arg.to_string());
+    }
+  }
+  if let Some(mem_max) = mem_max {
+    settings.mem_max = mem_max;
+  }
+
+  settings
+}
+
+/// Parses a java memory size like `4G` or `4096m` into megabytes
+fn parse_memory(value: &str) -> Option<usize> {
+  let (number, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit())?);
+  let number = number.parse::<usize>().ok()?;
+
+  match unit.to_lowercase().as_str() {
+    "g" => Some(number * 1024),
+    "m" => Some(number),
+    "k" => Some(number / 1024),
+    _ => None,
+  }
+}
+
+async fn read_icon(path: &Path) -> Option<String> {
+  let icon = fs::read(path).await.ok()?;
+  Some(BASE64_STANDARD.encode(icon))
+}
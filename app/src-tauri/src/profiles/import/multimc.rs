@@ -0,0 +1,160 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Result;
+use serde::Deserialize;
+use tokio::fs;
+
+use crate::{
+  path,
+  profiles::config::GameSettings,
+  utils::{dir::list_dirs_in_dir_path, file::read_parse_file, log::ResultLogExt},
+  versions::loader::{LoaderType, forge::neoforge_version_pair},
+};
+
+use super::{ImportCandidate, ImportSource, parse_jvm_args, read_icon};
+
+const INSTANCES_DIR: &str = "instances";
+const ICONS_DIR: &str = "icons";
+const INSTANCE_CFG: &str = "instance.cfg";
+const MMC_PACK: &str = "mmc-pack.json";
+// older instances use the dot prefixed name
+const GAME_DIRS: [&str; 2] = [".minecraft", "minecraft"];
+const MINECRAFT_UID: &str = "net.minecraft";
+const LOADER_UIDS: [(&str, LoaderType); 4] = [
+  ("net.fabricmc.fabric-loader", LoaderType::Fabric),
+  ("org.quiltmc.quilt-loader", LoaderType::Quilt),
+  ("net.minecraftforge", LoaderType::Forge),
+  ("net.neoforged", LoaderType::NeoForge),
+];
+
+#[derive(Deserialize, Debug)]
+struct MmcPack {
+  components: Vec<MmcComponent>,
+}
+
+#[derive(Deserialize, Debug)]
+struct MmcComponent {
+  uid: String,
+  version: Option<String>,
+}
+
+/// `path` can either be the launcher root or its instances directory
+pub async fn scan(path: &Path) -> Result<Vec<ImportCandidate>> {
+  let instances_dir = if path!(path, INSTANCES_DIR).exists() {
+    path!(path, INSTANCES_DIR)
+  } else {
+    path.to_path_buf()
+  };
+  let icons_dir = path!(instances_dir.parent().unwrap_or(path), ICONS_DIR);
+
+  let mut candidates = Vec::new();
+  for dir in list_dirs_in_dir_path(instances_dir).await? {
+    let cfg_path = path!(&dir, INSTANCE_CFG);
+    let pack_path = path!(&dir, MMC_PACK);
+    if !cfg_path.exists() || !pack_path.exists() {
+      continue;
+    }
+
+    // a single broken instance should not hide all the others
+    let Ok(cfg) = fs::read_to_string(&cfg_path).await.log() else {
+      continue;
+    };
+    let cfg = parse_cfg(&cfg);
+    let Ok(pack) = read_parse_file::<MmcPack>(&pack_path).await.log() else {
+      continue;
+    };
+    let Some(game_dir) = GAME_DIRS
+      .iter()
+      .map(|name| path!(&dir, name))
+      .find(|path| path.exists())
+    else {
+      continue;
+    };
+
+    let component = |uid: &str| {
+      pack
+        .components
+        .iter()
+        .find(|c| c.uid == uid)
+        .and_then(|c| c.version.clone())
+    };
+    let Some(version) = component(MINECRAFT_UID) else {
+      continue;
+    };
+    let (loader, loader_version) = LOADER_UIDS
+      .iter()
+      .find_map(|(uid, loader)| component(uid).map(|v| (*loader, Some(v))))
+      .unwrap_or((LoaderType::Vanilla, None));
+    let loader_version = match loader {
+      // the pack stores the full NeoForge version
+      LoaderType::NeoForge => loader_version
+        .and_then(|v| neoforge_version_pair(&v).ok())
+        .map(|(_, v)| v),
+      _ => loader_version,
+    };
+
+    let enabled = |key: &str| cfg.get(key).is_some_and(|v| v == "true");
+    let jvm = (enabled("OverrideMemory") || enabled("OverrideJavaArgs")).then(|| {
+      // each setting is only used if its own override is enabled
+      let args = cfg
+        .get("JvmArgs")
+        .filter(|_| enabled("OverrideJavaArgs"))
+        .map(String::as_str)
+        .unwrap_or_default();
+      let mem_max = cfg
+        .get("MaxMemAlloc")
+        .filter(|_| enabled("OverrideMemory"))
+        .and_then(|m| m.parse().ok());
+      parse_jvm_args(args, mem_max)
+    });
+    let game = enabled("OverrideWindow").then(|| {
+      let default = GameSettings::default();
+      GameSettings {
+        use_custom: true,
+        width: cfg
+          .get("MinecraftWinWidth")
+          .and_then(|w| w.parse().ok())
+          .unwrap_or(default.width),
+        height: cfg
+          .get("MinecraftWinHeight")
+          .and_then(|h| h.parse().ok())
+          .unwrap_or(default.height),
+      }
+    });
+
+    let icon = match cfg.get("iconKey") {
+      Some(key) => read_icon(&path!(&icons_dir, format!("{key}.png"))).await,
+      None => None,
+    };
+
+    candidates.push(ImportCandidate {
+      source: ImportSource::MultiMc,
+      name: cfg.get("name").cloned().unwrap_or_else(|| {
+        dir
+          .file_name()
+          .unwrap_or_default()
+          .to_string_lossy()
+          .to_string()
+      }),
+      game_dir,
+      version,
+      loader,
+      loader_version,
+      jvm,
+      game,
+      icon,
+    });
+  }
+
+  Ok(candidates)
+}
+
+/// Parses the ini like `key=value` format of `instance.cfg`
+fn parse_cfg(content: &str) -> HashMap<String, String> {
+  content
+    .lines()
+    .filter(|line| !line.starts_with('['))
+    .filter_map(|line| line.split_once('='))
+    .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+    .collect()
+}
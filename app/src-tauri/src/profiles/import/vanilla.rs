@@ -0,0 +1,220 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::{
+  path,
+  profiles::config::GameSettings,
+  utils::file::read_parse_file,
+  versions::{
+    loader::{LoaderType, forge::neoforge_version_pair},
+    meta::minecraft::LatestVersion,
+  },
+};
+
+use super::{ImportCandidate, ImportSource, parse_jvm_args};
+
+const LAUNCHER_PROFILES: &str = "launcher_profiles.json";
+const ICON_DATA_PREFIX: &str = "data:image/png;base64,";
+
+#[derive(Deserialize, Debug)]
+struct LauncherProfiles {
+  profiles: HashMap<String, LauncherProfile>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct LauncherProfile {
+  #[serde(default)]
+  name: String,
+  #[serde(default)]
+  r#type: String,
+  last_version_id: Option<String>,
+  game_dir: Option<String>,
+  java_args: Option<String>,
+  resolution: Option<Resolution>,
+  icon: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Resolution {
+  width: usize,
+  height: usize,
+}
+
+pub async fn scan(path: &Path, latest: &LatestVersion) -> Result<Vec<ImportCandidate>> {
+  let profiles: LauncherProfiles = read_parse_file(&path!(path, LAUNCHER_PROFILES)).await?;
+
+  let mut candidates = Vec::new();
+  for profile in profiles.profiles.into_values() {
+    let version_id = match profile.r#type.as_str() {
+      "latest-release" => latest.release.clone(),
+      "latest-snapshot" => latest.snapshot.clone(),
+      _ => match profile.last_version_id {
+        Some(id) => id,
+        None => continue,
+      },
+    };
+    let (version, loader, loader_version) = parse_version_id(&version_id);
+
+    let name = if profile.name.is_empty() {
+      match profile.r#type.as_str() {
+        "latest-snapshot" => "Latest Snapshot".to_string(),
+        _ => "Latest Release".to_string(),
+      }
+    } else {
+      profile.name
+    };
+
+    candidates.push(ImportCandidate {
+      source: ImportSource::Vanilla,
+      name,
+      game_dir: profile
+        .game_dir
+        .map(|dir| path!(dir))
+        .unwrap_or_else(|| path.to_path_buf()),
+      version,
+      loader,
+      loader_version,
+      jvm: profile.java_args.map(|args| parse_jvm_args(&args, None)),
+      game: profile.resolution.map(|r| GameSettings {
+        use_custom: true,
+        width: r.width,
+        height: r.height,
+      }),
+      // built in icons are only referenced by name
+      icon: profile
+        .icon
+        .and_then(|icon| icon.strip_prefix(ICON_DATA_PREFIX).map(String::from)),
+    });
+  }
+
+  Ok(candidates)
+}
+
+/// Maps the version id of an installed version onto the Minecraft version and loader
+fn parse_version_id(id: &str) -> (String, LoaderType, Option<String>) {
+  // e.g., "fabric-loader-0.15.0-1.20.1"
+  for (prefix, loader) in [
+    ("fabric-loader-", LoaderType::Fabric),
+    ("quilt-loader-", LoaderType::Quilt),
+  ] {
+    if let Some((loader_version, version)) = id.strip_prefix(prefix).and_then(|v| v.split_once('-'))
+    {
+      return (
+        version.to_string(),
+        loader,
+        Some(loader_version.to_string()),
+      );
+    }
+  }
+
+  // e.g., "neoforge-21.1.77"
+  if let Some(neoforge) = id.strip_prefix("neoforge-")
+    && let Ok((version, loader_version)) = neoforge_version_pair(neoforge)
+  {
+    return (version, LoaderType::NeoForge, Some(loader_version));
+  }
+
+  // e.g., "1.20.1-OptiFine_HD_U_I6", the installer has to be selected again by the user
+  if let Some((version, _)) = id.split_once("-OptiFine_") {
+    return (version.to_string(), LoaderType::OptiFine, None);
+  }
+
+  // e.g., "1.20.1-forge-47.2.0", "1.12.2-forge1.12.2-14.23.5.2847" or "1.7.10-Forge10.13.4.1614-1.7.10"
+  if let Some(index) = id.to_lowercase().find("-forge") {
+    let version = &id[..index];
+    let loader_version = id[index + "-forge".len()..].trim_start_matches('-');
+    let loader_version = loader_version
+      .strip_prefix(&format!("{version}-"))
+      .unwrap_or(loader_version)
+      .trim_end_matches(&format!("-{version}"));
+    return (
+      version.to_string(),
+      LoaderType::Forge,
+      Some(loader_version.to_string()),
+    );
+  }
+
+  (id.to_string(), LoaderType::Vanilla, None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn version_ids() {
+    let cases = [
+      ("1.20.1", "1.20.1", LoaderType::Vanilla, None),
+      ("23w31a", "23w31a", LoaderType::Vanilla, None),
+      (
+        "fabric-loader-0.15.0-1.20.1",
+        "1.20.1",
+        LoaderType::Fabric,
+        Some("0.15.0"),
+      ),
+      (
+        "quilt-loader-0.23.1-1.20.4",
+        "1.20.4",
+        LoaderType::Quilt,
+        Some("0.23.1"),
+      ),
+      (
+        "neoforge-21.1.77",
+        "1.21.1",
+        LoaderType::NeoForge,
+        Some("77"),
+      ),
+      (
+        "1.20.1-forge-47.2.0",
+        "1.20.1",
+        LoaderType::Forge,
+        Some("47.2.0"),
+      ),
+      (
+        "1.12.2-forge-14.23.5.2860",
+        "1.12.2",
+        LoaderType::Forge,
+        Some("14.23.5.2860"),
+      ),
+      (
+        "1.12.2-forge1.12.2-14.23.5.2847",
+        "1.12.2",
+        LoaderType::Forge,
+        Some("14.23.5.2847"),
+      ),
+      (
+        "1.7.10-Forge10.13.4.1614-1.7.10",
+        "1.7.10",
+        LoaderType::Forge,
+        Some("10.13.4.1614"),
+      ),
+      (
+        "1.20.1-OptiFine_HD_U_I6",
+        "1.20.1",
+        LoaderType::OptiFine,
+        None,
+      ),
+      (
+        "1.8.9-OptiFine_HD_U_M5",
+        "1.8.9",
+        LoaderType::OptiFine,
+        None,
+      ),
+    ];
+
+    for (id, version, loader, loader_version) in cases {
+      assert_eq!(
+        parse_version_id(id),
+        (
+          version.to_string(),
+          loader,
+          loader_version.map(String::from)
+        ),
+        "{id}"
+      );
+    }
+  }
+}
@@ -1,22 +1,39 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+  collections::HashMap,
+  io::SeekFrom,
+  path::PathBuf,
+  sync::{Arc, Mutex as StdMutex},
+  time::Duration,
+};
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use log::debug;
-use serde::Serialize;
-use tauri::{AppHandle, Emitter, async_runtime::spawn};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, async_runtime::spawn};
 use thiserror::Error;
 use tokio::{
-  fs,
-  io::{AsyncBufReadExt, BufReader},
-  process::Child,
+  fs::{self, File, OpenOptions},
+  io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader},
+  process::{Child, ChildStdin},
   select,
-  sync::{Mutex, Notify},
+  sync::Mutex,
+  time::sleep,
 };
 use uuid::Uuid;
 
 use crate::{
-  profiles::config::{Profile, ProfileInfo},
+  path,
+  profiles::{
+    GAME_LOGS_DIR, PROFILE_DIR,
+    config::{Profile, ProfileInfo},
+    crash::CrashInfo,
+    process::{process_alive, stop_process_tree},
+    profile::run_log_file,
+    sessions::{Session, SessionTarget, record_session},
+    stats::{InstanceSample, InstanceStats, RunSummary, spawn_sampler},
+  },
+  store::TauriAppStoreExt,
   utils::{
     log::ResultLogExt,
     updater::{UpdateType, update_data},
@@ -25,28 +42,69 @@ use crate::{
 };
 
 const CRASH_EVENT: &str = "instance-crash";
+const INSTANCES_KEY: &str = "instances";
+const GAME_LATEST_LOG: &str = "latest.log";
+const ATTACHED_POLL_INTERVAL: Duration = Duration::from_secs(2);
+// how long finished instances stay visible so their final state can be shown
+const FINISHED_LINGER: Duration = Duration::from_secs(10);
+
+pub type Instances = Arc<Mutex<HashMap<String, Vec<Instance>>>>;
 
 pub struct Instance {
   id: String,
+  pid: u32,
   launched_at: DateTime<Utc>,
   profile_name: String,
   profile_id: String,
   version: String,
   loader: LoaderType,
   loader_version: Option<String>,
-  stop_signal: Arc<Notify>,
+  log_file: PathBuf,
+  quick_play: Option<SessionTarget>,
+  attached: bool,
+  // closing stdin is the only way to ask the game to quit where there are no signals
+  stdin: Arc<StdMutex<Option<ChildStdin>>>,
+  state: Arc<StdMutex<InstanceState>>,
+  stats: Arc<Mutex<InstanceStats>>,
   lines: Arc<Mutex<Vec<String>>>,
 }
 
 #[derive(Serialize)]
 pub struct InstanceInfo {
   pub id: String,
+  pub pid: u32,
+  pub launched_at: DateTime<Utc>,
+  pub profile_name: String,
+  pub profile_id: String,
+  pub version: String,
+  pub loader: LoaderType,
+  pub loader_version: Option<String>,
+  pub attached: bool,
+  pub state: InstanceState,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InstanceState {
+  Running,
+  Stopping,
+  Exited(Option<i32>),
+  Killed,
+}
+
+/// The state of a running instance that is persisted so it can be reattached after a restart
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PersistedInstance {
+  pub id: String,
+  pub pid: u32,
   pub launched_at: DateTime<Utc>,
   pub profile_name: String,
   pub profile_id: String,
   pub version: String,
   pub loader: LoaderType,
   pub loader_version: Option<String>,
+  pub log_file: PathBuf,
+  #[serde(default)]
+  pub quick_play: Option<SessionTarget>,
 }
 
 #[derive(Error, Debug)]
@@ -62,14 +120,12 @@ impl Instance {
     mut child: Child,
     app_handle: &AppHandle,
     profile: &Profile,
-    instances: &Arc<Mutex<HashMap<String, Vec<Instance>>>>,
+    quick_play: Option<SessionTarget>,
+    instances: &Instances,
   ) -> Result<()> {
     let id = Uuid::new_v4().to_string();
-    debug!(
-      "Adding child instance with pid {} and id {}",
-      child.id().ok_or(InstanceError::InvalidProcessState)?,
-      id
-    );
+    let pid = child.id().ok_or(InstanceError::InvalidProcessState)?;
+    debug!("Adding child instance with pid {} and id {}", pid, id);
 
     let stdout = child
       .stdout
@@ -79,19 +135,24 @@ impl Instance {
       .stderr
       .take()
       .ok_or(InstanceError::InvalidProcessState)?;
+    let stdin = child.stdin.take();
     let mut stdout = BufReader::new(stdout).lines();
     let mut stderr = BufReader::new(stderr).lines();
 
     let lines = Arc::new(Mutex::new(Vec::new()));
-    let stop_signal = Arc::new(Notify::new());
+    let state = Arc::new(StdMutex::new(InstanceState::Running));
+    let stats = Arc::new(Mutex::new(InstanceStats::default()));
     let launched_at = Utc::now();
 
+    let log_file = run_log_file(&ProfileInfo::log_dir(app_handle, &profile.id)?, launched_at);
+    let mut log = open_log(&log_file).await?;
+
     let id_ = id.clone();
     let profile_ = profile.id.clone();
     let profile_name = profile.name.clone();
     let lines_ = lines.clone();
     let instances_ = instances.clone();
-    let notify = stop_signal.clone();
+    let state_ = state.clone();
     let handle = app_handle.clone();
 
     spawn(async move {
@@ -99,56 +160,230 @@ impl Instance {
         let line = select! {
           Ok(Some(line)) = stdout.next_line() => line,
           Ok(Some(line)) = stderr.next_line() => line,
-          _ = notify.notified() => {
-            debug!("Stopping instance with profile {profile_} and id {id_}");
-            let _ = child.kill().await.log();
-            clean_instance(&handle, &instances_, &profile_, &id_, &lines_, launched_at).await;
-            break;
-          }
           exit = child.wait() => {
             debug!("Child with profile {profile_} and id {id_} exited");
-            clean_instance(&handle, &instances_, &profile_, &id_, &lines_, launched_at).await;
+            let _ = log.flush().await.log();
+            let code = exit.as_ref().ok().and_then(|status| status.code());
+            let previous = finish(&state_, code);
+            // a non-zero exit code is expected when the instance was stopped
+            let crashed = previous == InstanceState::Running
+              && exit.as_ref().is_ok_and(|status| !status.success());
+            clean_instance(&handle, &instances_, &profile_, &id_, crashed).await;
 
-            if let Ok(status) = exit && !status.success() {
+            if crashed && let Ok(status) = exit {
               debug!("Child with profile {profile_} and id {id_} exited with status: {}", status);
-              let _ = handle.emit(CRASH_EVENT, CrashInfo {
+              let lines = lines_.lock().await.clone();
+              if let Ok(info) = CrashInfo::analyze(
+                &handle,
                 profile_name,
-              }).log();
+                profile_.clone(),
+                launched_at,
+                status.code(),
+                &lines,
+              ).await.log() {
+                let _ = info.save(&handle).await.log();
+                update_data(&handle, UpdateType::ProfileLogs);
+                let _ = handle.emit(CRASH_EVENT, info).log();
+              }
             }
             break;
           }
           else => break
         };
         debug!("Profile: {}, id: {}, {}", profile_, id_, line);
-        lines_.lock().await.push(line);
+        push_line(&mut log, &lines_, line).await;
         update_data(&handle, UpdateType::InstanceLogs);
       }
     });
 
+    spawn_sampler(pid, state.clone(), stats.clone(), app_handle.clone());
+
     let instance = Instance {
       id,
+      pid,
       lines,
-      stop_signal,
+      state,
+      stats,
       launched_at,
       profile_name: profile.name.clone(),
       profile_id: profile.id.clone(),
       version: profile.version.clone(),
       loader: profile.loader,
       loader_version: profile.loader_version.clone(),
+      log_file,
+      quick_play,
+      attached: false,
+      stdin: Arc::new(StdMutex::new(stdin)),
     };
     let mut instances = instances.lock().await;
     instances
       .entry(profile.id.clone())
       .or_default()
       .push(instance);
+    save_instances(app_handle, &instances);
+    update_data(app_handle, UpdateType::Instances);
+    update_data(app_handle, UpdateType::ProfileLogs);
+
+    Ok(())
+  }
+
+  /// Reattaches to an instance that was started by a previous launcher run and is still alive.
+  /// Its stdout is gone, so the game's own log file is followed instead.
+  pub async fn attach(
+    app_handle: &AppHandle,
+    persisted: PersistedInstance,
+    instances: &Instances,
+  ) -> Result<()> {
+    debug!(
+      "Reattaching instance with pid {} and id {}",
+      persisted.pid, persisted.id
+    );
+
+    let existing = fs::read_to_string(&persisted.log_file)
+      .await
+      .unwrap_or_default();
+    let lines = Arc::new(Mutex::new(
+      existing.lines().map(String::from).collect::<Vec<_>>(),
+    ));
+    let state = Arc::new(StdMutex::new(InstanceState::Running));
+    let stats = Arc::new(Mutex::new(InstanceStats::default()));
+    let mut log = open_log(&persisted.log_file).await?;
+
+    let game_log = path!(
+      app_handle.path().app_data_dir()?,
+      PROFILE_DIR,
+      &persisted.profile_id,
+      GAME_LOGS_DIR,
+      GAME_LATEST_LOG
+    );
+    let mut offset = fs::metadata(&game_log)
+      .await
+      .map(|m| m.len())
+      .unwrap_or_default();
+
+    let id_ = persisted.id.clone();
+    let profile_ = persisted.profile_id.clone();
+    let pid = persisted.pid;
+    let launched_at = persisted.launched_at;
+    let lines_ = lines.clone();
+    let instances_ = instances.clone();
+    let state_ = state.clone();
+    let handle = app_handle.clone();
+
+    spawn(async move {
+      loop {
+        sleep(ATTACHED_POLL_INTERVAL).await;
+
+        if let Ok(new_lines) = read_new_lines(&game_log, &mut offset).await.log()
+          && !new_lines.is_empty()
+        {
+          for line in new_lines {
+            push_line(&mut log, &lines_, line).await;
+          }
+          update_data(&handle, UpdateType::InstanceLogs);
+        }
+
+        if !process_alive(pid, launched_at) {
+          debug!("Attached instance with profile {profile_} and id {id_} exited");
+          let _ = log.flush().await.log();
+          // the exit code of a process that is not our child is unknown
+          finish(&state_, None);
+          clean_instance(&handle, &instances_, &profile_, &id_, false).await;
+          break;
+        }
+      }
+    });
+
+    spawn_sampler(pid, state.clone(), stats.clone(), app_handle.clone());
+
+    let instance = Instance {
+      id: persisted.id,
+      pid,
+      lines,
+      state,
+      stats,
+      launched_at,
+      profile_name: persisted.profile_name,
+      profile_id: persisted.profile_id.clone(),
+      version: persisted.version,
+      loader: persisted.loader,
+      loader_version: persisted.loader_version,
+      log_file: persisted.log_file,
+      quick_play: persisted.quick_play,
+      attached: true,
+      stdin: Arc::new(StdMutex::new(None)),
+    };
+    let mut instances = instances.lock().await;
+    instances
+      .entry(persisted.profile_id)
+      .or_default()
+      .push(instance);
+    save_instances(app_handle, &instances);
     update_data(app_handle, UpdateType::Instances);
 
     Ok(())
   }
 
-  pub fn stop(&self) {
+  /// Asks the game to shut down so the world gets saved and kills the whole process tree
+  /// if it did not exit within the timeout
+  pub fn stop(&self, handle: &AppHandle, timeout: Duration) {
+    {
+      let mut state = self.state.lock().unwrap();
+      if *state != InstanceState::Running {
+        return;
+      }
+      *state = InstanceState::Stopping;
+    }
     debug!("Stopping instance with id {}", self.id);
-    self.stop_signal.notify_waiters();
+    update_data(handle, UpdateType::Instances);
+
+    let pid = self.pid;
+    let launched_at = self.launched_at;
+    let stdin = self.stdin.clone();
+    let state = self.state.clone();
+    let handle = handle.clone();
+    spawn(async move {
+      let killed = stop_process_tree(
+        pid,
+        launched_at,
+        timeout,
+        || stdin.lock().unwrap().take().is_some(),
+        || {
+          *state.lock().unwrap() = InstanceState::Killed;
+        },
+      )
+      .await;
+
+      if killed {
+        update_data(&handle, UpdateType::Instances);
+      }
+    });
+  }
+
+  pub async fn stats(&self) -> Vec<InstanceSample> {
+    self.stats.lock().await.samples()
+  }
+
+  fn session(&self, ended_at: DateTime<Utc>, crashed: bool) -> Session {
+    Session {
+      launched_at: self.launched_at,
+      ended_at,
+      quick_play: self.quick_play.clone(),
+      crashed,
+    }
+  }
+
+  async fn summary(&self) -> RunSummary {
+    RunSummary {
+      launched_at: self.launched_at,
+      ended_at: Utc::now(),
+      state: self.state(),
+      peak_memory: self.stats.lock().await.peak_memory(),
+    }
+  }
+
+  pub fn state(&self) -> InstanceState {
+    *self.state.lock().unwrap()
   }
 
   pub async fn lines(&self) -> Vec<String> {
@@ -159,6 +394,10 @@ impl Instance {
     &self.id
   }
 
+  pub fn pid(&self) -> u32 {
+    self.pid
+  }
+
   pub fn profile_name(&self) -> &str {
     &self.profile_name
   }
@@ -182,41 +421,153 @@ impl Instance {
   pub fn launched_at(&self) -> DateTime<Utc> {
     self.launched_at
   }
+
+  pub fn attached(&self) -> bool {
+    self.attached
+  }
+
+  fn persisted(&self) -> PersistedInstance {
+    PersistedInstance {
+      id: self.id.clone(),
+      pid: self.pid,
+      launched_at: self.launched_at,
+      profile_name: self.profile_name.clone(),
+      profile_id: self.profile_id.clone(),
+      version: self.version.clone(),
+      loader: self.loader,
+      loader_version: self.loader_version.clone(),
+      log_file: self.log_file.clone(),
+      quick_play: self.quick_play.clone(),
+    }
+  }
+}
+
+impl PersistedInstance {
+  pub fn load(handle: &AppHandle) -> Result<Vec<PersistedInstance>> {
+    handle.app_store()?.get_or_default(INSTANCES_KEY)
+  }
+
+  pub fn is_alive(&self) -> bool {
+    process_alive(self.pid, self.launched_at)
+  }
+}
+
+pub fn save_instances(handle: &AppHandle, instances: &HashMap<String, Vec<Instance>>) {
+  let persisted = instances
+    .values()
+    .flatten()
+    .filter(|i| matches!(i.state(), InstanceState::Running | InstanceState::Stopping))
+    .map(Instance::persisted)
+    .collect::<Vec<_>>();
+
+  if let Ok(store) = handle.app_store().log() {
+    let _ = store.set(INSTANCES_KEY, &persisted).log();
+  }
+}
+
+async fn open_log(path: &PathBuf) -> Result<File> {
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+
+  Ok(
+    OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(path)
+      .await?,
+  )
+}
+
+async fn push_line(log: &mut File, lines: &Mutex<Vec<String>>, line: String) {
+  let _ = log.write_all(format!("{line}\n").as_bytes()).await.log();
+  lines.lock().await.push(line);
+}
+
+/// Reads all complete lines that were appended to the file since the last offset
+async fn read_new_lines(path: &PathBuf, offset: &mut u64) -> Result<Vec<String>> {
+  let Ok(mut file) = File::open(path).await else {
+    return Ok(Vec::new());
+  };
+
+  let len = file.metadata().await?.len();
+  if len < *offset {
+    // the log was rotated
+    *offset = 0;
+  }
+
+  file.seek(SeekFrom::Start(*offset)).await?;
+  let mut data = Vec::new();
+  file.read_to_end(&mut data).await?;
+
+  let Some(end) = data.iter().rposition(|b| *b == b'\n') else {
+    return Ok(Vec::new());
+  };
+  *offset += end as u64 + 1;
+
+  Ok(
+    String::from_utf8_lossy(&data[..end])
+      .lines()
+      .map(String::from)
+      .collect(),
+  )
+}
+
+/// Sets the final state and returns the state the instance was in before
+fn finish(state: &StdMutex<InstanceState>, code: Option<i32>) -> InstanceState {
+  let mut state = state.lock().unwrap();
+  let previous = *state;
+  if previous != InstanceState::Killed {
+    *state = InstanceState::Exited(code);
+  }
+  previous
 }
 
 async fn clean_instance(
   handle: &AppHandle,
-  instances: &Arc<Mutex<HashMap<String, Vec<Instance>>>>,
+  instances: &Instances,
   profile: &str,
   id: &str,
-  lines: &Arc<Mutex<Vec<String>>>,
-  launched_at: DateTime<Utc>,
+  crashed: bool,
 ) {
-  let mut instances = instances.lock().await;
-  if let Some(entry) = instances.get_mut(profile)
-    && let Some(i) = entry.iter().position(|i| i.id() == id)
+  let locked = instances.lock().await;
+  let finished = match locked
+    .get(profile)
+    .and_then(|e| e.iter().find(|i| i.id() == id))
   {
-    let _ = entry.swap_remove(i);
-  }
-  update_data(handle, UpdateType::Instances);
+    Some(instance) => {
+      let summary = instance.summary().await;
+      Some((instance.session(summary.ended_at, crashed), summary))
+    }
+    None => None,
+  };
+  save_instances(handle, &locked);
+  drop(locked);
 
-  if let Ok(logs_dir) = ProfileInfo::log_dir(handle, profile)
-    && fs::create_dir_all(&logs_dir).await.is_ok()
+  if let Some((session, summary)) = finished
+    && let Ok(log_dir) = ProfileInfo::log_dir(handle, profile)
   {
-    let log_file = logs_dir.join(format!(
-      "{}.log",
-      launched_at.to_rfc3339().replace(":", "-")
-    ));
-
-    let lines = lines.lock().await;
-    let content = lines.join("\n");
-    let _ = fs::write(log_file, content).await.log();
-
-    update_data(handle, UpdateType::ProfileLogs);
+    let _ = summary.save(&log_dir).await.log();
+    if let Some(profile_dir) = log_dir.parent() {
+      let _ = record_session(profile_dir, session).await.log();
+    }
   }
-}
+  update_data(handle, UpdateType::Instances);
+  update_data(handle, UpdateType::ProfileLogs);
 
-#[derive(Serialize, Clone, Debug)]
-struct CrashInfo {
-  profile_name: String,
+  let handle = handle.clone();
+  let instances = instances.clone();
+  let profile = profile.to_string();
+  let id = id.to_string();
+  spawn(async move {
+    sleep(FINISHED_LINGER).await;
+
+    let mut instances = instances.lock().await;
+    if let Some(entry) = instances.get_mut(&profile)
+      && let Some(i) = entry.iter().position(|i| i.id() == id)
+    {
+      let _ = entry.swap_remove(i);
+    }
+    update_data(&handle, UpdateType::Instances);
+  });
 }
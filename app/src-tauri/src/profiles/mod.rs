@@ -1,12 +1,33 @@
+pub mod backup;
+pub mod bisect;
 pub mod commands;
 pub mod config;
+pub mod crash;
+pub mod import;
 mod instance;
+pub mod mod_check;
+pub mod mod_updates;
+pub mod modrinth;
+pub mod mods;
+pub mod packs;
+mod process;
 mod profile;
+pub mod servers;
+pub mod sessions;
+pub mod stats;
 pub mod store;
 mod watcher;
+pub mod worlds;
 
 const PROFILE_DIR: &str = "profiles";
 const PROFILE_CONFIG: &str = "profile.json";
 const PROFILE_IMAGE: &str = "image.png";
 const PROFILE_LOGS: &str = "instance_logs";
+const PROFILE_SESSIONS: &str = "sessions.json";
+const SCREENSHOTS_DIR: &str = "screenshots";
+const MODS_DIR: &str = "mods";
+const RESOURCE_PACKS_DIR: &str = "resourcepacks";
+const SHADER_PACKS_DIR: &str = "shaderpacks";
+const GAME_LOGS_DIR: &str = "logs";
+const CRASH_REPORTS_DIR: &str = "crash-reports";
 const SAVES_DIR: &str = "saves";
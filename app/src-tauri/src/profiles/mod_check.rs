@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+  profiles::{
+    config::Profile,
+    mods::{DependencyKind, ModInfo},
+  },
+  utils::version::{matches_fabric_predicate, matches_maven_range},
+  versions::loader::{LoaderType, forge::neoforge_version},
+};
+
+const MINECRAFT_ID: &str = "minecraft";
+// fabric mods can depend on the java version, which is chosen by the launcher
+const IGNORED_DEPENDENCIES: [&str; 1] = ["java"];
+
+#[derive(Error, Debug)]
+pub enum ModCheckError {
+  #[error("ModIssues")]
+  Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ModIssue {
+  MissingDependency {
+    file: String,
+    mod_id: String,
+    dependency: String,
+    version: Option<String>,
+  },
+  WrongDependencyVersion {
+    file: String,
+    mod_id: String,
+    dependency: String,
+    version: String,
+    // the versions that are installed
+    found: Vec<String>,
+  },
+  Incompatible {
+    file: String,
+    mod_id: String,
+    other: String,
+    version: Option<String>,
+  },
+  DuplicateMod {
+    mod_id: String,
+    files: Vec<String>,
+  },
+  // only a warning, the loader skips the jar
+  WrongLoader {
+    file: String,
+    mod_id: String,
+  },
+  // only a warning, the game version has no semver form to check the requirement against
+  UnknownGameVersion {
+    file: String,
+    mod_id: String,
+    version: String,
+    found: String,
+  },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModReport {
+  pub issues: Vec<ModIssue>,
+  pub has_errors: bool,
+}
+
+impl ModIssue {
+  pub fn is_error(&self) -> bool {
+    !matches!(
+      self,
+      ModIssue::WrongLoader { .. } | ModIssue::UnknownGameVersion { .. }
+    )
+  }
+}
+
+/// Ids provided by the game and the loader itself, a version of `None` matches every requirement
+fn platform_mods(profile: &Profile, game_version: Option<&str>) -> Vec<(String, Option<String>)> {
+  let loader_version = profile.loader_version.clone();
+  let mut mods = vec![(
+    MINECRAFT_ID.to_string(),
+    Some(game_version.unwrap_or(&profile.version).to_string()),
+  )];
+
+  match profile.loader {
+    // optifine does not load any mods
+    LoaderType::Vanilla | LoaderType::LiteLoader | LoaderType::OptiFine => (),
+    // legacy fabric and babric are forks of the fabric loader
+    LoaderType::Fabric | LoaderType::LegacyFabric | LoaderType::Babric => {
+      mods.push(("fabricloader".into(), loader_version))
+    }
+    LoaderType::Quilt => {
+      mods.push(("quilt_loader".into(), loader_version));
+      // quilt pretends to be a recent fabric loader
+      mods.push(("fabricloader".into(), None));
+    }
+    LoaderType::Forge => mods.push(("forge".into(), loader_version)),
+    LoaderType::NeoForge => mods.push((
+      "neoforge".into(),
+      loader_version.map(|v| neoforge_version(&profile.version, &v)),
+    )),
+    // the version of a custom loader is its installer, so any version is accepted
+    LoaderType::CustomForge => {
+      mods.push(("forge".into(), None));
+      mods.push(("neoforge".into(), None));
+    }
+    LoaderType::CustomFabric => mods.push(("fabricloader".into(), None)),
+  }
+
+  mods
+}
+
+fn matches_requirement(loader: Option<LoaderType>, requirement: &str, version: &str) -> bool {
+  match loader {
+    Some(LoaderType::Forge | LoaderType::NeoForge) => matches_maven_range(requirement, version),
+    _ => matches_fabric_predicate(requirement, version),
+  }
+}
+
+/// Checks the dependencies and conflicts of the mods the profile will load.
+/// `game_version` is the semver form of the profile version, requirements on the game are
+/// only warnings without it.
+pub fn check_mods(profile: &Profile, mods: &[ModInfo], game_version: Option<&str>) -> ModReport {
+  let mut issues = Vec::new();
+
+  let mut loaded = Vec::new();
+  for info in mods.iter().filter(|m| m.enabled) {
+    if info.loader_mismatch {
+      issues.push(ModIssue::WrongLoader {
+        file: info.file.clone(),
+        mod_id: info.id.clone(),
+      });
+    } else if info.loader.is_some() {
+      loaded.push(info);
+    }
+  }
+
+  let mut files_by_id = HashMap::<&str, Vec<String>>::new();
+  for info in &loaded {
+    files_by_id
+      .entry(&info.id)
+      .or_default()
+      .push(info.file.clone());
+  }
+  let mut duplicates = files_by_id
+    .into_iter()
+    .filter(|(_, files)| files.len() > 1)
+    .collect::<Vec<_>>();
+  duplicates.sort_by_key(|(id, _)| *id);
+  issues.extend(
+    duplicates
+      .into_iter()
+      .map(|(id, files)| ModIssue::DuplicateMod {
+        mod_id: id.to_string(),
+        files,
+      }),
+  );
+
+  let mut provided = HashMap::<String, Vec<Option<String>>>::new();
+  for (id, version) in platform_mods(profile, game_version) {
+    provided.entry(id).or_default().push(version);
+  }
+  for info in &loaded {
+    provided
+      .entry(info.id.clone())
+      .or_default()
+      .push(Some(info.version.clone()));
+    for p in &info.provides {
+      provided
+        .entry(p.id.clone())
+        .or_default()
+        .push(Some(p.version.clone()));
+    }
+  }
+
+  for info in &loaded {
+    for dependency in &info.dependencies {
+      if IGNORED_DEPENDENCIES.contains(&dependency.id.as_str()) {
+        continue;
+      }
+      let versions = provided.get(&dependency.id);
+      let matches = |version: &Option<String>| match (&dependency.version, version) {
+        (Some(requirement), Some(version)) => {
+          matches_requirement(info.loader, requirement, version)
+        }
+        _ => true,
+      };
+
+      // the raw id of snapshots and old versions can not be compared as semver
+      let unknown_game = dependency.id == MINECRAFT_ID && game_version.is_none();
+
+      match dependency.kind {
+        DependencyKind::Required => match versions {
+          None => issues.push(ModIssue::MissingDependency {
+            file: info.file.clone(),
+            mod_id: info.id.clone(),
+            dependency: dependency.id.clone(),
+            version: dependency.version.clone(),
+          }),
+          Some(versions) if unknown_game && !versions.iter().any(matches) => {
+            issues.push(ModIssue::UnknownGameVersion {
+              file: info.file.clone(),
+              mod_id: info.id.clone(),
+              version: dependency.version.clone().unwrap_or_default(),
+              found: profile.version.clone(),
+            })
+          }
+          Some(versions) if !versions.iter().any(matches) => {
+            issues.push(ModIssue::WrongDependencyVersion {
+              file: info.file.clone(),
+              mod_id: info.id.clone(),
+              dependency: dependency.id.clone(),
+              version: dependency.version.clone().unwrap_or_default(),
+              found: versions.iter().flatten().cloned().collect(),
+            })
+          }
+          Some(_) => (),
+        },
+        DependencyKind::Incompatible => {
+          if !unknown_game && versions.is_some_and(|versions| versions.iter().any(matches)) {
+            issues.push(ModIssue::Incompatible {
+              file: info.file.clone(),
+              mod_id: info.id.clone(),
+              other: dependency.id.clone(),
+              version: dependency.version.clone(),
+            });
+          }
+        }
+        DependencyKind::Optional => (),
+      }
+    }
+  }
+
+  ModReport {
+    has_errors: issues.iter().any(ModIssue::is_error),
+    issues,
+  }
+}
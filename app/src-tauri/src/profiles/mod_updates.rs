@@ -0,0 +1,240 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use log::{debug, warn};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::fs;
+
+use crate::{
+  path,
+  profiles::{
+    config::Profile,
+    modrinth::{
+      self, ModrinthVersion, download_primary_file, latest_versions_from_hashes,
+      versions_from_hashes,
+    },
+    mods::{is_enabled, mod_files, modrinth_loaders, mods_dir},
+  },
+  utils::file::file_sha1,
+};
+
+// new jars are downloaded here first so a failed download leaves the mods untouched
+const UPDATE_DIR: &str = ".update";
+
+#[derive(Error, Debug)]
+pub enum ModUpdateError {
+  #[error("NotFound")]
+  NotFound,
+  #[error("FileExists")]
+  FileExists,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModChangelog {
+  pub version_number: String,
+  pub changelog: Option<String>,
+  pub date_published: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModUpdate {
+  // the jar name inside the mods folder
+  pub file: String,
+  pub project_id: String,
+  pub current_version: String,
+  pub version_id: String,
+  pub version_number: String,
+  // all versions newer than the installed one, newest first
+  pub changelogs: Vec<ModChangelog>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModUpdateTarget {
+  pub file: String,
+  pub version_id: String,
+}
+
+impl From<&ModrinthVersion> for ModChangelog {
+  fn from(version: &ModrinthVersion) -> Self {
+    Self {
+      version_number: version.version_number.clone(),
+      changelog: version.changelog.clone(),
+      date_published: version.date_published,
+    }
+  }
+}
+
+/// Finds newer versions on modrinth for all mods that are not pinned.
+/// Mods are identified by the sha1 hash of their jar.
+pub async fn check_mod_updates(
+  client: &Client,
+  profile_dir: &Path,
+  profile: &Profile,
+) -> Result<Vec<ModUpdate>> {
+  let dir = mods_dir(profile_dir);
+  let mut files_by_hash = HashMap::new();
+  for file in mod_files(profile_dir).await? {
+    // a new jar would silently enable a disabled mod
+    if profile.pinned_mods.contains(&file) || !is_enabled(&file) {
+      continue;
+    }
+    match file_sha1(&path!(&dir, &file)).await {
+      Ok(hash) => {
+        files_by_hash.insert(hash, file);
+      }
+      Err(err) => debug!("Failed to hash mod {file}: {err:#}"),
+    }
+  }
+  if files_by_hash.is_empty() {
+    return Ok(Vec::new());
+  }
+
+  let hashes = files_by_hash.keys().cloned().collect::<Vec<_>>();
+  let loaders = modrinth_loaders(profile.loader);
+  let current = versions_from_hashes(client, &hashes).await?;
+  let latest = latest_versions_from_hashes(client, &hashes, &profile.version, loaders).await?;
+
+  let updates = latest.into_iter().filter_map(|(hash, latest)| {
+    let current = current.get(&hash)?;
+    // the update lookup also returns the installed version if nothing newer exists
+    if current.id == latest.id || current.date_published >= latest.date_published {
+      return None;
+    }
+    Some((files_by_hash.get(&hash)?.clone(), current.clone(), latest))
+  });
+
+  let mut updates = join_all(updates.map(|(file, current, latest)| async move {
+    let changelogs =
+      match modrinth::project_versions(client, &latest.project_id, Some(&profile.version), loaders)
+        .await
+      {
+        Ok(versions) => versions
+          .iter()
+          .take_while(|v| v.id != current.id && v.date_published > current.date_published)
+          .map(ModChangelog::from)
+          .collect(),
+        Err(err) => {
+          debug!("Failed to get changelogs of {}: {err:#}", latest.project_id);
+          vec![ModChangelog::from(&latest)]
+        }
+      };
+
+    ModUpdate {
+      file,
+      project_id: latest.project_id,
+      current_version: current.version_number,
+      version_id: latest.id,
+      version_number: latest.version_number,
+      changelogs,
+    }
+  }))
+  .await;
+  updates.sort_by(|a, b| a.file.cmp(&b.file));
+  Ok(updates)
+}
+
+/// Replaces the jars with the given versions.
+/// Either all updates are applied or the mods folder is left unchanged.
+pub async fn apply_mod_updates(
+  client: &Client,
+  profile_dir: &Path,
+  targets: &[ModUpdateTarget],
+) -> Result<()> {
+  if targets.is_empty() {
+    return Ok(());
+  }
+
+  let dir = mods_dir(profile_dir);
+  let staging = path!(&dir, UPDATE_DIR);
+  if staging.exists() {
+    fs::remove_dir_all(&staging).await?;
+  }
+  fs::create_dir_all(&staging).await?;
+
+  let downloaded = match download_updates(client, &dir, &staging, targets).await {
+    Ok(downloaded) => downloaded,
+    Err(err) => {
+      let _ = fs::remove_dir_all(&staging).await;
+      return Err(err);
+    }
+  };
+
+  // (old jar, moved old jar, new jar)
+  let mut swapped: Vec<(PathBuf, PathBuf, PathBuf)> = Vec::new();
+  for (file, new) in downloaded {
+    let old = path!(&dir, &file);
+    let backup = path!(&staging, format!("{file}.old"));
+    let target = path!(&dir, new.file_name().unwrap_or_default());
+
+    if let Err(err) = fs::rename(&old, &backup).await {
+      rollback(&swapped).await;
+      return Err(err.into());
+    }
+    if let Err(err) = fs::rename(&new, &target).await {
+      let _ = fs::rename(&backup, &old).await;
+      rollback(&swapped).await;
+      return Err(err.into());
+    }
+    swapped.push((old, backup, target));
+  }
+
+  let _ = fs::remove_dir_all(&staging).await;
+  Ok(())
+}
+
+/// Downloads the new jars into `staging` and returns them with the jar they replace
+async fn download_updates(
+  client: &Client,
+  dir: &Path,
+  staging: &Path,
+  targets: &[ModUpdateTarget],
+) -> Result<Vec<(String, PathBuf)>> {
+  let ids = targets
+    .iter()
+    .map(|t| t.version_id.clone())
+    .collect::<Vec<_>>();
+  let versions = modrinth::versions(client, &ids)
+    .await?
+    .into_iter()
+    .map(|v| (v.id.clone(), v))
+    .collect::<HashMap<_, _>>();
+
+  let mut downloaded = Vec::new();
+  for target in targets {
+    // the file name comes from the frontend and must stay inside the mods folder
+    if Path::new(&target.file).components().count() != 1 || !path!(dir, &target.file).is_file() {
+      return Err(ModUpdateError::NotFound.into());
+    }
+    let version = versions
+      .get(&target.version_id)
+      .ok_or(ModUpdateError::NotFound)?;
+
+    let path = download_primary_file(client, version, staging).await?;
+    let name = path.file_name().unwrap_or_default();
+    // do not overwrite a different mod that already uses the new name
+    if name != target.file.as_str() && path!(dir, name).exists() {
+      return Err(ModUpdateError::FileExists.into());
+    }
+    downloaded.push((target.file.clone(), path));
+  }
+
+  Ok(downloaded)
+}
+
+async fn rollback(swapped: &[(PathBuf, PathBuf, PathBuf)]) {
+  for (old, backup, new) in swapped.iter().rev() {
+    if new != old {
+      let _ = fs::remove_file(new).await;
+    }
+    if let Err(err) = fs::rename(backup, old).await {
+      warn!("Failed to restore mod {}: {err}", old.display());
+    }
+  }
+}
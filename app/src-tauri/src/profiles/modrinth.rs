@@ -0,0 +1,199 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, header::USER_AGENT};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::json;
+use tauri::Url;
+use thiserror::Error;
+
+use crate::{
+  path,
+  utils::{download::download_file, zip::safe_path},
+};
+
+const API_URL: &str = "https://api.modrinth.com/v2";
+// modrinth asks for a user agent that identifies the application
+const API_USER_AGENT: &str = concat!("ProfiiDev/proton/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Error, Debug)]
+pub enum ModrinthError {
+  #[error("NoVersionFound")]
+  NoVersionFound,
+  #[error("InvalidFile")]
+  InvalidFile,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModrinthVersion {
+  pub id: String,
+  pub project_id: String,
+  pub version_number: String,
+  pub changelog: Option<String>,
+  pub date_published: DateTime<Utc>,
+  pub files: Vec<ModrinthFile>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModrinthFile {
+  pub url: Url,
+  pub filename: String,
+  pub primary: bool,
+  pub hashes: ModrinthHashes,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ModrinthHashes {
+  pub sha1: String,
+}
+
+impl ModrinthVersion {
+  pub fn primary_file(&self) -> Option<&ModrinthFile> {
+    self
+      .files
+      .iter()
+      .find(|f| f.primary)
+      .or_else(|| self.files.first())
+  }
+}
+
+async fn get<R: DeserializeOwned>(client: &Client, url: Url) -> Result<R> {
+  Ok(
+    client
+      .get(url)
+      .header(USER_AGENT, API_USER_AGENT)
+      .send()
+      .await?
+      .error_for_status()?
+      .json()
+      .await?,
+  )
+}
+
+async fn post<B: Serialize, R: DeserializeOwned>(client: &Client, url: Url, body: &B) -> Result<R> {
+  Ok(
+    client
+      .post(url)
+      .header(USER_AGENT, API_USER_AGENT)
+      .json(body)
+      .send()
+      .await?
+      .error_for_status()?
+      .json()
+      .await?,
+  )
+}
+
+pub async fn version(client: &Client, id: &str) -> Result<ModrinthVersion> {
+  get(client, Url::parse(&format!("{API_URL}/version/{id}"))?).await
+}
+
+pub async fn versions(client: &Client, ids: &[String]) -> Result<Vec<ModrinthVersion>> {
+  let url = Url::parse_with_params(
+    &format!("{API_URL}/versions"),
+    [("ids", serde_json::to_string(ids)?)],
+  )?;
+  get(client, url).await
+}
+
+/// Looks up the versions of files by their sha1 hash, unknown files are missing in the result
+pub async fn versions_from_hashes(
+  client: &Client,
+  hashes: &[String],
+) -> Result<HashMap<String, ModrinthVersion>> {
+  post(
+    client,
+    Url::parse(&format!("{API_URL}/version_files"))?,
+    &json!({ "hashes": hashes, "algorithm": "sha1" }),
+  )
+  .await
+}
+
+/// Finds the newest version matching the filters for each file hash
+pub async fn latest_versions_from_hashes(
+  client: &Client,
+  hashes: &[String],
+  game_version: &str,
+  loaders: &[&str],
+) -> Result<HashMap<String, ModrinthVersion>> {
+  post(
+    client,
+    Url::parse(&format!("{API_URL}/version_files/update"))?,
+    &json!({
+      "hashes": hashes,
+      "algorithm": "sha1",
+      "loaders": loaders,
+      "game_versions": [game_version],
+    }),
+  )
+  .await
+}
+
+/// Lists the versions of a project newest first, `project` can be the id or the slug
+pub async fn project_versions(
+  client: &Client,
+  project: &str,
+  game_version: Option<&str>,
+  loaders: &[&str],
+) -> Result<Vec<ModrinthVersion>> {
+  let mut params = Vec::new();
+  if let Some(game_version) = game_version {
+    params.push(("game_versions", serde_json::to_string(&[game_version])?));
+  }
+  if !loaders.is_empty() {
+    params.push(("loaders", serde_json::to_string(loaders)?));
+  }
+  let url = Url::parse_with_params(&format!("{API_URL}/project/{project}/version"), params)?;
+
+  get(client, url).await
+}
+
+/// Picks the given version or the newest one matching the filters
+pub async fn resolve_version(
+  client: &Client,
+  project: &str,
+  version_id: Option<&str>,
+  game_version: Option<&str>,
+  loaders: &[&str],
+) -> Result<ModrinthVersion> {
+  if let Some(id) = version_id {
+    return version(client, id).await;
+  }
+
+  project_versions(client, project, game_version, loaders)
+    .await?
+    .into_iter()
+    .next()
+    .ok_or(ModrinthError::NoVersionFound.into())
+}
+
+/// Downloads the primary file of the version into `dir` and returns its path
+pub async fn download_primary_file(
+  client: &Client,
+  version: &ModrinthVersion,
+  dir: &Path,
+) -> Result<PathBuf> {
+  let file = version
+    .primary_file()
+    .ok_or(ModrinthError::NoVersionFound)?;
+  // the file name comes from the api and must not escape the target dir
+  let name = safe_path(&file.filename)
+    .filter(|p| p.components().count() == 1)
+    .ok_or(ModrinthError::InvalidFile)?;
+
+  let target = path!(dir, name);
+  download_file(
+    client,
+    &target,
+    file.url.clone(),
+    &file.hashes.sha1,
+    Box::new(|_| {}),
+  )
+  .await?;
+
+  Ok(target)
+}
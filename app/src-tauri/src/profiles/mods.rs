@@ -0,0 +1,829 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use base64::prelude::*;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tokio::fs;
+
+use crate::{
+  path,
+  profiles::{MODS_DIR, bisect::Bisect},
+  utils::{
+    file::{read_parse_file, write_file},
+    zip::{archive_name, read_files_from_zip, read_files_from_zip_data, safe_path},
+  },
+  versions::loader::{LoaderType, util::extract_file_from_zip},
+};
+
+const FABRIC_META: &str = "fabric.mod.json";
+const QUILT_META: &str = "quilt.mod.json";
+const FORGE_META: &str = "META-INF/mods.toml";
+const NEOFORGE_META: &str = "META-INF/neoforge.mods.toml";
+// used by forge before 1.13
+const LEGACY_FORGE_META: &str = "mcmod.info";
+const JAR_MANIFEST: &str = "META-INF/MANIFEST.MF";
+// forge replaces this placeholder with the version from the manifest
+const JAR_VERSION_PLACEHOLDER: &str = "${file.jarVersion}";
+const NEOFORGE_ID: &str = "neoforge";
+// fabric and quilt bundle jars in META-INF/jars, forge and neoforge in META-INF/jarjar
+const NESTED_JAR_DIRS: [&str; 2] = ["META-INF/jars/", "META-INF/jarjar/"];
+const MAX_NESTED_DEPTH: usize = 3;
+// other launchers disable mods the same way, so the loader ignores the file
+const DISABLED_SUFFIX: &str = ".disabled";
+// stored in the profile dir, holds the launcher state of the mods
+const MOD_MANIFEST: &str = "mods.json";
+
+#[derive(Error, Debug)]
+pub enum ModError {
+  #[error("NotFound")]
+  NotFound,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+  Required,
+  Optional,
+  Incompatible,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModDependency {
+  pub id: String,
+  // in the notation of the loader, e.g. `>=1.2` for fabric or `[1.2,)` for forge
+  pub version: Option<String>,
+  pub kind: DependencyKind,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProvidedMod {
+  pub id: String,
+  pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModInfo {
+  // the file name inside the mods folder, ends with `.disabled` if the mod is disabled
+  pub file: String,
+  pub enabled: bool,
+  pub id: String,
+  pub name: String,
+  pub version: String,
+  pub description: Option<String>,
+  pub authors: Vec<String>,
+  // base64 encoded png
+  pub icon: Option<String>,
+  pub dependencies: Vec<ModDependency>,
+  // all loaders the jar contains metadata for
+  pub loaders: Vec<LoaderType>,
+  // the loader whose metadata is shown, decides the notation of the dependency versions
+  pub loader: Option<LoaderType>,
+  // aliases and bundled mods that satisfy dependencies of other mods
+  pub provides: Vec<ProvidedMod>,
+  // the jar was built for a different loader than the one of the profile
+  pub loader_mismatch: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ModManifest {
+  #[serde(default)]
+  pub bisect: Option<Bisect>,
+}
+
+struct ModMeta {
+  id: String,
+  name: Option<String>,
+  version: String,
+  description: Option<String>,
+  authors: Vec<String>,
+  icon: Option<String>,
+  dependencies: Vec<ModDependency>,
+  provides: Vec<ProvidedMod>,
+}
+
+#[derive(Deserialize)]
+struct FabricMod {
+  id: String,
+  version: String,
+  name: Option<String>,
+  description: Option<String>,
+  #[serde(default)]
+  authors: Vec<FabricPerson>,
+  icon: Option<ModIcon>,
+  #[serde(default)]
+  depends: HashMap<String, Value>,
+  #[serde(default)]
+  recommends: HashMap<String, Value>,
+  #[serde(default)]
+  suggests: HashMap<String, Value>,
+  #[serde(default)]
+  breaks: HashMap<String, Value>,
+  #[serde(default)]
+  provides: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FabricPerson {
+  Name(String),
+  Object { name: String },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ModIcon {
+  Path(String),
+  // sizes to paths
+  Sizes(HashMap<String, String>),
+}
+
+#[derive(Deserialize)]
+struct QuiltMod {
+  quilt_loader: QuiltLoader,
+}
+
+#[derive(Deserialize)]
+struct QuiltLoader {
+  id: String,
+  version: String,
+  #[serde(default)]
+  metadata: QuiltMetadata,
+  #[serde(default)]
+  depends: Vec<QuiltDependency>,
+  #[serde(default)]
+  breaks: Vec<QuiltDependency>,
+  #[serde(default)]
+  provides: Vec<QuiltDependency>,
+}
+
+#[derive(Deserialize, Default)]
+struct QuiltMetadata {
+  name: Option<String>,
+  description: Option<String>,
+  // names to roles
+  #[serde(default)]
+  contributors: HashMap<String, Value>,
+  icon: Option<ModIcon>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum QuiltDependency {
+  Id(String),
+  Object {
+    id: String,
+    versions: Option<Value>,
+    #[serde(default)]
+    optional: bool,
+  },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModsToml {
+  #[serde(default)]
+  mods: Vec<TomlMod>,
+  #[serde(default)]
+  dependencies: HashMap<String, Vec<TomlDependency>>,
+  logo_file: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TomlMod {
+  mod_id: String,
+  version: Option<String>,
+  display_name: Option<String>,
+  description: Option<String>,
+  authors: Option<toml::Value>,
+  logo_file: Option<String>,
+}
+
+/// Either a plain list of mods or the list with a format version
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum McModInfo {
+  List(Vec<McMod>),
+  Versioned {
+    #[serde(rename = "modList")]
+    mod_list: Vec<McMod>,
+  },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct McMod {
+  modid: String,
+  name: Option<String>,
+  version: Option<String>,
+  description: Option<String>,
+  #[serde(default, alias = "authors")]
+  author_list: Vec<String>,
+  logo_file: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TomlDependency {
+  mod_id: String,
+  // before forge 1.20.6 and neoforge 20.5 only mandatory was available
+  mandatory: Option<bool>,
+  r#type: Option<String>,
+  version_range: Option<String>,
+}
+
+impl ModIcon {
+  fn path(self) -> Option<String> {
+    match self {
+      ModIcon::Path(path) => Some(path),
+      // prefer the largest icon
+      ModIcon::Sizes(sizes) => sizes
+        .into_iter()
+        .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or_default())
+        .map(|(_, path)| path),
+    }
+  }
+}
+
+/// Fabric and quilt accept a single version or a list of alternatives
+fn version_requirement(value: &Value) -> Option<String> {
+  match value {
+    Value::String(v) => Some(v.clone()),
+    Value::Array(items) => Some(
+      items
+        .iter()
+        .filter_map(Value::as_str)
+        .collect::<Vec<_>>()
+        .join(" || "),
+    ),
+    _ => None,
+  }
+}
+
+impl FabricMod {
+  fn into_meta(self) -> ModMeta {
+    let mut dependencies = Vec::new();
+    for (deps, kind) in [
+      (&self.depends, DependencyKind::Required),
+      (&self.recommends, DependencyKind::Optional),
+      (&self.suggests, DependencyKind::Optional),
+      (&self.breaks, DependencyKind::Incompatible),
+    ] {
+      dependencies.extend(deps.iter().map(|(id, version)| ModDependency {
+        id: id.clone(),
+        version: version_requirement(version),
+        kind,
+      }));
+    }
+
+    let provides = self
+      .provides
+      .into_iter()
+      .map(|id| ProvidedMod {
+        id,
+        version: self.version.clone(),
+      })
+      .collect();
+
+    ModMeta {
+      id: self.id,
+      name: self.name,
+      version: self.version,
+      description: self.description,
+      authors: self
+        .authors
+        .into_iter()
+        .map(|a| match a {
+          FabricPerson::Name(name) | FabricPerson::Object { name } => name,
+        })
+        .collect(),
+      icon: self.icon.and_then(ModIcon::path),
+      dependencies,
+      provides,
+    }
+  }
+}
+
+impl QuiltDependency {
+  fn into_dependency(self, incompatible: bool) -> ModDependency {
+    let (id, version, optional) = match self {
+      QuiltDependency::Id(id) => (id, None, false),
+      QuiltDependency::Object {
+        id,
+        versions,
+        optional,
+      } => (
+        id,
+        versions.as_ref().and_then(version_requirement),
+        optional,
+      ),
+    };
+
+    ModDependency {
+      id,
+      version,
+      kind: match (incompatible, optional) {
+        (true, _) => DependencyKind::Incompatible,
+        (false, true) => DependencyKind::Optional,
+        (false, false) => DependencyKind::Required,
+      },
+    }
+  }
+}
+
+impl QuiltMod {
+  fn into_meta(self) -> ModMeta {
+    let loader = self.quilt_loader;
+    let mut dependencies = loader
+      .depends
+      .into_iter()
+      .map(|d| d.into_dependency(false))
+      .collect::<Vec<_>>();
+    dependencies.extend(loader.breaks.into_iter().map(|d| d.into_dependency(true)));
+    let provides = loader
+      .provides
+      .into_iter()
+      .map(|p| ProvidedMod {
+        id: p.into_dependency(false).id,
+        version: loader.version.clone(),
+      })
+      .collect();
+
+    ModMeta {
+      id: loader.id,
+      name: loader.metadata.name,
+      version: loader.version,
+      description: loader.metadata.description,
+      authors: loader.metadata.contributors.into_keys().collect(),
+      icon: loader.metadata.icon.and_then(ModIcon::path),
+      dependencies,
+      provides,
+    }
+  }
+}
+
+impl ModsToml {
+  /// Only the first mod of the jar is shown, additional ones are usually bundled libraries
+  fn into_meta(mut self, manifest: Option<&[u8]>) -> Option<ModMeta> {
+    if self.mods.is_empty() {
+      return None;
+    }
+    let first = self.mods.remove(0);
+
+    let jar_version = |version: Option<String>| {
+      let version = version.unwrap_or_default();
+      if version == JAR_VERSION_PLACEHOLDER {
+        manifest
+          .and_then(|m| manifest_value(m, "Implementation-Version"))
+          .unwrap_or_default()
+      } else {
+        version
+      }
+    };
+    let version = jar_version(first.version);
+    let provides = self
+      .mods
+      .into_iter()
+      .map(|m| ProvidedMod {
+        id: m.mod_id,
+        version: jar_version(m.version),
+      })
+      .collect();
+
+    let dependencies = self
+      .dependencies
+      .remove(&first.mod_id)
+      .unwrap_or_default()
+      .into_iter()
+      .map(|d| ModDependency {
+        kind: match (d.r#type.as_deref(), d.mandatory) {
+          (Some("required"), _) | (None, Some(true)) => DependencyKind::Required,
+          (Some("incompatible"), _) => DependencyKind::Incompatible,
+          _ => DependencyKind::Optional,
+        },
+        id: d.mod_id,
+        version: d.version_range,
+      })
+      .collect();
+
+    Some(ModMeta {
+      id: first.mod_id,
+      name: first.display_name,
+      version,
+      description: first.description.map(|d| d.trim().to_string()),
+      authors: match first.authors {
+        Some(toml::Value::String(authors)) => authors
+          .split(',')
+          .map(|a| a.trim().to_string())
+          .filter(|a| !a.is_empty())
+          .collect(),
+        Some(toml::Value::Array(authors)) => authors
+          .iter()
+          .filter_map(|a| a.as_str().map(String::from))
+          .collect(),
+        _ => Vec::new(),
+      },
+      icon: first.logo_file.or(self.logo_file),
+      dependencies,
+      provides,
+    })
+  }
+}
+
+impl McModInfo {
+  /// Only the first mod of the jar is shown like for mods.toml
+  fn into_meta(self, manifest: Option<&[u8]>) -> Option<ModMeta> {
+    let mut mods = match self {
+      McModInfo::List(mods) | McModInfo::Versioned { mod_list: mods } => mods,
+    };
+    if mods.is_empty() {
+      return None;
+    }
+    let first = mods.remove(0);
+
+    // unprocessed build placeholders like `${version}` are common
+    let version = |version: Option<String>| match version {
+      Some(version) if !version.contains("${") => version,
+      _ => manifest
+        .and_then(|m| manifest_value(m, "Implementation-Version"))
+        .unwrap_or_default(),
+    };
+
+    Some(ModMeta {
+      version: version(first.version),
+      provides: mods
+        .into_iter()
+        .map(|m| ProvidedMod {
+          id: m.modid,
+          version: version(m.version),
+        })
+        .collect(),
+      id: first.modid,
+      name: first.name,
+      description: first.description.map(|d| d.trim().to_string()),
+      authors: first.author_list,
+      icon: first
+        .logo_file
+        .map(|l| l.trim_start_matches('/').to_string()),
+      // the dependency ids are not consistent with the mod ids, so they would only cause false reports
+      dependencies: Vec::new(),
+    })
+  }
+}
+
+fn manifest_value(manifest: &[u8], key: &str) -> Option<String> {
+  String::from_utf8_lossy(manifest).lines().find_map(|line| {
+    let (k, v) = line.split_once(':')?;
+    (k.trim() == key).then(|| v.trim().to_string())
+  })
+}
+
+pub fn mods_dir(profile_dir: &Path) -> PathBuf {
+  path!(profile_dir, MODS_DIR)
+}
+
+impl ModManifest {
+  pub async fn load(profile_dir: &Path) -> Result<Self> {
+    let path = path!(profile_dir, MOD_MANIFEST);
+    if !path.exists() {
+      return Ok(Self::default());
+    }
+    read_parse_file(&path).await
+  }
+
+  pub async fn save(&self, profile_dir: &Path) -> Result<()> {
+    write_file(&path!(profile_dir, MOD_MANIFEST), self).await
+  }
+}
+
+/// Returns true if a mod for `mod_loader` can be loaded by `profile_loader`
+fn loader_supports(profile_loader: LoaderType, mod_loader: LoaderType) -> bool {
+  profile_loader == mod_loader
+    // quilt can load fabric mods
+    || (profile_loader == LoaderType::Quilt && mod_loader == LoaderType::Fabric)
+    // legacy fabric and babric mods use the fabric metadata
+    || (matches!(profile_loader, LoaderType::LegacyFabric | LoaderType::Babric)
+      && mod_loader == LoaderType::Fabric)
+    // custom loaders are forks of one of the known loaders
+    || (profile_loader == LoaderType::CustomForge
+      && matches!(mod_loader, LoaderType::Forge | LoaderType::NeoForge))
+    || (profile_loader == LoaderType::CustomFabric && mod_loader == LoaderType::Fabric)
+}
+
+pub fn is_enabled(file: &str) -> bool {
+  !file.ends_with(DISABLED_SUFFIX)
+}
+
+/// The file name of the jar while it is enabled
+pub fn enabled_name(file: &str) -> &str {
+  file.strip_suffix(DISABLED_SUFFIX).unwrap_or(file)
+}
+
+/// Lists the file names of all jars in the mods folder, including disabled ones
+pub async fn mod_files(profile_dir: &Path) -> Result<Vec<String>> {
+  let dir = mods_dir(profile_dir);
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut files = Vec::new();
+  let mut entries = fs::read_dir(&dir).await?;
+  while let Some(entry) = entries.next_entry().await? {
+    let path = entry.path();
+    let Some(file) = entry.file_name().to_str().map(String::from) else {
+      continue;
+    };
+    if path.is_file() && enabled_name(&file).to_lowercase().ends_with(".jar") {
+      files.push(file);
+    }
+  }
+
+  Ok(files)
+}
+
+/// Reads the metadata of every jar in the mods folder.
+/// Jars without known metadata are listed with their file name as id.
+pub async fn scan_mods(profile_dir: &Path, loader: LoaderType) -> Result<Vec<ModInfo>> {
+  let dir = mods_dir(profile_dir);
+
+  let mut mods = Vec::new();
+  for file in mod_files(profile_dir).await? {
+    let path = path!(&dir, &file);
+    match read_mod(&path, &file, loader).await {
+      Ok(info) => mods.push(info),
+      Err(err) => debug!("Failed to read mod {}: {err:#}", path.display()),
+    }
+  }
+  mods.sort_by_key(|m| m.name.to_lowercase());
+
+  Ok(mods)
+}
+
+/// Enables or disables multiple mods and returns their new file names.
+/// If one of them fails, the already renamed mods are renamed back.
+pub async fn set_mods_enabled(
+  profile_dir: &Path,
+  files: &[String],
+  enabled: bool,
+) -> Result<Vec<String>> {
+  let mut names = Vec::new();
+  // only the mods that were actually renamed are rolled back
+  let mut renamed = Vec::new();
+  for file in files {
+    let name = enabled_name(file);
+    let target = if enabled {
+      name.to_string()
+    } else {
+      format!("{name}{DISABLED_SUFFIX}")
+    };
+    let unchanged = path!(mods_dir(profile_dir), &target).exists();
+
+    match set_mod_enabled(profile_dir, file, enabled).await {
+      Ok(name) => {
+        if !unchanged {
+          renamed.push(name.clone());
+        }
+        names.push(name);
+      }
+      Err(err) => {
+        for name in &renamed {
+          if let Err(err) = set_mod_enabled(profile_dir, name, !enabled).await {
+            debug!("Failed to roll back mod {name}: {err:#}");
+          }
+        }
+        return Err(err);
+      }
+    }
+  }
+
+  Ok(names)
+}
+
+/// Enables or disables a mod by renaming it, `file` can be the enabled or disabled name.
+/// Returns the new file name.
+pub async fn set_mod_enabled(profile_dir: &Path, file: &str, enabled: bool) -> Result<String> {
+  let name = enabled_name(file);
+  // the name comes from the frontend and must stay inside the mods folder
+  if Path::new(name).components().count() != 1 || !name.to_lowercase().ends_with(".jar") {
+    return Err(ModError::NotFound.into());
+  }
+
+  let dir = mods_dir(profile_dir);
+  let enabled_path = path!(&dir, name);
+  let disabled_path = path!(&dir, format!("{name}{DISABLED_SUFFIX}"));
+  let (from, to) = if enabled {
+    (&disabled_path, &enabled_path)
+  } else {
+    (&enabled_path, &disabled_path)
+  };
+
+  if !to.exists() {
+    if !from.is_file() {
+      return Err(ModError::NotFound.into());
+    }
+    fs::rename(from, to).await?;
+  }
+
+  Ok(
+    to.file_name()
+      .and_then(|n| n.to_str())
+      .unwrap_or_default()
+      .to_string(),
+  )
+}
+
+/// The loaders modrinth should search versions for
+pub fn modrinth_loaders(loader: LoaderType) -> &'static [&'static str] {
+  match loader {
+    LoaderType::Vanilla | LoaderType::LiteLoader | LoaderType::OptiFine => &[],
+    LoaderType::Fabric => &["fabric"],
+    // quilt can load fabric mods
+    LoaderType::Quilt => &["quilt", "fabric"],
+    LoaderType::Forge => &["forge"],
+    LoaderType::NeoForge => &["neoforge"],
+    // mods for these old versions are often only tagged as fabric
+    LoaderType::LegacyFabric => &["legacy-fabric", "fabric"],
+    LoaderType::Babric => &["babric", "fabric"],
+    LoaderType::CustomForge => &["forge", "neoforge"],
+    LoaderType::CustomFabric => &["fabric"],
+  }
+}
+
+fn is_metadata_file(name: &str) -> bool {
+  [
+    FABRIC_META,
+    QUILT_META,
+    FORGE_META,
+    NEOFORGE_META,
+    LEGACY_FORGE_META,
+    JAR_MANIFEST,
+  ]
+  .contains(&name)
+}
+
+fn is_nested_jar(name: &str) -> bool {
+  NESTED_JAR_DIRS.iter().any(|dir| name.starts_with(dir)) && name.ends_with(".jar")
+}
+
+/// Parses all known metadata files of a jar, `file` is only used for logging
+fn parse_metadata(files: &HashMap<String, Vec<u8>>, file: &str) -> Vec<(LoaderType, ModMeta)> {
+  let mut metas = Vec::new();
+  if let Some(data) = files.get(FABRIC_META) {
+    match serde_json::from_slice::<FabricMod>(data) {
+      Ok(meta) => metas.push((LoaderType::Fabric, meta.into_meta())),
+      Err(err) => debug!("Invalid {FABRIC_META} in {file}: {err}"),
+    }
+  }
+  if let Some(data) = files.get(QUILT_META) {
+    match serde_json::from_slice::<QuiltMod>(data) {
+      Ok(meta) => metas.push((LoaderType::Quilt, meta.into_meta())),
+      Err(err) => debug!("Invalid {QUILT_META} in {file}: {err}"),
+    }
+  }
+  for (name, loader) in [
+    (NEOFORGE_META, LoaderType::NeoForge),
+    (FORGE_META, LoaderType::Forge),
+  ] {
+    let Some(data) = files.get(name) else {
+      continue;
+    };
+    let meta = toml::from_str::<ModsToml>(&String::from_utf8_lossy(data))
+      .map(|toml| toml.into_meta(files.get(JAR_MANIFEST).map(Vec::as_slice)));
+    match meta {
+      Ok(Some(meta)) => {
+        // early neoforge versions still used the forge file name
+        let loader = if meta.dependencies.iter().any(|d| d.id == NEOFORGE_ID) {
+          LoaderType::NeoForge
+        } else {
+          loader
+        };
+        metas.push((loader, meta));
+      }
+      Ok(None) => (),
+      Err(err) => debug!("Invalid {name} in {file}: {err}"),
+    }
+  }
+  if let Some(data) = files.get(LEGACY_FORGE_META)
+    && !files.contains_key(FORGE_META)
+  {
+    let meta = serde_json::from_slice::<McModInfo>(data)
+      .map(|info| info.into_meta(files.get(JAR_MANIFEST).map(Vec::as_slice)));
+    match meta {
+      Ok(Some(meta)) => metas.push((LoaderType::Forge, meta)),
+      Ok(None) => (),
+      Err(err) => debug!("Invalid {LEGACY_FORGE_META} in {file}: {err}"),
+    }
+  }
+
+  metas
+}
+
+/// Collects the mods bundled inside the jar, including jars nested in bundled jars
+async fn nested_mods(files: HashMap<String, Vec<u8>>, file: &str) -> Vec<ProvidedMod> {
+  let mut queue = files
+    .into_iter()
+    .filter(|(name, _)| is_nested_jar(name))
+    .map(|(_, data)| (data, 1))
+    .collect::<Vec<_>>();
+
+  let mut provided = Vec::new();
+  while let Some((data, depth)) = queue.pop() {
+    let nested = match read_files_from_zip_data(data, |name| {
+      is_metadata_file(name) || (depth < MAX_NESTED_DEPTH && is_nested_jar(name))
+    })
+    .await
+    {
+      Ok(nested) => nested,
+      Err(err) => {
+        debug!("Failed to read nested jar in {file}: {err:#}");
+        continue;
+      }
+    };
+
+    for (_, meta) in parse_metadata(&nested, file) {
+      provided.extend(meta.provides);
+      provided.push(ProvidedMod {
+        id: meta.id,
+        version: meta.version,
+      });
+    }
+    queue.extend(
+      nested
+        .into_iter()
+        .filter(|(name, _)| is_nested_jar(name))
+        .map(|(_, data)| (data, depth + 1)),
+    );
+  }
+
+  provided
+}
+
+pub async fn read_mod(path: &Path, file: &str, profile_loader: LoaderType) -> Result<ModInfo> {
+  let files =
+    read_files_from_zip(path, |name| is_metadata_file(name) || is_nested_jar(name)).await?;
+  let mut metas = parse_metadata(&files, file);
+
+  let loaders = metas.iter().map(|(l, _)| *l).collect::<Vec<_>>();
+  let loader_mismatch =
+    !loaders.is_empty() && !loaders.iter().any(|l| loader_supports(profile_loader, *l));
+
+  let enabled = is_enabled(file);
+  if metas.is_empty() {
+    let name = enabled_name(file);
+    let stem = name[..name.len() - ".jar".len()].to_string();
+    return Ok(ModInfo {
+      file: file.to_string(),
+      enabled,
+      id: stem.clone(),
+      name: stem,
+      version: String::new(),
+      description: None,
+      authors: Vec::new(),
+      icon: None,
+      dependencies: Vec::new(),
+      loaders,
+      loader: None,
+      provides: Vec::new(),
+      loader_mismatch,
+    });
+  }
+
+  // prefer the metadata the profile will actually load
+  let index = metas
+    .iter()
+    .position(|(l, _)| loader_supports(profile_loader, *l))
+    .unwrap_or_default();
+  let (loader, meta) = metas.swap_remove(index);
+  let mut provides = meta.provides;
+  provides.extend(nested_mods(files, file).await);
+
+  let icon = match meta.icon.as_deref().and_then(safe_path) {
+    Some(icon) => extract_file_from_zip(path, &archive_name(&icon))
+      .await
+      .ok()
+      .map(|icon| BASE64_STANDARD.encode(icon)),
+    None => None,
+  };
+
+  Ok(ModInfo {
+    file: file.to_string(),
+    enabled,
+    name: meta.name.unwrap_or_else(|| meta.id.clone()),
+    id: meta.id,
+    version: meta.version,
+    description: meta.description,
+    authors: meta.authors,
+    icon,
+    dependencies: meta.dependencies,
+    loaders,
+    loader: Some(loader),
+    provides,
+    loader_mismatch,
+  })
+}
@@ -0,0 +1,583 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use async_zip::tokio::read::fs::ZipFileReader;
+use base64::prelude::*;
+use chrono::{DateTime, TimeZone, Utc};
+use log::debug;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tokio::fs;
+
+use crate::{
+  path,
+  profiles::{
+    RESOURCE_PACKS_DIR, SHADER_PACKS_DIR,
+    modrinth::{download_primary_file, resolve_version},
+  },
+  utils::{
+    chat::{component_text, strip_formatting},
+    dir::{copy_dir, list_files_in_dir},
+    file::read_parse_file,
+    log::ResultLogExt,
+    zip::safe_path,
+  },
+  versions::{
+    loader::util::extract_file_from_zip, meta::minecraft::JarVersion, paths::MCVersionPath,
+  },
+};
+
+const PACK_META: &str = "pack.mcmeta";
+const PACK_ICON: &str = "pack.png";
+const SHADERS_FOLDER: &str = "shaders/";
+const OPTIONS_FILE: &str = "options.txt";
+const RESOURCE_PACKS_KEY: &str = "resourcePacks";
+const INCOMPATIBLE_PACKS_KEY: &str = "incompatibleResourcePacks";
+// since 1.13 packs from the resourcepacks folder are referenced with this prefix
+const FILE_PACK_PREFIX: &str = "file/";
+const VANILLA_PACK: &str = "vanilla";
+// release of 17w43a, the first snapshot with the 1.13 pack ids
+const PACK_PREFIX_RELEASE: (i32, u32, u32) = (2017, 10, 25);
+const IRIS_CONFIG: &str = "config/iris.properties";
+const IRIS_ENABLED_KEY: &str = "enableShaders";
+const OPTIFINE_CONFIG: &str = "optionsshaders.txt";
+const OPTIFINE_DISABLED: &str = "OFF";
+const SHADER_PACK_KEY: &str = "shaderPack";
+const MODRINTH_RESOURCE_LOADER: &str = "minecraft";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PackType {
+  Resource,
+  Shader,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PackCompatibility {
+  Compatible,
+  // made for an older game version
+  TooOld,
+  // made for a newer game version
+  TooNew,
+  Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PackInfo {
+  // the file or folder name inside the pack folder
+  pub id: String,
+  pub r#type: PackType,
+  pub description: Option<String>,
+  pub pack_format: Option<i32>,
+  pub compatibility: PackCompatibility,
+  pub enabled: bool,
+  // in bytes
+  pub size: u64,
+  // base64 encoded png
+  pub icon: Option<String>,
+}
+
+/// The release time in the version json
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionRelease {
+  release_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Error, Debug)]
+pub enum PackError {
+  #[error("NotFound")]
+  NotFound,
+  #[error("InvalidPack")]
+  InvalidPack,
+  #[error("AlreadyExists")]
+  AlreadyExists,
+}
+
+#[derive(Deserialize)]
+struct PackMeta {
+  pack: PackSection,
+}
+
+#[derive(Deserialize)]
+struct PackSection {
+  pack_format: Option<i32>,
+  description: Option<Value>,
+  // since 23w31a
+  supported_formats: Option<Value>,
+  // since 25w31a, replaces all other format fields
+  min_format: Option<Value>,
+  max_format: Option<Value>,
+}
+
+impl PackType {
+  fn dir(&self, profile_dir: &Path) -> PathBuf {
+    match self {
+      PackType::Resource => path!(profile_dir, RESOURCE_PACKS_DIR),
+      PackType::Shader => path!(profile_dir, SHADER_PACKS_DIR),
+    }
+  }
+}
+
+impl PackSection {
+  /// The range of resource pack formats the pack supports
+  fn format_range(&self) -> Option<(i32, i32)> {
+    if let (Some(min), Some(max)) = (&self.min_format, &self.max_format) {
+      return Some((format_major(min)?, format_major(max)?));
+    }
+
+    match &self.supported_formats {
+      Some(Value::Number(v)) => v.as_i64().map(|v| (v as i32, v as i32)),
+      Some(Value::Array(range)) => match range.as_slice() {
+        [min, max] => Some((min.as_i64()? as i32, max.as_i64()? as i32)),
+        _ => None,
+      },
+      Some(Value::Object(range)) => Some((
+        range.get("min_inclusive")?.as_i64()? as i32,
+        range.get("max_inclusive")?.as_i64()? as i32,
+      )),
+      _ => self.pack_format.map(|f| (f, f)),
+    }
+  }
+}
+
+/// Formats are either a single number or `[major, minor]`
+fn format_major(value: &Value) -> Option<i32> {
+  match value {
+    Value::Array(parts) => parts.first()?.as_i64().map(|v| v as i32),
+    v => v.as_i64().map(|v| v as i32),
+  }
+}
+
+/// The resource pack format of the game version,
+/// an unreadable version.json means the compatibility is unknown
+pub async fn game_pack_format(version_path: &MCVersionPath) -> Option<i32> {
+  let jar_version = JarVersion::read(version_path).await.log().ok()?;
+  if let Some(pack_version) = jar_version.and_then(|v| v.pack_version) {
+    return Some(pack_version.resource());
+  }
+
+  // versions before 1.14 have no version.json
+  let mut parts = version_path.version().split('.');
+  let (Some("1"), Some(minor)) = (parts.next(), parts.next().and_then(|m| m.parse().ok())) else {
+    return None;
+  };
+  match minor {
+    6..=8 => Some(1),
+    9..=10 => Some(2),
+    11..=12 => Some(3),
+    13..=14 => Some(4),
+    _ => None,
+  }
+}
+
+pub async fn list_packs(
+  profile_dir: &Path,
+  r#type: PackType,
+  version_path: &MCVersionPath,
+) -> Result<Vec<PackInfo>> {
+  let dir = r#type.dir(profile_dir);
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let game_format = match r#type {
+    PackType::Resource => game_pack_format(version_path).await,
+    PackType::Shader => None,
+  };
+  let enabled = enabled_packs(profile_dir, r#type, version_path).await?;
+
+  let mut packs = Vec::new();
+  let mut entries = fs::read_dir(&dir).await?;
+  while let Some(entry) = entries.next_entry().await? {
+    let path = entry.path();
+    let Some(id) = entry.file_name().to_str().map(String::from) else {
+      continue;
+    };
+    if !path.is_dir() && !is_zip(&path) {
+      continue;
+    }
+
+    match read_pack(&path, &id, r#type, game_format).await {
+      Ok(mut pack) => {
+        pack.enabled = enabled.contains(&id);
+        packs.push(pack);
+      }
+      Err(err) => debug!("Failed to read pack {}: {err:#}", path.display()),
+    }
+  }
+  packs.sort_by_key(|p| p.id.to_lowercase());
+
+  Ok(packs)
+}
+
+async fn read_pack(
+  path: &Path,
+  id: &str,
+  r#type: PackType,
+  game_format: Option<i32>,
+) -> Result<PackInfo> {
+  let size = if path.is_dir() {
+    let mut size = 0;
+    for file in list_files_in_dir(path).await? {
+      size += fs::metadata(path!(path, file)).await?.len();
+    }
+    size
+  } else {
+    fs::metadata(path).await?.len()
+  };
+
+  let meta = match r#type {
+    PackType::Resource => read_pack_file(path, PACK_META)
+      .await
+      .and_then(|data| serde_json::from_slice::<PackMeta>(&data).ok()),
+    PackType::Shader => None,
+  };
+  let compatibility = match (
+    game_format,
+    meta.as_ref().and_then(|m| m.pack.format_range()),
+  ) {
+    (Some(game), Some((min, _))) if game < min => PackCompatibility::TooNew,
+    (Some(game), Some((_, max))) if game > max => PackCompatibility::TooOld,
+    (Some(_), Some(_)) => PackCompatibility::Compatible,
+    _ => PackCompatibility::Unknown,
+  };
+
+  Ok(PackInfo {
+    id: id.to_string(),
+    r#type,
+    description: meta
+      .as_ref()
+      .and_then(|m| m.pack.description.as_ref())
+      .map(|d| strip_formatting(&component_text(d))),
+    pack_format: meta.as_ref().and_then(|m| m.pack.pack_format),
+    compatibility,
+    enabled: false,
+    size,
+    icon: read_pack_file(path, PACK_ICON)
+      .await
+      .map(|icon| BASE64_STANDARD.encode(icon)),
+  })
+}
+
+async fn read_pack_file(path: &Path, name: &str) -> Option<Vec<u8>> {
+  if path.is_dir() {
+    fs::read(path!(path, name)).await.ok()
+  } else {
+    extract_file_from_zip(path, name).await.ok()
+  }
+}
+
+fn is_zip(path: &Path) -> bool {
+  path
+    .extension()
+    .is_some_and(|e| e.eq_ignore_ascii_case("zip"))
+}
+
+fn pack_path(profile_dir: &Path, r#type: PackType, id: &str) -> Result<PathBuf> {
+  let relative = safe_path(id)
+    .filter(|p| p.components().count() == 1)
+    .ok_or(PackError::NotFound)?;
+  let path = path!(r#type.dir(profile_dir), relative);
+  if !path.exists() {
+    return Err(PackError::NotFound.into());
+  }
+
+  Ok(path)
+}
+
+/// Before 1.13 the options store plain file names and the vanilla pack is not listed
+async fn legacy_pack_ids(version_path: &MCVersionPath) -> Result<bool> {
+  let release = read_parse_file::<VersionRelease>(&version_path.version_manifest())
+    .await
+    .ok()
+    .and_then(|v| v.release_time);
+  if let Some(release) = release {
+    let (year, month, day) = PACK_PREFIX_RELEASE;
+    let prefix_release = Utc
+      .with_ymd_and_hms(year, month, day, 0, 0, 0)
+      .single()
+      .unwrap_or_default();
+    return Ok(release < prefix_release);
+  }
+
+  // custom versions do not need a release time
+  Ok(game_pack_format(version_path).await.is_some_and(|f| f < 4))
+}
+
+/// The id of a pack from the resourcepacks folder in the options
+fn pack_option_id(id: &str, legacy: bool) -> String {
+  if legacy {
+    id.to_string()
+  } else {
+    format!("{FILE_PACK_PREFIX}{id}")
+  }
+}
+
+/// The ids of the enabled packs, only one shader pack can be enabled at a time
+async fn enabled_packs(
+  profile_dir: &Path,
+  r#type: PackType,
+  version_path: &MCVersionPath,
+) -> Result<Vec<String>> {
+  match r#type {
+    PackType::Resource => {
+      let legacy = legacy_pack_ids(version_path).await?;
+      Ok(
+        resource_pack_option(profile_dir, RESOURCE_PACKS_KEY)
+          .await?
+          .unwrap_or_default()
+          .iter()
+          .filter_map(|p| {
+            if legacy {
+              Some(p.as_str())
+            } else {
+              p.strip_prefix(FILE_PACK_PREFIX)
+            }
+          })
+          .map(String::from)
+          .collect(),
+      )
+    }
+    PackType::Shader => {
+      let mut enabled = Vec::new();
+      let iris = path!(profile_dir, IRIS_CONFIG);
+      if read_option(&iris, IRIS_ENABLED_KEY, '=').await?.as_deref() != Some("false")
+        && let Some(pack) = read_option(&iris, SHADER_PACK_KEY, '=').await?
+      {
+        enabled.push(pack);
+      }
+      let optifine = path!(profile_dir, OPTIFINE_CONFIG);
+      if let Some(pack) = read_option(&optifine, SHADER_PACK_KEY, '=').await?
+        && pack != OPTIFINE_DISABLED
+      {
+        enabled.push(pack);
+      }
+      Ok(enabled)
+    }
+  }
+}
+
+/// Enables or disables a pack for the next launch.
+/// Resource packs are enabled on top of the already enabled ones.
+pub async fn set_pack_enabled(
+  profile_dir: &Path,
+  r#type: PackType,
+  id: &str,
+  enabled: bool,
+  version_path: &MCVersionPath,
+) -> Result<()> {
+  pack_path(profile_dir, r#type, id)?;
+
+  match r#type {
+    PackType::Resource => {
+      let legacy = legacy_pack_ids(version_path).await?;
+      let name = pack_option_id(id, legacy);
+      let mut packs = resource_pack_option(profile_dir, RESOURCE_PACKS_KEY)
+        .await?
+        .unwrap_or_else(|| {
+          // since 1.13 the game always enables the vanilla pack
+          if legacy {
+            Vec::new()
+          } else {
+            vec![VANILLA_PACK.into()]
+          }
+        });
+      packs.retain(|p| p != &name);
+      // the last pack has the highest priority
+      if enabled {
+        packs.push(name.clone());
+      }
+      set_resource_pack_option(profile_dir, RESOURCE_PACKS_KEY, &packs).await?;
+
+      if !enabled
+        && let Some(mut incompatible) =
+          resource_pack_option(profile_dir, INCOMPATIBLE_PACKS_KEY).await?
+      {
+        incompatible.retain(|p| p != &name);
+        set_resource_pack_option(profile_dir, INCOMPATIBLE_PACKS_KEY, &incompatible).await?;
+      }
+    }
+    PackType::Shader => {
+      let iris = path!(profile_dir, IRIS_CONFIG);
+      let optifine = path!(profile_dir, OPTIFINE_CONFIG);
+      // without any config the shader mod is unknown, iris and its forks are the most common
+      let use_iris = iris.exists() || !optifine.exists();
+
+      if use_iris {
+        if enabled {
+          set_option(&iris, SHADER_PACK_KEY, '=', id).await?;
+          set_option(&iris, IRIS_ENABLED_KEY, '=', "true").await?;
+        } else if read_option(&iris, SHADER_PACK_KEY, '=').await?.as_deref() == Some(id) {
+          set_option(&iris, IRIS_ENABLED_KEY, '=', "false").await?;
+        }
+      }
+      if optifine.exists() {
+        if enabled {
+          set_option(&optifine, SHADER_PACK_KEY, '=', id).await?;
+        } else if read_option(&optifine, SHADER_PACK_KEY, '=')
+          .await?
+          .as_deref()
+          == Some(id)
+        {
+          set_option(&optifine, SHADER_PACK_KEY, '=', OPTIFINE_DISABLED).await?;
+        }
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// Copies a pack zip or folder into the profile and returns its id
+pub async fn import_pack(profile_dir: &Path, r#type: PackType, source: &Path) -> Result<String> {
+  let id = source
+    .file_name()
+    .and_then(|n| n.to_str())
+    .ok_or(PackError::InvalidPack)?
+    .to_string();
+  let target = path!(r#type.dir(profile_dir), &id);
+  if target.exists() {
+    return Err(PackError::AlreadyExists.into());
+  }
+
+  if source.is_dir() {
+    let valid = match r#type {
+      PackType::Resource => path!(source, PACK_META).exists(),
+      PackType::Shader => path!(source, SHADERS_FOLDER).is_dir(),
+    };
+    if !valid {
+      return Err(PackError::InvalidPack.into());
+    }
+    copy_dir(source, &target, |_| true).await?;
+  } else {
+    let zip = ZipFileReader::new(source).await?;
+    let valid = zip.file().entries().iter().any(|e| {
+      let name = e.filename().as_str().unwrap_or_default();
+      match r#type {
+        PackType::Resource => name == PACK_META,
+        PackType::Shader => name.starts_with(SHADERS_FOLDER),
+      }
+    });
+    if !valid {
+      return Err(PackError::InvalidPack.into());
+    }
+    fs::create_dir_all(r#type.dir(profile_dir)).await?;
+    fs::copy(source, &target).await?;
+  }
+  debug!("Imported {:?} pack {id}", r#type);
+
+  Ok(id)
+}
+
+/// Downloads a pack from modrinth and returns its id
+pub async fn install_pack(
+  client: &Client,
+  profile_dir: &Path,
+  r#type: PackType,
+  project: &str,
+  version: Option<&str>,
+  game_version: &str,
+) -> Result<String> {
+  let loaders: &[&str] = match r#type {
+    PackType::Resource => &[MODRINTH_RESOURCE_LOADER],
+    // shaders are tagged with the shader mod they support
+    PackType::Shader => &[],
+  };
+  let version = resolve_version(client, project, version, Some(game_version), loaders).await?;
+  let path = download_primary_file(client, &version, &r#type.dir(profile_dir)).await?;
+  debug!(
+    "Installed {:?} pack {} from modrinth",
+    r#type,
+    path.display()
+  );
+
+  Ok(
+    path
+      .file_name()
+      .and_then(|n| n.to_str())
+      .unwrap_or_default()
+      .to_string(),
+  )
+}
+
+pub async fn remove_pack(
+  profile_dir: &Path,
+  r#type: PackType,
+  id: &str,
+  version_path: &MCVersionPath,
+) -> Result<()> {
+  set_pack_enabled(profile_dir, r#type, id, false, version_path).await?;
+
+  let path = pack_path(profile_dir, r#type, id)?;
+  if path.is_dir() {
+    fs::remove_dir_all(path).await?;
+  } else {
+    fs::remove_file(path).await?;
+  }
+
+  Ok(())
+}
+
+async fn resource_pack_option(profile_dir: &Path, key: &str) -> Result<Option<Vec<String>>> {
+  let Some(value) = read_option(&path!(profile_dir, OPTIONS_FILE), key, ':').await? else {
+    return Ok(None);
+  };
+  Ok(serde_json::from_str(&value).ok())
+}
+
+async fn set_resource_pack_option(profile_dir: &Path, key: &str, packs: &[String]) -> Result<()> {
+  set_option(
+    &path!(profile_dir, OPTIONS_FILE),
+    key,
+    ':',
+    &serde_json::to_string(packs)?,
+  )
+  .await
+}
+
+async fn read_option(path: &Path, key: &str, separator: char) -> Result<Option<String>> {
+  if !path.exists() {
+    return Ok(None);
+  }
+
+  Ok(fs::read_to_string(path).await?.lines().find_map(|line| {
+    let (k, v) = line.split_once(separator)?;
+    (k.trim() == key).then(|| v.trim().to_string())
+  }))
+}
+
+/// Replaces the value of the key and keeps all other lines
+async fn set_option(path: &Path, key: &str, separator: char, value: &str) -> Result<()> {
+  let content = if path.exists() {
+    fs::read_to_string(path).await?
+  } else {
+    String::new()
+  };
+
+  let line = format!("{key}{separator}{value}");
+  let mut found = false;
+  let mut lines = content
+    .lines()
+    .map(|l| match l.split_once(separator) {
+      Some((k, _)) if k.trim() == key => {
+        found = true;
+        line.clone()
+      }
+      _ => l.to_string(),
+    })
+    .collect::<Vec<_>>();
+  if !found {
+    lines.push(line);
+  }
+
+  if let Some(parent) = path.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+  fs::write(path, lines.join("\n") + "\n").await?;
+
+  Ok(())
+}
@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use log::debug;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, Signal, System};
+use tokio::time::sleep;
+
+// the process start time reported by the os and our launch time are not exactly the same
+const START_TIME_TOLERANCE_SECS: i64 = 30;
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn process_alive(pid: u32, launched_at: DateTime<Utc>) -> bool {
+  let pid = Pid::from_u32(pid);
+  let mut system = System::new();
+  system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+
+  // compare the start time so a reused pid is not mistaken for the instance
+  system.process(pid).is_some_and(|process| {
+    process.status() != ProcessStatus::Zombie
+      && (process.start_time() as i64 - launched_at.timestamp()).abs() <= START_TIME_TOLERANCE_SECS
+  })
+}
+
+/// Asks the process tree to shut down and kills it when it is still alive after the timeout.
+/// `fallback` is used to request the shutdown where no terminate signal exists (windows) and
+/// returns false if it could not request it either.
+/// `on_kill` is called right before the kill signal is sent. Returns true if the tree had to be killed.
+pub async fn stop_process_tree(
+  pid: u32,
+  launched_at: DateTime<Utc>,
+  timeout: Duration,
+  fallback: impl FnOnce() -> bool,
+  on_kill: impl FnOnce(),
+) -> bool {
+  // collect the tree up front because children get reparented once the root exits
+  let mut tree = process_tree(pid);
+
+  if signal_tree(&tree, Signal::Term) || fallback() {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+      if !process_alive(pid, launched_at) {
+        debug!("Process {pid} exited after {:?}", start.elapsed());
+        return false;
+      }
+      sleep(STOP_POLL_INTERVAL).await;
+    }
+    debug!("Process {pid} did not exit within {timeout:?}, killing it");
+  } else {
+    debug!("Graceful stop is not supported for process {pid}, killing it");
+  }
+
+  for child in process_tree(pid) {
+    if !tree.contains(&child) {
+      tree.push(child);
+    }
+  }
+  on_kill();
+  signal_tree(&tree, Signal::Kill);
+
+  true
+}
+
+fn process_tree(root: u32) -> Vec<Pid> {
+  let mut system = System::new();
+  system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+
+  let mut tree = vec![Pid::from_u32(root)];
+  let mut i = 0;
+  while i < tree.len() {
+    let parent = tree[i];
+    for (pid, process) in system.processes() {
+      if process.parent() == Some(parent) && !tree.contains(pid) {
+        tree.push(*pid);
+      }
+    }
+    i += 1;
+  }
+
+  tree
+}
+
+/// Returns false if the signal is not supported on this platform
+fn signal_tree(tree: &[Pid], signal: Signal) -> bool {
+  let mut system = System::new();
+  system.refresh_processes_specifics(
+    ProcessesToUpdate::Some(tree),
+    true,
+    ProcessRefreshKind::nothing(),
+  );
+
+  let mut supported = true;
+  // signal children first so wrappers can not restart them
+  for pid in tree.iter().rev() {
+    if let Some(process) = system.process(*pid) {
+      match process.kill_with(signal) {
+        Some(sent) => {
+          if !sent {
+            debug!("Failed to send {signal:?} to process {pid}");
+          }
+        }
+        None => supported = false,
+      }
+    }
+  }
+
+  supported
+}
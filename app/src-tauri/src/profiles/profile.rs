@@ -1,4 +1,8 @@
-use std::{io::Cursor, path::PathBuf};
+use std::{
+  cmp::Ordering,
+  io::Cursor,
+  path::{Path, PathBuf},
+};
 
 use anyhow::Result;
 use base64::prelude::*;
@@ -12,26 +16,69 @@ use uuid::Uuid;
 use crate::{
   path,
   profiles::{
-    PROFILE_CONFIG, PROFILE_DIR, PROFILE_IMAGE, PROFILE_LOGS, SAVES_DIR,
-    config::{Profile, ProfileError, ProfileInfo, QuickPlayInfo, QuickPlayType},
+    CRASH_REPORTS_DIR, GAME_LOGS_DIR, MODS_DIR, PROFILE_CONFIG, PROFILE_DIR, PROFILE_IMAGE,
+    PROFILE_LOGS, PROFILE_SESSIONS, SAVES_DIR, SCREENSHOTS_DIR,
+    backup::BackupSettings,
+    config::{
+      DuplicateOptions, Profile, ProfileError, ProfileFilter, ProfileInfo, ProfileSort,
+      ProfileSortKey, QuickPlayInfo, QuickPlayType,
+    },
+    crash::CrashInfo,
+    sessions::{ProfileStats, load_sessions},
+    stats::RunSummary,
     watcher::watch_profile,
   },
   utils::{
-    dir::list_dirs_in_dir,
+    dir::{copy_dir, list_dirs_in_dir},
     file::{bytes_hash, last_modified_ago, read_parse_file, write_file},
+    ping::ping_server,
+    version::compare_versions,
   },
   versions::{
-    loader::LoaderType,
+    loader::{LoaderType, util::compare_mc_versions},
     paths::{MCVersionPath, QUICK_PLAY},
   },
 };
 
 const DEFAULT_ICON: &[u8] = include_bytes!("../../assets/default_icon.png");
 const ICON_FILE: &str = "icon.png";
-const ICON_BASE_URL: &str = "https://api.mcstatus.io/v2/icon";
 const SERVER_ICON_DIR: &str = "server_icons";
+const FAILED_PING_SUFFIX: &str = "failed";
 
 impl Profile {
+  pub fn new(
+    id: String,
+    name: String,
+    version: String,
+    loader: LoaderType,
+    loader_version: Option<String>,
+  ) -> Self {
+    Profile {
+      id,
+      name,
+      created_at: Utc::now(),
+      last_played: None,
+      last_played_non_quick_play: None,
+      favorite: false,
+      quick_play: Vec::new(),
+      version,
+      loader,
+      loader_version,
+      downloaded: false,
+      use_local_game: false,
+      use_local_jvm: false,
+      game: None,
+      jvm: None,
+      group: None,
+      tags: Vec::new(),
+      sort_index: 0,
+      pinned: false,
+      backup: BackupSettings::default(),
+      pinned_mods: Vec::new(),
+      auto_update_loader: false,
+    }
+  }
+
   pub fn relative_to_data(&self) -> PathBuf {
     path!(PROFILE_DIR, &self.id)
   }
@@ -55,41 +102,15 @@ impl Profile {
           .newest_loader_version_for_mc_version(&version, &version_path)
           .await?,
       )
+    } else if loader.is_custom() {
+      // there is nothing to install a custom loader from until a source is set
+      return Err(ProfileError::InvalidLoaderVersion.into());
     } else {
       None
     };
 
-    let icon = match icon {
-      Some(icon) => {
-        let Some(icon) = image::load_from_memory(icon).ok() else {
-          return Err(ProfileError::InvalidImage.into());
-        };
-
-        let scaled = icon.resize_to_fill(256, 256, FilterType::Lanczos3);
-        let mut cursor = Cursor::new(Vec::new());
-        scaled.write_to(&mut cursor, ImageFormat::Png)?;
-        Some(cursor.into_inner())
-      }
-      None => None,
-    };
-
-    let profile = Profile {
-      id: id.clone(),
-      name,
-      created_at: Utc::now(),
-      last_played: None,
-      last_played_non_quick_play: None,
-      favorite: false,
-      quick_play: Vec::new(),
-      version,
-      loader,
-      loader_version,
-      downloaded: false,
-      use_local_game: false,
-      use_local_jvm: false,
-      game: None,
-      jvm: None,
-    };
+    let icon = icon.map(scale_icon).transpose()?;
+    let profile = Profile::new(id.clone(), name, version, loader, loader_version);
 
     fs::create_dir_all(&path).await?;
 
@@ -109,6 +130,136 @@ impl Profile {
     ))
   }
 
+  pub async fn duplicate(
+    &self,
+    data_dir: &PathBuf,
+    app: &AppHandle,
+    name: String,
+    options: &DuplicateOptions,
+  ) -> Result<(String, ProfileInfo)> {
+    let id = Uuid::new_v4().to_string();
+    let relative_path = path!(PROFILE_DIR, &id);
+    let path = path!(data_dir, &relative_path);
+
+    let mut profile = self.clone();
+    profile.id = id.clone();
+    profile.name = name;
+    profile.created_at = Utc::now();
+    profile.last_played = None;
+    profile.last_played_non_quick_play = None;
+    profile.favorite = false;
+    profile.pinned = false;
+    for quick_play in &mut profile.quick_play {
+      quick_play.favorite = false;
+      quick_play.history = false;
+    }
+    if !options.saves {
+      profile
+        .quick_play
+        .retain(|q| q.r#type != QuickPlayType::Singleplayer);
+    }
+
+    copy_dir(
+      &path!(data_dir, self.relative_to_data()),
+      &path,
+      |relative| {
+        let Some(top) = relative
+          .components()
+          .next()
+          .and_then(|c| c.as_os_str().to_str())
+        else {
+          return true;
+        };
+        match top {
+          // written below with the new id
+          PROFILE_CONFIG => false,
+          SAVES_DIR => options.saves,
+          SCREENSHOTS_DIR => options.screenshots,
+          MODS_DIR => options.mods,
+          PROFILE_LOGS | PROFILE_SESSIONS | GAME_LOGS_DIR | CRASH_REPORTS_DIR => options.logs,
+          _ => true,
+        }
+      },
+    )
+    .await?;
+
+    let stop = watch_profile(path.clone(), id.clone(), app.clone())?;
+    write_file(&path!(&path, PROFILE_CONFIG), &profile).await?;
+
+    Ok((
+      id,
+      ProfileInfo {
+        path: relative_path,
+        watcher: stop,
+      },
+    ))
+  }
+
+  /// Switches to another loader after checking that it supports the game version.
+  /// Without `loader_version` the newest stable one is used.
+  pub async fn switch_loader(
+    &mut self,
+    data_dir: &PathBuf,
+    loader: LoaderType,
+    loader_version: Option<String>,
+  ) -> Result<()> {
+    let loader_version = if let Some(loader) = loader.loader() {
+      let version_path = MCVersionPath::new(data_dir, &self.version);
+      if !loader
+        .supported_versions(&version_path, false)
+        .await?
+        .contains(&self.version)
+      {
+        return Err(ProfileError::UnsupportedVersion.into());
+      }
+
+      match loader_version {
+        Some(loader_version) => {
+          if !loader
+            .loader_versions_for_mc_version(&self.version, &version_path, false)
+            .await?
+            .contains(&loader_version)
+          {
+            return Err(ProfileError::InvalidLoaderVersion.into());
+          }
+          Some(loader_version)
+        }
+        None => Some(
+          loader
+            .newest_loader_version_for_mc_version(&self.version, &version_path)
+            .await?,
+        ),
+      }
+    } else if loader.is_custom() {
+      // custom loaders need the installer or meta url to install from
+      Some(loader_version.ok_or(ProfileError::InvalidLoaderVersion)?)
+    } else {
+      None
+    };
+
+    if loader != self.loader || loader_version != self.loader_version {
+      self.downloaded = false;
+    }
+    self.loader = loader;
+    self.loader_version = loader_version;
+
+    Ok(())
+  }
+
+  /// Returns the newest stable loader version if it is newer than the current one
+  pub async fn loader_update(&self, data_dir: &PathBuf) -> Result<Option<String>> {
+    let (Some(loader), Some(current)) = (self.loader.loader(), &self.loader_version) else {
+      return Ok(None);
+    };
+
+    let version_path = MCVersionPath::new(data_dir, &self.version);
+    let newest = loader
+      .newest_loader_version_for_mc_version(&self.version, &version_path)
+      .await?;
+
+    Ok((compare_versions(&newest, current) == Ordering::Greater).then_some(newest))
+  }
+
   pub async fn update(&self, data_dir: &PathBuf) -> Result<()> {
     write_file(
       &path!(data_dir, self.relative_to_data(), PROFILE_CONFIG),
@@ -213,7 +364,7 @@ impl Profile {
           fs::create_dir_all(&dir).await?;
         }
         let icon_hash = bytes_hash(quick_play.id.as_bytes())?;
-        let icon_path = path!(dir, format!("{}.png", icon_hash));
+        let icon_path = path!(&dir, format!("{}.png", icon_hash));
 
         // if the icon was fetched less than an hour ago, use the cached version
         if let Ok(Some(duration)) = last_modified_ago(&icon_path).await
@@ -221,21 +372,35 @@ impl Profile {
         {
           debug!("Using cached server icon for {}", quick_play.id);
           let icon = fs::read(&icon_path).await?;
-          if icon != DEFAULT_ICON {
-            return Ok(Some(BASE64_STANDARD.encode(icon)));
-          }
+          return Ok((icon != DEFAULT_ICON).then(|| BASE64_STANDARD.encode(icon)));
         }
 
-        debug!("Fetching server icon for {}", quick_play.id);
-        let url = format!("{}/{}", ICON_BASE_URL, quick_play.id);
-        if let Ok(resp) = reqwest::get(&url).await
-          && resp.status().is_success()
-          && let Ok(bytes) = resp.bytes().await
+        // unreachable servers are not pinged again for a few minutes
+        let failed_path = path!(dir, format!("{}.{}", icon_hash, FAILED_PING_SUFFIX));
+        if let Ok(Some(duration)) = last_modified_ago(&failed_path).await
+          && duration < Duration::minutes(5)
         {
-          let icon = bytes.to_vec();
-          std::fs::write(&icon_path, &icon)?;
-          if icon != DEFAULT_ICON {
-            return Ok(Some(BASE64_STANDARD.encode(icon)));
+          debug!("Server {} failed to answer recently", quick_play.id);
+          return Ok(None);
+        }
+
+        debug!("Pinging server {} for its icon", quick_play.id);
+        match ping_server(&quick_play.id).await {
+          Ok(status) => {
+            // servers without a favicon are cached with the default icon to avoid pinging them again
+            let icon = status
+              .favicon
+              .and_then(|f| BASE64_STANDARD.decode(f).ok())
+              .unwrap_or_else(|| DEFAULT_ICON.to_vec());
+            fs::write(&icon_path, &icon).await?;
+            let _ = fs::remove_file(&failed_path).await;
+            if icon != DEFAULT_ICON {
+              return Ok(Some(BASE64_STANDARD.encode(icon)));
+            }
+          }
+          Err(err) => {
+            debug!("Failed to ping server {}: {err:#}", quick_play.id);
+            fs::write(&failed_path, []).await?;
           }
         }
       }
@@ -251,6 +416,54 @@ impl Profile {
   }
 }
 
+impl ProfileFilter {
+  pub fn matches(&self, profile: &Profile) -> bool {
+    if let Some(group) = &self.group
+      && profile.group.as_ref() != Some(group)
+    {
+      return false;
+    }
+    if !self.tags.iter().all(|t| profile.tags.contains(t)) {
+      return false;
+    }
+    if let Some(search) = &self.search {
+      let search = search.to_lowercase();
+      return profile.name.to_lowercase().contains(&search)
+        || profile.version.to_lowercase().contains(&search)
+        || profile
+          .tags
+          .iter()
+          .any(|t| t.to_lowercase().contains(&search));
+    }
+
+    true
+  }
+}
+
+impl ProfileSort {
+  pub fn sort(&self, profiles: &mut [Profile]) {
+    profiles.sort_by(|a, b| {
+      let order = match self.key {
+        ProfileSortKey::Manual => a.sort_index.cmp(&b.sort_index),
+        ProfileSortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        ProfileSortKey::LastPlayed => a.last_played.cmp(&b.last_played),
+        ProfileSortKey::Created => a.created_at.cmp(&b.created_at),
+        ProfileSortKey::Version => compare_mc_versions(&a.version, &b.version),
+        ProfileSortKey::Loader => a.loader.name().cmp(b.loader.name()),
+      }
+      .then_with(|| a.name.cmp(&b.name));
+      let order = if self.descending {
+        order.reverse()
+      } else {
+        order
+      };
+
+      // pinned profiles always stay on top
+      b.pinned.cmp(&a.pinned).then(order)
+    });
+  }
+}
+
 impl ProfileInfo {
   pub fn log_dir(handle: &AppHandle, profile: &str) -> Result<PathBuf> {
     Ok(path!(
@@ -292,26 +505,31 @@ impl ProfileInfo {
   }
 
   pub async fn list_runs(&self, data_dir: &PathBuf) -> Result<Vec<DateTime<Utc>>> {
-    let log_dir = path!(data_dir, &self.path, PROFILE_LOGS);
-    if !log_dir.exists() {
-      return Ok(Vec::new());
-    }
+    run_timestamps(&path!(data_dir, &self.path, PROFILE_LOGS)).await
+  }
 
-    let mut res = Vec::new();
-    let mut stream = fs::read_dir(log_dir).await?;
-    while let Some(entry) = stream.next_entry().await? {
-      if entry.file_type().await?.is_file()
-        && let Some(name) = entry.file_name().to_str()
-      {
-        // replace the last 3 dashes with colons but leave the rest of the name intact
-        let name = name.trim_end_matches(".log").replace("-", ":");
-        if let Ok(date) = DateTime::parse_from_str(&name, "%Y:%m:%dT%H:%M:%S.%f%:z") {
-          res.push(date.to_utc());
-        }
-      }
-    }
+  pub async fn stats(&self, data_dir: &PathBuf) -> Result<ProfileStats> {
+    let profile = self.profile(data_dir).await?;
+    let sessions = load_sessions(&path!(data_dir, &self.path)).await?;
+    Ok(ProfileStats::new(&sessions, &profile.quick_play))
+  }
 
-    Ok(res)
+  pub async fn crash_info(
+    &self,
+    data_dir: &PathBuf,
+    timestamp: DateTime<Utc>,
+  ) -> Result<Option<CrashInfo>> {
+    let log_dir = path!(data_dir, &self.path, PROFILE_LOGS);
+    CrashInfo::load(&log_dir, timestamp).await
+  }
+
+  pub async fn run_summary(
+    &self,
+    data_dir: &PathBuf,
+    timestamp: DateTime<Utc>,
+  ) -> Result<Option<RunSummary>> {
+    let log_dir = path!(data_dir, &self.path, PROFILE_LOGS);
+    RunSummary::load(&log_dir, timestamp).await
   }
 
   pub async fn clear_logs(&self, data_dir: &PathBuf) -> Result<()> {
@@ -332,7 +550,7 @@ impl ProfileInfo {
       return Ok(Vec::new());
     }
 
-    let log_file = log_dir.join(format!("{}.log", timestamp.to_rfc3339().replace(":", "-")));
+    let log_file = run_log_file(&log_dir, timestamp);
     println!("Log file path: {:?}", log_file.to_str());
     if !log_file.exists() {
       return Ok(Vec::new());
@@ -342,3 +560,57 @@ impl ProfileInfo {
     Ok(content.lines().map(String::from).collect())
   }
 }
+
+pub fn scale_icon(icon: &[u8]) -> Result<Vec<u8>> {
+  let Some(icon) = image::load_from_memory(icon).ok() else {
+    return Err(ProfileError::InvalidImage.into());
+  };
+
+  let scaled = icon.resize_to_fill(256, 256, FilterType::Lanczos3);
+  let mut cursor = Cursor::new(Vec::new());
+  scaled.write_to(&mut cursor, ImageFormat::Png)?;
+  Ok(cursor.into_inner())
+}
+
+pub async fn run_timestamps(log_dir: &Path) -> Result<Vec<DateTime<Utc>>> {
+  if !log_dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut res = Vec::new();
+  let mut stream = fs::read_dir(log_dir).await?;
+  while let Some(entry) = stream.next_entry().await? {
+    if entry.file_type().await?.is_file()
+      && let Some(name) = entry.file_name().to_str()
+      && let Some(date) = parse_timestamp_file_stem(name.trim_end_matches(".log"))
+    {
+      res.push(date);
+    }
+  }
+
+  Ok(res)
+}
+
+pub fn timestamp_file_stem(timestamp: DateTime<Utc>) -> String {
+  timestamp.to_rfc3339().replace(":", "-")
+}
+
+pub fn parse_timestamp_file_stem(stem: &str) -> Option<DateTime<Utc>> {
+  // replace the last 3 dashes with colons but leave the rest of the name intact
+  let stem = stem.replace("-", ":");
+  DateTime::parse_from_str(&stem, "%Y:%m:%dT%H:%M:%S.%f%:z")
+    .ok()
+    .map(|date| date.to_utc())
+}
+
+pub fn run_log_file(log_dir: &Path, timestamp: DateTime<Utc>) -> PathBuf {
+  log_dir.join(format!("{}.log", timestamp_file_stem(timestamp)))
+}
+
+pub fn run_summary_file(log_dir: &Path, timestamp: DateTime<Utc>) -> PathBuf {
+  log_dir.join(format!("{}.summary.json", timestamp_file_stem(timestamp)))
+}
+
+pub fn crash_file(log_dir: &Path, timestamp: DateTime<Utc>) -> PathBuf {
+  log_dir.join(format!("{}.crash.json", timestamp_file_stem(timestamp)))
+}
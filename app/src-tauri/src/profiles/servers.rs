@@ -0,0 +1,153 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::fs;
+
+use crate::{
+  path,
+  utils::nbt::{Tag, read_nbt, write_nbt},
+};
+
+const SERVERS_FILE: &str = "servers.dat";
+const SERVERS_TMP_FILE: &str = "servers.dat_tmp";
+const SERVERS_KEY: &str = "servers";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServerEntry {
+  pub name: String,
+  // the address is also used as the quick play id
+  pub ip: String,
+  // base64 encoded png, updated by the game when it pings the server
+  pub icon: Option<String>,
+  // none means the game asks before downloading the resource pack
+  pub accept_textures: Option<bool>,
+  pub hidden: bool,
+}
+
+#[derive(Error, Debug)]
+pub enum ServerError {
+  #[error("NotFound")]
+  NotFound,
+  #[error("InvalidServers")]
+  InvalidServers,
+}
+
+impl ServerEntry {
+  fn from_tag(tag: &Tag) -> Self {
+    ServerEntry {
+      name: tag
+        .get("name")
+        .and_then(Tag::as_str)
+        .unwrap_or_default()
+        .to_string(),
+      ip: tag
+        .get("ip")
+        .and_then(Tag::as_str)
+        .unwrap_or_default()
+        .to_string(),
+      icon: tag.get("icon").and_then(Tag::as_str).map(str::to_string),
+      accept_textures: tag
+        .get("acceptTextures")
+        .and_then(Tag::as_i64)
+        .map(|v| v != 0),
+      hidden: tag.get("hidden").and_then(Tag::as_i64).unwrap_or_default() != 0,
+    }
+  }
+
+  /// Writes the entry into the compound and keeps all other keys the game may have stored
+  fn write_to(&self, map: &mut HashMap<String, Tag>) {
+    map.insert("name".into(), Tag::String(self.name.clone()));
+    map.insert("ip".into(), Tag::String(self.ip.clone()));
+    match &self.icon {
+      Some(icon) => map.insert("icon".into(), Tag::String(icon.clone())),
+      None => map.remove("icon"),
+    };
+    match self.accept_textures {
+      Some(accept) => map.insert("acceptTextures".into(), Tag::Byte(accept as i8)),
+      None => map.remove("acceptTextures"),
+    };
+    map.insert("hidden".into(), Tag::Byte(self.hidden as i8));
+  }
+}
+
+pub async fn list_servers(profile_dir: &Path) -> Result<Vec<ServerEntry>> {
+  Ok(
+    read_servers(profile_dir)
+      .await?
+      .iter()
+      .map(ServerEntry::from_tag)
+      .collect(),
+  )
+}
+
+pub async fn add_server(profile_dir: &Path, server: &ServerEntry) -> Result<()> {
+  let mut servers = read_servers(profile_dir).await?;
+  let mut map = HashMap::new();
+  server.write_to(&mut map);
+  servers.push(Tag::Compound(map));
+
+  write_servers(profile_dir, servers).await
+}
+
+pub async fn update_server(profile_dir: &Path, index: usize, server: &ServerEntry) -> Result<()> {
+  let mut servers = read_servers(profile_dir).await?;
+  let Some(Tag::Compound(map)) = servers.get_mut(index) else {
+    return Err(ServerError::NotFound.into());
+  };
+  server.write_to(map);
+
+  write_servers(profile_dir, servers).await
+}
+
+pub async fn move_server(profile_dir: &Path, from: usize, to: usize) -> Result<()> {
+  let mut servers = read_servers(profile_dir).await?;
+  if from >= servers.len() || to >= servers.len() {
+    return Err(ServerError::NotFound.into());
+  }
+  let server = servers.remove(from);
+  servers.insert(to, server);
+
+  write_servers(profile_dir, servers).await
+}
+
+pub async fn remove_server(profile_dir: &Path, index: usize) -> Result<()> {
+  let mut servers = read_servers(profile_dir).await?;
+  if index >= servers.len() {
+    return Err(ServerError::NotFound.into());
+  }
+  servers.remove(index);
+
+  write_servers(profile_dir, servers).await
+}
+
+async fn read_servers(profile_dir: &Path) -> Result<Vec<Tag>> {
+  let path = path!(profile_dir, SERVERS_FILE);
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+
+  let (_, root) = read_nbt(&fs::read(&path).await?)?;
+  let Some(servers) = root.get(SERVERS_KEY) else {
+    return Ok(Vec::new());
+  };
+  let servers = servers.as_list().ok_or(ServerError::InvalidServers)?;
+  if servers.iter().any(|s| !matches!(s, Tag::Compound(_))) {
+    return Err(ServerError::InvalidServers.into());
+  }
+
+  Ok(servers.clone())
+}
+
+async fn write_servers(profile_dir: &Path, servers: Vec<Tag>) -> Result<()> {
+  let root = Tag::Compound(HashMap::from([(SERVERS_KEY.into(), Tag::List(servers))]));
+  let data = write_nbt("", &root)?;
+
+  // the game reads the file while running, so it should never see a partial write
+  let tmp = path!(profile_dir, SERVERS_TMP_FILE);
+  fs::write(&tmp, data).await?;
+  fs::rename(&tmp, path!(profile_dir, SERVERS_FILE)).await?;
+
+  Ok(())
+}
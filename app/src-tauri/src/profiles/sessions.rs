@@ -0,0 +1,197 @@
+use std::{
+  collections::HashMap,
+  path::{Path, PathBuf},
+  sync::{Arc, LazyLock, Mutex as StdMutex},
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::{fs, sync::Mutex};
+
+use crate::{
+  path,
+  profiles::{
+    PROFILE_LOGS, PROFILE_SESSIONS,
+    config::{QuickPlayInfo, QuickPlayType},
+    profile::{crash_file, run_log_file, run_timestamps},
+    stats::RunSummary,
+  },
+  utils::file::{read_parse_file, write_file},
+};
+
+// instances of the same profile can exit at the same time, so the session file of a profile
+// is only read and written by one of them at a time
+static SESSION_LOCKS: LazyLock<StdMutex<HashMap<PathBuf, Arc<Mutex<()>>>>> =
+  LazyLock::new(Default::default);
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SessionTarget {
+  pub id: String,
+  pub r#type: QuickPlayType,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Session {
+  pub launched_at: DateTime<Utc>,
+  pub ended_at: DateTime<Utc>,
+  pub quick_play: Option<SessionTarget>,
+  pub crashed: bool,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct PlayStats {
+  // all durations in seconds
+  pub total_playtime: i64,
+  pub session_count: usize,
+  pub average_session: i64,
+  pub crash_count: usize,
+  pub last_session: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct QuickPlayStats {
+  pub id: String,
+  pub name: Option<String>,
+  pub r#type: QuickPlayType,
+  pub stats: PlayStats,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ProfileStats {
+  pub total: PlayStats,
+  pub quick_play: Vec<QuickPlayStats>,
+}
+
+impl From<&QuickPlayInfo> for SessionTarget {
+  fn from(info: &QuickPlayInfo) -> Self {
+    SessionTarget {
+      id: info.id.clone(),
+      r#type: info.r#type.clone(),
+    }
+  }
+}
+
+impl Session {
+  fn duration(&self) -> i64 {
+    (self.ended_at - self.launched_at).num_seconds().max(0)
+  }
+}
+
+/// Loads the session history of a profile.
+/// If there is none yet it is rebuilt from the existing run logs.
+pub async fn load_sessions(profile_dir: &Path) -> Result<Vec<Session>> {
+  let lock = session_lock(profile_dir);
+  let _guard = lock.lock().await;
+  load_sessions_unlocked(profile_dir).await
+}
+
+pub async fn record_session(profile_dir: &Path, session: Session) -> Result<()> {
+  let lock = session_lock(profile_dir);
+  let _guard = lock.lock().await;
+
+  let mut sessions = load_sessions_unlocked(profile_dir).await?;
+  // the rebuild can already contain the session if the run log was written before
+  sessions.retain(|s| s.launched_at != session.launched_at);
+  sessions.push(session);
+  sessions.sort_by_key(|s| s.launched_at);
+
+  write_file(&path!(profile_dir, PROFILE_SESSIONS), &sessions).await
+}
+
+fn session_lock(profile_dir: &Path) -> Arc<Mutex<()>> {
+  SESSION_LOCKS
+    .lock()
+    .unwrap()
+    .entry(profile_dir.to_path_buf())
+    .or_default()
+    .clone()
+}
+
+async fn load_sessions_unlocked(profile_dir: &Path) -> Result<Vec<Session>> {
+  let path = path!(profile_dir, PROFILE_SESSIONS);
+  if path.exists() {
+    return read_parse_file(&path).await;
+  }
+
+  let sessions = rebuild_sessions(&path!(profile_dir, PROFILE_LOGS)).await?;
+  write_file(&path, &sessions).await?;
+
+  Ok(sessions)
+}
+
+async fn rebuild_sessions(log_dir: &Path) -> Result<Vec<Session>> {
+  let mut sessions = Vec::new();
+
+  for launched_at in run_timestamps(log_dir).await? {
+    let ended_at = if let Ok(Some(summary)) = RunSummary::load(log_dir, launched_at).await {
+      summary.ended_at
+    } else {
+      // the log is written until the game exits
+      let Ok(modified) = fs::metadata(run_log_file(log_dir, launched_at))
+        .await
+        .and_then(|m| m.modified())
+      else {
+        continue;
+      };
+      modified.into()
+    };
+
+    sessions.push(Session {
+      launched_at,
+      ended_at,
+      quick_play: None,
+      crashed: crash_file(log_dir, launched_at).exists(),
+    });
+  }
+  sessions.sort_by_key(|s| s.launched_at);
+
+  Ok(sessions)
+}
+
+impl PlayStats {
+  fn add(&mut self, session: &Session) {
+    self.total_playtime += session.duration();
+    self.session_count += 1;
+    self.average_session = self.total_playtime / self.session_count as i64;
+    if session.crashed {
+      self.crash_count += 1;
+    }
+    if self
+      .last_session
+      .is_none_or(|last| last < session.launched_at)
+    {
+      self.last_session = Some(session.launched_at);
+    }
+  }
+}
+
+impl ProfileStats {
+  pub fn new(sessions: &[Session], quick_plays: &[QuickPlayInfo]) -> Self {
+    let mut total = PlayStats::default();
+    let mut targets: HashMap<SessionTarget, PlayStats> = HashMap::new();
+
+    for session in sessions {
+      total.add(session);
+      if let Some(target) = &session.quick_play {
+        targets.entry(target.clone()).or_default().add(session);
+      }
+    }
+
+    let mut quick_play = targets
+      .into_iter()
+      .map(|(target, stats)| QuickPlayStats {
+        name: quick_plays
+          .iter()
+          .find(|q| q.id == target.id && q.r#type == target.r#type)
+          .map(|q| q.name.clone()),
+        id: target.id,
+        r#type: target.r#type,
+        stats,
+      })
+      .collect::<Vec<_>>();
+    quick_play.sort_by_key(|q| std::cmp::Reverse(q.stats.total_playtime));
+
+    ProfileStats { total, quick_play }
+  }
+}
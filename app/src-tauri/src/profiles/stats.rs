@@ -0,0 +1,131 @@
+use std::{
+  collections::VecDeque,
+  path::Path,
+  sync::{Arc, Mutex as StdMutex},
+  time::Duration,
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, async_runtime::spawn};
+use tokio::{sync::Mutex, time::sleep};
+
+use crate::{
+  profiles::{instance::InstanceState, profile::run_summary_file},
+  utils::{
+    file::{read_parse_file, write_file},
+    updater::{UpdateType, update_data},
+  },
+};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+// one hour of samples
+const MAX_SAMPLES: usize = 720;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InstanceSample {
+  pub timestamp: DateTime<Utc>,
+  // percent of a single core, so this can be above 100 on multi core systems
+  pub cpu: f32,
+  // resident memory in bytes
+  pub memory: u64,
+  // only available on linux
+  pub threads: Option<usize>,
+  // in seconds
+  pub uptime: u64,
+}
+
+/// Stored next to the run log once the instance finished
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RunSummary {
+  pub launched_at: DateTime<Utc>,
+  pub ended_at: DateTime<Utc>,
+  pub state: InstanceState,
+  // in bytes
+  pub peak_memory: u64,
+}
+
+#[derive(Default)]
+pub struct InstanceStats {
+  samples: VecDeque<InstanceSample>,
+  peak_memory: u64,
+}
+
+impl InstanceStats {
+  fn push(&mut self, sample: InstanceSample) {
+    self.peak_memory = self.peak_memory.max(sample.memory);
+    if self.samples.len() >= MAX_SAMPLES {
+      self.samples.pop_front();
+    }
+    self.samples.push_back(sample);
+  }
+
+  pub fn samples(&self) -> Vec<InstanceSample> {
+    self.samples.iter().cloned().collect()
+  }
+
+  pub fn peak_memory(&self) -> u64 {
+    self.peak_memory
+  }
+}
+
+impl RunSummary {
+  pub async fn save(&self, log_dir: &Path) -> Result<()> {
+    write_file(&run_summary_file(log_dir, self.launched_at), self).await
+  }
+
+  pub async fn load(log_dir: &Path, launched_at: DateTime<Utc>) -> Result<Option<Self>> {
+    let path = run_summary_file(log_dir, launched_at);
+    if !path.exists() {
+      return Ok(None);
+    }
+
+    Ok(Some(read_parse_file(&path).await?))
+  }
+}
+
+/// Samples the resource usage of the process until the instance is no longer running
+pub fn spawn_sampler(
+  pid: u32,
+  state: Arc<StdMutex<InstanceState>>,
+  stats: Arc<Mutex<InstanceStats>>,
+  handle: AppHandle,
+) {
+  spawn(async move {
+    let pid = Pid::from_u32(pid);
+    // cpu usage is calculated between two refreshes so the system has to be reused
+    let mut system = System::new();
+    let refresh = ProcessRefreshKind::nothing()
+      .with_cpu()
+      .with_memory()
+      .with_tasks();
+
+    loop {
+      if !matches!(
+        *state.lock().unwrap(),
+        InstanceState::Running | InstanceState::Stopping
+      ) {
+        break;
+      }
+
+      system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, refresh);
+      let Some(process) = system.process(pid) else {
+        break;
+      };
+
+      let sample = InstanceSample {
+        timestamp: Utc::now(),
+        cpu: process.cpu_usage(),
+        memory: process.memory(),
+        threads: process.tasks().map(|tasks| tasks.len()),
+        uptime: process.run_time(),
+      };
+      stats.lock().await.push(sample);
+      update_data(&handle, UpdateType::InstanceStats);
+
+      sleep(SAMPLE_INTERVAL).await;
+    }
+  });
+}
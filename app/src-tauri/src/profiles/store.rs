@@ -1,25 +1,35 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use log::debug;
+use reqwest::Client;
 use tauri::{AppHandle, Manager};
-use tokio::sync::Mutex;
+use tokio::fs;
 
 use crate::{
   account::store::LaunchInfo,
   path,
   profiles::{
     PROFILE_CONFIG,
+    backup::{
+      BackupError, BackupInfo, BackupReason, backed_up_profiles, create_backup, list_backups,
+      restore_backup,
+    },
     config::{
-      PlayHistoryFavoriteInfo, Profile, ProfileError, ProfileInfo, QuickPlayInfo, QuickPlayType,
+      DuplicateOptions, PlayHistoryFavoriteInfo, Profile, ProfileError, ProfileFilter, ProfileInfo,
+      ProfileSort, QuickPlayInfo, QuickPlayType,
     },
+    import::ImportCandidate,
     watcher::watch_profile,
+    worlds::remove_world,
   },
   settings::SettingsExt,
   store::TauriAppStoreExt,
   utils::{
     file::read_parse_file,
-    updater::{UpdateType, update_data},
+    log::ResultLogExt,
+    updater::{UpdateType, default_client, update_data},
   },
   versions::{
     launch::{LaunchArgs, launch_minecraft_version},
@@ -27,13 +37,21 @@ use crate::{
   },
 };
 
-use super::instance::{Instance, InstanceError, InstanceInfo};
+use super::{
+  instance::{
+    Instance, InstanceError, InstanceInfo, InstanceState, Instances, PersistedInstance,
+    save_instances,
+  },
+  sessions::SessionTarget,
+  stats::InstanceSample,
+};
 
 pub struct ProfileStore {
   profiles: HashMap<String, ProfileInfo>,
-  instances: Arc<Mutex<HashMap<String, Vec<Instance>>>>,
+  instances: Instances,
   handle: AppHandle,
   data_dir: PathBuf,
+  client: Client,
 }
 
 impl ProfileStore {
@@ -58,6 +76,7 @@ impl ProfileStore {
       handle,
       instances: Default::default(),
       data_dir,
+      client: default_client(),
     })
   }
 
@@ -76,6 +95,37 @@ impl ProfileStore {
     Ok(())
   }
 
+  pub async fn duplicate_profile(
+    &mut self,
+    profile: &str,
+    name: String,
+    options: &DuplicateOptions,
+  ) -> Result<()> {
+    let (id, info) = self
+      .profile(profile)
+      .await?
+      .duplicate(&self.data_dir, &self.handle, name, options)
+      .await?;
+    self.profiles.insert(id, info);
+    self.save()?;
+
+    Ok(())
+  }
+
+  pub async fn import_profiles(
+    &mut self,
+    candidates: &[ImportCandidate],
+    link: bool,
+  ) -> Result<()> {
+    for candidate in candidates {
+      let (id, info) = candidate.import(&self.data_dir, &self.handle, link).await?;
+      self.profiles.insert(id, info);
+      self.save()?;
+    }
+
+    Ok(())
+  }
+
   pub fn get_profile_path(&self, profile: &str) -> Result<PathBuf> {
     let info = self.profile_info(profile)?;
     Ok(path!(&self.data_dir, &info.path))
@@ -84,6 +134,13 @@ impl ProfileStore {
   pub async fn remove_profile(&mut self, id: &str) -> Result<()> {
     let info = self.profile_info(id)?.clone();
 
+    let profile = self.profile(id).await?;
+    if profile.backup.before_remove {
+      // the removal is aborted if the backup fails
+      create_backup(&self.data_dir, &profile, BackupReason::Remove).await?;
+      self.update_data(UpdateType::ProfileBackups);
+    }
+
     self.profiles.remove(id);
     self.save()?;
 
@@ -91,15 +148,64 @@ impl ProfileStore {
     Ok(())
   }
 
-  pub async fn list_profiles(&self) -> Result<Vec<Profile>> {
+  pub async fn list_profiles(
+    &self,
+    filter: &ProfileFilter,
+    sort: &ProfileSort,
+  ) -> Result<Vec<Profile>> {
     let mut profiles = Vec::new();
     for info in self.profiles.values() {
-      profiles.push(read_parse_file(&path!(&self.data_dir, &info.path, PROFILE_CONFIG)).await?);
+      let profile: Profile =
+        read_parse_file(&path!(&self.data_dir, &info.path, PROFILE_CONFIG)).await?;
+      if filter.matches(&profile) {
+        profiles.push(profile);
+      }
     }
+    sort.sort(&mut profiles);
 
     Ok(profiles)
   }
 
+  pub async fn list_groups(&self) -> Result<Vec<String>> {
+    let mut groups = Vec::new();
+    for profile in self
+      .list_profiles(&Default::default(), &Default::default())
+      .await?
+    {
+      if let Some(group) = profile.group
+        && !groups.contains(&group)
+      {
+        groups.push(group);
+      }
+    }
+    groups.sort();
+
+    Ok(groups)
+  }
+
+  pub async fn move_profiles(&self, profiles: &[String], group: Option<String>) -> Result<()> {
+    for profile in profiles {
+      let mut profile = self.profile(profile).await?;
+      profile.group = group.clone();
+      profile.update(&self.data_dir).await?;
+    }
+
+    Ok(())
+  }
+
+  /// Sets the manual sort index to the position in `profiles`
+  pub async fn reorder_profiles(&self, profiles: &[String]) -> Result<()> {
+    for (i, profile) in profiles.iter().enumerate() {
+      let mut profile = self.profile(profile).await?;
+      if profile.sort_index != i {
+        profile.sort_index = i;
+        profile.update(&self.data_dir).await?;
+      }
+    }
+
+    Ok(())
+  }
+
   pub async fn launch_profile(
     &mut self,
     info: LaunchInfo,
@@ -143,7 +249,14 @@ impl ProfileStore {
     })
     .await?;
 
-    Instance::create(child, &self.handle, &profile, &self.instances).await?;
+    Instance::create(
+      child,
+      &self.handle,
+      &profile,
+      quick_play.as_ref().map(SessionTarget::from),
+      &self.instances,
+    )
+    .await?;
 
     Ok(())
   }
@@ -227,12 +340,15 @@ impl ProfileStore {
         .iter()
         .map(|i| InstanceInfo {
           id: i.id().to_string(),
+          pid: i.pid(),
           profile_name: profile_name.clone().unwrap_or(i.profile_name().to_string()),
           profile_id: i.profile_id().to_string(),
           version: i.version().to_string(),
           loader: i.loader(),
           loader_version: i.loader_version().cloned(),
           launched_at: i.launched_at(),
+          attached: i.attached(),
+          state: i.state(),
         })
         .collect();
       if instances.is_empty() {
@@ -245,6 +361,28 @@ impl ProfileStore {
     res
   }
 
+  /// Picks up instances that survived a restart of the launcher
+  pub async fn reattach_instances(&self) -> Result<()> {
+    for persisted in PersistedInstance::load(&self.handle)? {
+      if !self.profiles.contains_key(&persisted.profile_id) || !persisted.is_alive() {
+        debug!(
+          "Dropping persisted instance {} of profile {}",
+          persisted.id, persisted.profile_id
+        );
+        continue;
+      }
+
+      let _ = Instance::attach(&self.handle, persisted, &self.instances)
+        .await
+        .log();
+    }
+
+    // persist again so dead instances are removed
+    save_instances(&self.handle, &*self.instances.lock().await);
+
+    Ok(())
+  }
+
   pub async fn get_instance_logs(&self, profile: &str, id: &str) -> Result<Vec<String>> {
     let instances = self.instances.lock().await;
     let instances = instances.get(profile).ok_or(InstanceError::NotFound)?;
@@ -255,6 +393,16 @@ impl ProfileStore {
     Ok(instance.lines().await)
   }
 
+  pub async fn get_instance_stats(&self, profile: &str, id: &str) -> Result<Vec<InstanceSample>> {
+    let instances = self.instances.lock().await;
+    let instances = instances.get(profile).ok_or(InstanceError::NotFound)?;
+    let instance = instances
+      .iter()
+      .find(|i| i.id() == id)
+      .ok_or(InstanceError::NotFound)?;
+    Ok(instance.stats().await)
+  }
+
   pub async fn stop_instance(&self, profile: &str, id: &str) -> Result<()> {
     let mut instances = self.instances.lock().await;
     let entry = instances.get_mut(profile).ok_or(InstanceError::NotFound)?;
@@ -262,7 +410,60 @@ impl ProfileStore {
       .iter()
       .find(|i| i.id() == id)
       .ok_or(InstanceError::NotFound)?;
-    instance.stop();
+    let timeout = self.handle.app_settings()?.minecraft.stop_timeout_secs;
+    instance.stop(&self.handle, Duration::from_secs(timeout));
+    Ok(())
+  }
+
+  /// Removes the world and the quick play entries pointing to it
+  pub async fn remove_world(&self, profile: &str, world: &str) -> Result<()> {
+    remove_world(&self.get_profile_path(profile)?, world).await?;
+
+    let mut profile = self.profile(profile).await?;
+    profile
+      .quick_play
+      .retain(|q| q.r#type != QuickPlayType::Singleplayer || q.id != world);
+    profile.update(&self.data_dir).await?;
+
+    Ok(())
+  }
+
+  /// Backups of profiles that were removed
+  pub async fn list_removed_backups(&self) -> Result<Vec<BackupInfo>> {
+    let mut backups = Vec::new();
+    for profile in backed_up_profiles(&self.data_dir).await? {
+      if self.profiles.contains_key(&profile) {
+        continue;
+      }
+      backups.extend(list_backups(&self.data_dir, &profile).await?);
+    }
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+
+    Ok(backups)
+  }
+
+  /// Restores a backup, profiles that were removed since are registered again
+  pub async fn restore_profile(&mut self, id: &str, created_at: DateTime<Utc>) -> Result<()> {
+    let running = self.instances.lock().await.get(id).is_some_and(|e| {
+      e.iter()
+        .any(|i| matches!(i.state(), InstanceState::Running | InstanceState::Stopping))
+    });
+    if running {
+      return Err(BackupError::ProfileRunning.into());
+    }
+
+    let profile = restore_backup(&self.data_dir, id, created_at).await?;
+    if !self.profiles.contains_key(id) {
+      let path = path!(&self.data_dir, profile.relative_to_data());
+      fs::create_dir_all(&path).await?;
+      let watcher = watch_profile(path.clone(), id.to_string(), self.handle.clone())?;
+      profile.update(&self.data_dir).await?;
+      self
+        .profiles
+        .insert(id.to_string(), ProfileInfo { path, watcher });
+      self.save()?;
+    }
+
     Ok(())
   }
 
@@ -274,6 +475,10 @@ impl ProfileStore {
     &self.handle
   }
 
+  pub fn client(&self) -> &Client {
+    &self.client
+  }
+
   fn save(&self) -> Result<()> {
     let mut profiles = HashMap::new();
     for (id, info) in &self.profiles {
@@ -0,0 +1,314 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use async_zip::{base::write::ZipFileWriter, tokio::read::fs::ZipFileReader};
+use base64::prelude::*;
+use chrono::{DateTime, Utc};
+use log::debug;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::{fs, io};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+use crate::{
+  path,
+  profiles::SAVES_DIR,
+  utils::{
+    dir::{copy_dir, list_dirs_in_dir_path, list_files_in_dir},
+    file::create_or_open_file,
+    log::ResultLogExt,
+    nbt::{Tag, read_gzip_nbt},
+    zip::{safe_path, write_files_to_zip},
+  },
+  versions::{meta::minecraft::JarVersion, paths::MCVersionPath},
+};
+
+const LEVEL_DAT: &str = "level.dat";
+const WORLD_ICON: &str = "icon.png";
+const SESSION_LOCK: &str = "session.lock";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+  Survival,
+  Creative,
+  Adventure,
+  Spectator,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+  Peaceful,
+  Easy,
+  Normal,
+  Hard,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WorldInfo {
+  // the folder name, also used as the quick play id
+  pub id: String,
+  pub name: String,
+  pub game_mode: Option<GameMode>,
+  pub hardcore: bool,
+  pub difficulty: Option<Difficulty>,
+  pub last_played: Option<DateTime<Utc>>,
+  pub data_version: Option<i32>,
+  pub version_name: Option<String>,
+  pub seed: Option<i64>,
+  // in bytes
+  pub size: u64,
+  // base64 encoded png
+  pub icon: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum WorldError {
+  #[error("NotFound")]
+  NotFound,
+  #[error("InvalidWorld")]
+  InvalidWorld,
+  #[error("WorldNewer")]
+  WorldNewer,
+}
+
+/// A world last saved by a newer game version than the one it would be opened in
+#[derive(Serialize, Debug, Clone)]
+pub struct NewerWorld {
+  pub world_version: String,
+  pub game_version: String,
+}
+
+pub fn saves_dir(profile_dir: &Path) -> PathBuf {
+  path!(profile_dir, SAVES_DIR)
+}
+
+pub async fn list_worlds(profile_dir: &Path) -> Result<Vec<WorldInfo>> {
+  let saves = saves_dir(profile_dir);
+  if !saves.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut worlds = Vec::new();
+  for dir in list_dirs_in_dir_path(saves).await? {
+    match read_world(&dir).await {
+      Ok(world) => worlds.push(world),
+      Err(e) => debug!("Skipping world {}: {e}", dir.display()),
+    }
+  }
+  worlds.sort_by_key(|w| std::cmp::Reverse(w.last_played));
+
+  Ok(worlds)
+}
+
+pub async fn world_info(profile_dir: &Path, world: &str) -> Result<WorldInfo> {
+  read_world(&world_dir(profile_dir, world)?).await
+}
+
+async fn read_world(dir: &Path) -> Result<WorldInfo> {
+  let id = dir
+    .file_name()
+    .and_then(|n| n.to_str())
+    .ok_or(WorldError::InvalidWorld)?
+    .to_string();
+  let level_dat = path!(dir, LEVEL_DAT);
+  if !level_dat.exists() {
+    return Err(WorldError::InvalidWorld.into());
+  }
+
+  let (_, root) = read_gzip_nbt(&fs::read(&level_dat).await?)?;
+  let data = root.get("Data").ok_or(WorldError::InvalidWorld)?;
+  let int = |key: &str| data.get(key).and_then(Tag::as_i64);
+
+  let mut size = 0;
+  for file in list_files_in_dir(dir).await? {
+    size += fs::metadata(path!(dir, file)).await?.len();
+  }
+  let icon = match fs::read(path!(dir, WORLD_ICON)).await {
+    Ok(icon) => Some(BASE64_STANDARD.encode(icon)),
+    Err(_) => None,
+  };
+
+  Ok(WorldInfo {
+    name: data
+      .get("LevelName")
+      .and_then(Tag::as_str)
+      .map(String::from)
+      .unwrap_or_else(|| id.clone()),
+    id,
+    game_mode: int("GameType").and_then(|mode| match mode {
+      0 => Some(GameMode::Survival),
+      1 => Some(GameMode::Creative),
+      2 => Some(GameMode::Adventure),
+      3 => Some(GameMode::Spectator),
+      _ => None,
+    }),
+    hardcore: int("hardcore").is_some_and(|h| h != 0),
+    // old worlds do not store the difficulty
+    difficulty: int("Difficulty").and_then(|difficulty| match difficulty {
+      0 => Some(Difficulty::Peaceful),
+      1 => Some(Difficulty::Easy),
+      2 => Some(Difficulty::Normal),
+      3 => Some(Difficulty::Hard),
+      _ => None,
+    }),
+    last_played: int("LastPlayed").and_then(DateTime::from_timestamp_millis),
+    data_version: int("DataVersion").map(|v| v as i32),
+    version_name: data
+      .get("Version")
+      .and_then(|v| v.get("Name"))
+      .and_then(Tag::as_str)
+      .map(String::from),
+    // moved into the world gen settings in 1.16
+    seed: int("RandomSeed").or_else(|| {
+      data
+        .get("WorldGenSettings")
+        .and_then(|s| s.get("seed"))
+        .and_then(Tag::as_i64)
+    }),
+    size,
+    icon,
+  })
+}
+
+/// Returns the versions if the world was last saved by a newer game version than `version_path`,
+/// opening it would corrupt the world.
+/// The check is only advisory, unreadable or old worlds and versions without a data version are skipped.
+pub async fn newer_world(
+  profile_dir: &Path,
+  world: &str,
+  version_path: &MCVersionPath,
+) -> Option<NewerWorld> {
+  let info = world_info(profile_dir, world).await.log().ok()?;
+  let world_version = info.data_version?;
+  let version = JarVersion::read(version_path)
+    .await
+    .log()
+    .ok()??
+    .world_version;
+  if world_version <= version {
+    return None;
+  }
+
+  debug!("World {world} has data version {world_version} but the game only {version}");
+  Some(NewerWorld {
+    world_version: info.version_name.unwrap_or(world_version.to_string()),
+    game_version: version_path.version().to_string(),
+  })
+}
+
+/// Writes the world into a zip with the world folder at its root
+pub async fn export_world(profile_dir: &Path, world: &str, target: &Path) -> Result<()> {
+  let dir = world_dir(profile_dir, world)?;
+  let files = list_files_in_dir(&dir)
+    .await?
+    .into_iter()
+    .filter(|f| !f.ends_with(SESSION_LOCK))
+    .collect::<Vec<_>>();
+
+  let mut writer = ZipFileWriter::with_tokio(create_or_open_file(&target.to_path_buf()).await?);
+  write_files_to_zip(&mut writer, &dir, &files, &format!("{world}/")).await?;
+  writer.close().await?;
+
+  Ok(())
+}
+
+/// Extracts a world zip into the saves of the profile and returns the id of the new world
+pub async fn import_world(profile_dir: &Path, source: &Path) -> Result<String> {
+  let zip = ZipFileReader::new(source).await?;
+  let names = zip
+    .file()
+    .entries()
+    .iter()
+    .map(|e| e.filename().as_str().ok().map(String::from))
+    .collect::<Vec<_>>();
+
+  // the world root is the folder with the least nested level.dat
+  let root = names
+    .iter()
+    .flatten()
+    .filter_map(|n| n.strip_suffix(LEVEL_DAT))
+    .filter(|prefix| prefix.is_empty() || prefix.ends_with('/'))
+    .min_by_key(|prefix| prefix.matches('/').count())
+    .ok_or(WorldError::InvalidWorld)?
+    .to_string();
+
+  // the folder name comes from the archive, so it has to be a plain name
+  let name = [
+    root.trim_end_matches('/').rsplit('/').next(),
+    source.file_stem().and_then(|s| s.to_str()),
+  ]
+  .into_iter()
+  .flatten()
+  .find(|name| safe_path(name).is_some_and(|p| p.components().count() == 1))
+  .ok_or(WorldError::InvalidWorld)?
+  .to_string();
+  let id = unique_world_id(profile_dir, &name);
+  let dir = path!(saves_dir(profile_dir), &id);
+
+  for (i, name) in names.iter().enumerate() {
+    let Some(relative) = name
+      .as_deref()
+      .and_then(|n| n.strip_prefix(root.as_str()))
+      .filter(|n| !n.is_empty() && !n.ends_with('/'))
+      .and_then(safe_path)
+    else {
+      continue;
+    };
+
+    let reader = zip.reader_with_entry(i).await?;
+    let mut file = create_or_open_file(&path!(&dir, relative)).await?;
+    io::copy(&mut reader.compat(), &mut file).await?;
+  }
+  debug!("Imported world {} as {id}", source.display());
+
+  Ok(id)
+}
+
+/// Copies a world into another profile and returns the id of the copy
+pub async fn copy_world(
+  profile_dir: &Path,
+  world: &str,
+  target_profile_dir: &Path,
+) -> Result<String> {
+  let dir = world_dir(profile_dir, world)?;
+  let id = unique_world_id(target_profile_dir, world);
+
+  copy_dir(
+    &dir,
+    &path!(saves_dir(target_profile_dir), &id),
+    |relative| !relative.ends_with(SESSION_LOCK),
+  )
+  .await?;
+
+  Ok(id)
+}
+
+pub async fn remove_world(profile_dir: &Path, world: &str) -> Result<()> {
+  fs::remove_dir_all(world_dir(profile_dir, world)?).await?;
+  Ok(())
+}
+
+fn world_dir(profile_dir: &Path, world: &str) -> Result<PathBuf> {
+  let relative = safe_path(world).ok_or(WorldError::NotFound)?;
+  let dir = path!(saves_dir(profile_dir), relative);
+  if !dir.is_dir() {
+    return Err(WorldError::NotFound.into());
+  }
+
+  Ok(dir)
+}
+
+/// Appends a counter to the name like the game does for duplicate world names
+fn unique_world_id(profile_dir: &Path, name: &str) -> String {
+  let saves = saves_dir(profile_dir);
+  let mut id = name.to_string();
+  let mut i = 1;
+  while path!(&saves, &id).exists() {
+    id = format!("{name} ({i})");
+    i += 1;
+  }
+
+  id
+}
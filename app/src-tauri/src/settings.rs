@@ -33,13 +33,35 @@ pub struct Settings {
   pub minecraft: MinecraftSettings,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Serialize, Deserialize)]
 pub struct MinecraftSettings {
   pub show_snapshots: bool,
+  // old_alpha and old_beta versions are listed independently of snapshots
+  #[serde(default)]
+  pub show_old_versions: bool,
   #[serde(default)]
   pub game_settings: GameSettings,
   #[serde(default)]
   pub jvm_settings: JvmSettings,
+  // how long a stopping instance gets to save before it is killed
+  #[serde(default = "default_stop_timeout")]
+  pub stop_timeout_secs: u64,
+}
+
+impl Default for MinecraftSettings {
+  fn default() -> Self {
+    Self {
+      show_snapshots: false,
+      show_old_versions: false,
+      game_settings: GameSettings::default(),
+      jvm_settings: JvmSettings::default(),
+      stop_timeout_secs: default_stop_timeout(),
+    }
+  }
+}
+
+fn default_stop_timeout() -> u64 {
+  30
 }
 
 pub trait SettingsExt {
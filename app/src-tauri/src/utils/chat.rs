@@ -0,0 +1,37 @@
+use serde_json::Value;
+
+/// Flattens a chat component into plain text
+pub fn component_text(component: &Value) -> String {
+  match component {
+    Value::String(text) => text.clone(),
+    Value::Array(items) => items.iter().map(component_text).collect(),
+    Value::Object(map) => {
+      let mut text = map
+        .get("text")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+      if let Some(Value::Array(extra)) = map.get("extra") {
+        for item in extra {
+          text.push_str(&component_text(item));
+        }
+      }
+      text
+    }
+    _ => String::new(),
+  }
+}
+
+/// Removes legacy `§` formatting codes
+pub fn strip_formatting(text: &str) -> String {
+  let mut result = String::with_capacity(text.len());
+  let mut chars = text.chars();
+  while let Some(c) = chars.next() {
+    if c == '§' {
+      chars.next();
+    } else {
+      result.push(c);
+    }
+  }
+  result
+}
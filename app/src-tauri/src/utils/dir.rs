@@ -1,7 +1,18 @@
-use std::{io, path::PathBuf};
+use std::{
+  fs::FileType,
+  io,
+  path::{Path, PathBuf},
+};
 
+use anyhow::Result;
+use log::debug;
+use tauri::async_runtime::spawn_blocking;
 use tokio::fs;
 
+// hardlinks are only used for large archives, those are replaced instead of modified in place
+const HARDLINK_MIN_SIZE: u64 = 1024 * 1024;
+const HARDLINK_EXTENSIONS: [&str; 2] = ["jar", "zip"];
+
 pub async fn list_dirs_in_dir_path(path: PathBuf) -> io::Result<Vec<PathBuf>> {
   let mut dirs = Vec::new();
   let mut stream = fs::read_dir(path).await?;
@@ -23,3 +34,158 @@ pub async fn list_dirs_in_dir(path: PathBuf) -> io::Result<Vec<String>> {
   }
   Ok(dirs)
 }
+
+/// Recursively lists all files in `path`, the returned paths are relative to `path`.
+/// Symlinks are followed, e.g. for folders linked into an imported profile.
+pub async fn list_files_in_dir(path: &Path) -> io::Result<Vec<PathBuf>> {
+  let mut files = Vec::new();
+  // the resolved parents of each directory, to not follow symlinks into a loop
+  let mut dirs = vec![(PathBuf::new(), Vec::new())];
+
+  while let Some((relative, mut parents)) = dirs.pop() {
+    let resolved = fs::canonicalize(path.join(&relative)).await?;
+    if parents.contains(&resolved) {
+      debug!(
+        "Skipping symlink loop at {}",
+        path.join(&relative).display()
+      );
+      continue;
+    }
+    parents.push(resolved);
+
+    let mut stream = fs::read_dir(path.join(&relative)).await?;
+    while let Some(entry) = stream.next_entry().await? {
+      let Some(file_type) = resolved_type(&entry).await? else {
+        continue;
+      };
+      if file_type.is_dir() {
+        dirs.push((relative.join(entry.file_name()), parents.clone()));
+      } else if file_type.is_file() {
+        files.push(relative.join(entry.file_name()));
+      }
+    }
+  }
+
+  Ok(files)
+}
+
+/// Recursively copies `src` into `dst`, symlinks are followed and their targets copied.
+/// `filter` gets the path relative to `src` and returns if the entry should be copied.
+pub async fn copy_dir(src: &Path, dst: &Path, filter: impl Fn(&Path) -> bool) -> Result<()> {
+  // the resolved parents of each directory, to not follow symlinks into a loop
+  let mut dirs = vec![(PathBuf::new(), Vec::new())];
+
+  while let Some((relative, mut parents)) = dirs.pop() {
+    let resolved = fs::canonicalize(src.join(&relative)).await?;
+    if parents.contains(&resolved) {
+      debug!("Skipping symlink loop at {}", src.join(&relative).display());
+      continue;
+    }
+    parents.push(resolved);
+    fs::create_dir_all(dst.join(&relative)).await?;
+
+    let mut stream = fs::read_dir(src.join(&relative)).await?;
+    while let Some(entry) = stream.next_entry().await? {
+      let relative = relative.join(entry.file_name());
+      if !filter(&relative) {
+        continue;
+      }
+
+      let Some(file_type) = resolved_type(&entry).await? else {
+        continue;
+      };
+      if file_type.is_dir() {
+        dirs.push((relative, parents.clone()));
+      } else if file_type.is_file() {
+        // hardlinking a symlink would link the symlink itself instead of its target
+        let source = fs::canonicalize(entry.path()).await?;
+        copy_file_linked(&source, &dst.join(&relative)).await?;
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// The type of the entry with symlinks resolved, `None` for broken symlinks
+async fn resolved_type(entry: &fs::DirEntry) -> io::Result<Option<FileType>> {
+  let file_type = entry.file_type().await?;
+  if !file_type.is_symlink() {
+    return Ok(Some(file_type));
+  }
+
+  match fs::metadata(entry.path()).await {
+    Ok(metadata) => Ok(Some(metadata.file_type())),
+    Err(e) if e.kind() == io::ErrorKind::NotFound => {
+      debug!("Skipping broken symlink {}", entry.path().display());
+      Ok(None)
+    }
+    Err(e) => Err(e),
+  }
+}
+
+/// Symlinks the top level entries of `src` into `dst`, entries that can not be linked are copied.
+/// `filter` gets the path relative to `src` and returns if the entry should be linked.
+pub async fn link_dir(src: &Path, dst: &Path, filter: impl Fn(&Path) -> bool) -> Result<()> {
+  fs::create_dir_all(dst).await?;
+
+  let mut stream = fs::read_dir(src).await?;
+  while let Some(entry) = stream.next_entry().await? {
+    let relative = PathBuf::from(entry.file_name());
+    if !filter(&relative) {
+      continue;
+    }
+
+    let is_dir = entry.file_type().await?.is_dir();
+    if let Err(e) = symlink(&entry.path(), &dst.join(&relative), is_dir).await {
+      debug!("Failed to symlink {}: {e}", entry.path().display());
+      if is_dir {
+        copy_dir(&entry.path(), &dst.join(&relative), &filter).await?;
+      } else {
+        copy_file_linked(&entry.path(), &dst.join(&relative)).await?;
+      }
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(unix)]
+async fn symlink(src: &Path, dst: &Path, _is_dir: bool) -> io::Result<()> {
+  fs::symlink(src, dst).await
+}
+
+#[cfg(windows)]
+async fn symlink(src: &Path, dst: &Path, is_dir: bool) -> io::Result<()> {
+  if is_dir {
+    fs::symlink_dir(src, dst).await
+  } else {
+    fs::symlink_file(src, dst).await
+  }
+}
+
+/// Copies a file by reflinking it where the filesystem supports it.
+/// Large archives fall back to a hardlink, everything else is copied.
+pub async fn copy_file_linked(src: &Path, dst: &Path) -> Result<()> {
+  let (src_, dst_) = (src.to_path_buf(), dst.to_path_buf());
+  if spawn_blocking(move || reflink_copy::reflink(src_, dst_))
+    .await?
+    .is_ok()
+  {
+    return Ok(());
+  }
+
+  let archive = src
+    .extension()
+    .and_then(|e| e.to_str())
+    .is_some_and(|e| HARDLINK_EXTENSIONS.contains(&e));
+  if archive && fs::metadata(src).await?.len() >= HARDLINK_MIN_SIZE {
+    match fs::hard_link(src, dst).await {
+      Ok(()) => return Ok(()),
+      Err(e) => debug!("Failed to hardlink {}: {e}", src.display()),
+    }
+  }
+
+  fs::copy(src, dst).await?;
+  Ok(())
+}
@@ -0,0 +1,50 @@
+use std::{sync::LazyLock, time::Duration};
+
+use anyhow::Result;
+use hickory_resolver::{TokioResolver, proto::rr::RData};
+use log::{debug, error};
+use tokio::time::timeout;
+
+const DNS_TIMEOUT: Duration = Duration::from_secs(2);
+
+// reading the system configuration is only done once, it also keeps the resolver cache around
+static RESOLVER: LazyLock<Option<TokioResolver>> = LazyLock::new(|| {
+  TokioResolver::builder_tokio()
+    .and_then(|builder| builder.build())
+    .inspect_err(|err| error!("Failed to create dns resolver: {err}"))
+    .ok()
+});
+
+/// Looks up the srv record with the lowest priority and returns its target and port.
+/// The nameservers are taken from the system configuration.
+pub async fn resolve_srv(name: &str) -> Result<Option<(String, u16)>> {
+  debug!("Resolving srv record {name}");
+  let resolver = RESOLVER
+    .as_ref()
+    .ok_or_else(|| anyhow::anyhow!("No dns resolver available"))?;
+
+  let lookup = match timeout(DNS_TIMEOUT, resolver.srv_lookup(name)).await? {
+    Ok(lookup) => lookup,
+    // nxdomain and empty answers just mean there is no record
+    Err(err) if err.is_no_records_found() => return Ok(None),
+    Err(err) => return Err(err.into()),
+  };
+
+  Ok(
+    lookup
+      .answers()
+      .iter()
+      .filter_map(|record| match &record.data {
+        RData::SRV(srv) => Some(srv),
+        _ => None,
+      })
+      // higher weights should be picked more often, prefer them for a stable result
+      .min_by_key(|srv| (srv.priority, std::cmp::Reverse(srv.weight)))
+      .map(|srv| {
+        (
+          srv.target.to_utf8().trim_end_matches('.').to_string(),
+          srv.port,
+        )
+      }),
+  )
+}
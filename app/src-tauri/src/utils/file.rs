@@ -21,6 +21,14 @@ pub async fn file_hash(hash: &str, path: &PathBuf) -> Result<bool> {
   let Ok(file) = File::open(path).await else {
     return Ok(false);
   };
+  Ok(hash == sha1_of(file).await?)
+}
+
+pub async fn file_sha1(path: &Path) -> Result<String> {
+  sha1_of(File::open(path).await?).await
+}
+
+async fn sha1_of(file: File) -> Result<String> {
   let mut file = file.into_std().await;
   let found_hash = spawn_blocking(move || {
     let mut hasher = Sha1::new();
@@ -35,7 +43,7 @@ pub async fn file_hash(hash: &str, path: &PathBuf) -> Result<bool> {
     Ok::<_, std::io::Error>(hex::encode(hasher.finalize()))
   })
   .await??;
-  Ok(hash == found_hash)
+  Ok(found_hash)
 }
 
 pub fn hash_bytes(hash: &str, bytes: &[u8]) -> Result<bool> {
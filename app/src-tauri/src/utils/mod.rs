@@ -1,7 +1,13 @@
+pub mod chat;
 pub mod dir;
+pub mod dns;
 pub mod download;
 pub mod file;
 pub mod future;
 pub mod log;
 pub mod macros;
+pub mod nbt;
+pub mod ping;
 pub mod updater;
+pub mod version;
+pub mod zip;
@@ -0,0 +1,382 @@
+use std::{
+  collections::HashMap,
+  io::{self, Cursor, Read, Write},
+};
+
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use thiserror::Error;
+
+// guards against stack overflows from malicious files
+const MAX_DEPTH: usize = 512;
+
+#[derive(Error, Debug)]
+pub enum NbtError {
+  #[error("InvalidTag {0}")]
+  InvalidTag(u8),
+  #[error("InvalidRoot")]
+  InvalidRoot,
+  #[error("TooDeep")]
+  TooDeep,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+  Byte(i8),
+  Short(i16),
+  Int(i32),
+  Long(i64),
+  Float(f32),
+  Double(f64),
+  ByteArray(Vec<i8>),
+  String(String),
+  List(Vec<Tag>),
+  Compound(HashMap<String, Tag>),
+  IntArray(Vec<i32>),
+  LongArray(Vec<i64>),
+}
+
+impl Tag {
+  pub fn get(&self, key: &str) -> Option<&Tag> {
+    match self {
+      Tag::Compound(map) => map.get(key),
+      _ => None,
+    }
+  }
+
+  /// Returns the value of any integer tag
+  pub fn as_i64(&self) -> Option<i64> {
+    match self {
+      Tag::Byte(v) => Some(*v as i64),
+      Tag::Short(v) => Some(*v as i64),
+      Tag::Int(v) => Some(*v as i64),
+      Tag::Long(v) => Some(*v),
+      _ => None,
+    }
+  }
+
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Tag::String(v) => Some(v),
+      _ => None,
+    }
+  }
+
+  pub fn as_list(&self) -> Option<&Vec<Tag>> {
+    match self {
+      Tag::List(v) => Some(v),
+      _ => None,
+    }
+  }
+
+  fn id(&self) -> u8 {
+    match self {
+      Tag::Byte(_) => 1,
+      Tag::Short(_) => 2,
+      Tag::Int(_) => 3,
+      Tag::Long(_) => 4,
+      Tag::Float(_) => 5,
+      Tag::Double(_) => 6,
+      Tag::ByteArray(_) => 7,
+      Tag::String(_) => 8,
+      Tag::List(_) => 9,
+      Tag::Compound(_) => 10,
+      Tag::IntArray(_) => 11,
+      Tag::LongArray(_) => 12,
+    }
+  }
+}
+
+/// Reads an uncompressed nbt file and returns the name and value of the root tag
+pub fn read_nbt(data: &[u8]) -> Result<(String, Tag)> {
+  let mut reader = Cursor::new(data);
+  if read_u8(&mut reader)? != 10 {
+    return Err(NbtError::InvalidRoot.into());
+  }
+  let name = read_string(&mut reader)?;
+  let tag = read_payload(&mut reader, 10, 0)?;
+
+  Ok((name, tag))
+}
+
+pub fn read_gzip_nbt(data: &[u8]) -> Result<(String, Tag)> {
+  let mut decoded = Vec::new();
+  GzDecoder::new(data).read_to_end(&mut decoded)?;
+  read_nbt(&decoded)
+}
+
+/// Writes an uncompressed nbt file with the given root compound
+pub fn write_nbt(name: &str, root: &Tag) -> Result<Vec<u8>> {
+  if !matches!(root, Tag::Compound(_)) {
+    return Err(NbtError::InvalidRoot.into());
+  }
+
+  let mut data = Vec::new();
+  data.write_all(&[root.id()])?;
+  write_string(&mut data, name)?;
+  write_payload(&mut data, root)?;
+
+  Ok(data)
+}
+
+fn read_payload(reader: &mut impl Read, id: u8, depth: usize) -> Result<Tag> {
+  if depth > MAX_DEPTH {
+    return Err(NbtError::TooDeep.into());
+  }
+
+  Ok(match id {
+    1 => Tag::Byte(read_u8(reader)? as i8),
+    2 => Tag::Short(i16::from_be_bytes(read_bytes(reader)?)),
+    3 => Tag::Int(i32::from_be_bytes(read_bytes(reader)?)),
+    4 => Tag::Long(i64::from_be_bytes(read_bytes(reader)?)),
+    5 => Tag::Float(f32::from_be_bytes(read_bytes(reader)?)),
+    6 => Tag::Double(f64::from_be_bytes(read_bytes(reader)?)),
+    7 => {
+      // the length is untrusted, so only as much as the input holds is allocated
+      let len = read_len(reader)?;
+      let mut data = Vec::new();
+      reader.by_ref().take(len as u64).read_to_end(&mut data)?;
+      if data.len() != len {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+      }
+      Tag::ByteArray(data.into_iter().map(|b| b as i8).collect())
+    }
+    8 => Tag::String(read_string(reader)?),
+    9 => {
+      let item_id = read_u8(reader)?;
+      let len = read_len(reader)?;
+      let mut items = Vec::new();
+      for _ in 0..len {
+        items.push(read_payload(reader, item_id, depth + 1)?);
+      }
+      Tag::List(items)
+    }
+    10 => {
+      let mut map = HashMap::new();
+      loop {
+        let item_id = read_u8(reader)?;
+        if item_id == 0 {
+          break;
+        }
+        let name = read_string(reader)?;
+        map.insert(name, read_payload(reader, item_id, depth + 1)?);
+      }
+      Tag::Compound(map)
+    }
+    11 => {
+      let len = read_len(reader)?;
+      let mut items = Vec::new();
+      for _ in 0..len {
+        items.push(i32::from_be_bytes(read_bytes(reader)?));
+      }
+      Tag::IntArray(items)
+    }
+    12 => {
+      let len = read_len(reader)?;
+      let mut items = Vec::new();
+      for _ in 0..len {
+        items.push(i64::from_be_bytes(read_bytes(reader)?));
+      }
+      Tag::LongArray(items)
+    }
+    id => return Err(NbtError::InvalidTag(id).into()),
+  })
+}
+
+fn read_bytes<const N: usize>(reader: &mut impl Read) -> Result<[u8; N]> {
+  let mut buf = [0; N];
+  reader.read_exact(&mut buf)?;
+  Ok(buf)
+}
+
+fn read_u8(reader: &mut impl Read) -> Result<u8> {
+  Ok(read_bytes::<1>(reader)?[0])
+}
+
+fn read_len(reader: &mut impl Read) -> Result<usize> {
+  // negative lengths are treated as empty
+  Ok(i32::from_be_bytes(read_bytes(reader)?).max(0) as usize)
+}
+
+fn read_string(reader: &mut impl Read) -> Result<String> {
+  let len = u16::from_be_bytes(read_bytes(reader)?) as usize;
+  let mut data = vec![0; len];
+  reader.read_exact(&mut data)?;
+  Ok(decode_modified_utf8(&data))
+}
+
+/// Decodes java's modified utf-8, which encodes null as two bytes and supplementary characters
+/// as two three byte surrogates. Plain 4 byte utf-8 sequences written by other tools are accepted too.
+fn decode_modified_utf8(data: &[u8]) -> String {
+  let continuation = |i: usize| data.get(i).is_some_and(|b| b & 0xC0 == 0x80);
+  let bits = |i: usize| (data[i] & 0x3F) as u32;
+
+  let mut units = Vec::with_capacity(data.len());
+  let mut i = 0;
+  while i < data.len() {
+    let b = data[i];
+    let (code, len) = if b & 0x80 == 0 {
+      (b as u32, 1)
+    } else if b & 0xE0 == 0xC0 && continuation(i + 1) {
+      (((b & 0x1F) as u32) << 6 | bits(i + 1), 2)
+    } else if b & 0xF0 == 0xE0 && continuation(i + 1) && continuation(i + 2) {
+      (
+        ((b & 0x0F) as u32) << 12 | bits(i + 1) << 6 | bits(i + 2),
+        3,
+      )
+    } else if b & 0xF8 == 0xF0 && (1..4).all(|j| continuation(i + j)) {
+      (
+        ((b & 0x07) as u32) << 18 | bits(i + 1) << 12 | bits(i + 2) << 6 | bits(i + 3),
+        4,
+      )
+    } else {
+      (char::REPLACEMENT_CHARACTER as u32, 1)
+    };
+
+    match char::from_u32(code) {
+      Some(c) if code > 0xFFFF => units.extend_from_slice(c.encode_utf16(&mut [0; 2])),
+      Some(_) => units.push(code as u16),
+      // surrogates are kept as single units so pairs can be joined by the utf-16 decoding
+      None if (0xD800..=0xDFFF).contains(&code) => units.push(code as u16),
+      None => units.push(char::REPLACEMENT_CHARACTER as u16),
+    }
+    i += len;
+  }
+
+  String::from_utf16_lossy(&units)
+}
+
+fn encode_modified_utf8(value: &str) -> Vec<u8> {
+  let mut data = Vec::with_capacity(value.len());
+  for unit in value.encode_utf16() {
+    match unit {
+      0x0001..=0x007F => data.push(unit as u8),
+      0x0000 | 0x0080..=0x07FF => {
+        data.push(0xC0 | (unit >> 6) as u8);
+        data.push(0x80 | (unit & 0x3F) as u8);
+      }
+      _ => {
+        data.push(0xE0 | (unit >> 12) as u8);
+        data.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+        data.push(0x80 | (unit & 0x3F) as u8);
+      }
+    }
+  }
+  data
+}
+
+fn write_payload(writer: &mut impl Write, tag: &Tag) -> Result<()> {
+  match tag {
+    Tag::Byte(v) => writer.write_all(&v.to_be_bytes())?,
+    Tag::Short(v) => writer.write_all(&v.to_be_bytes())?,
+    Tag::Int(v) => writer.write_all(&v.to_be_bytes())?,
+    Tag::Long(v) => writer.write_all(&v.to_be_bytes())?,
+    Tag::Float(v) => writer.write_all(&v.to_be_bytes())?,
+    Tag::Double(v) => writer.write_all(&v.to_be_bytes())?,
+    Tag::ByteArray(items) => {
+      write_len(writer, items.len())?;
+      writer.write_all(&items.iter().map(|b| *b as u8).collect::<Vec<_>>())?;
+    }
+    Tag::String(v) => write_string(writer, v)?,
+    Tag::List(items) => {
+      // empty lists are stored with the end tag as item type
+      let item_id = items.first().map(Tag::id).unwrap_or(0);
+      if items.iter().any(|i| i.id() != item_id) {
+        return Err(NbtError::InvalidTag(item_id).into());
+      }
+      writer.write_all(&[item_id])?;
+      write_len(writer, items.len())?;
+      for item in items {
+        write_payload(writer, item)?;
+      }
+    }
+    Tag::Compound(map) => {
+      for (name, item) in map {
+        writer.write_all(&[item.id()])?;
+        write_string(writer, name)?;
+        write_payload(writer, item)?;
+      }
+      writer.write_all(&[0])?;
+    }
+    Tag::IntArray(items) => {
+      write_len(writer, items.len())?;
+      for item in items {
+        writer.write_all(&item.to_be_bytes())?;
+      }
+    }
+    Tag::LongArray(items) => {
+      write_len(writer, items.len())?;
+      for item in items {
+        writer.write_all(&item.to_be_bytes())?;
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn write_len(writer: &mut impl Write, len: usize) -> Result<()> {
+  writer.write_all(&i32::try_from(len)?.to_be_bytes())?;
+  Ok(())
+}
+
+fn write_string(writer: &mut impl Write, value: &str) -> Result<()> {
+  let data = encode_modified_utf8(value);
+  writer.write_all(&u16::try_from(data.len())?.to_be_bytes())?;
+  writer.write_all(&data)?;
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn modified_utf8_encoding() {
+    assert_eq!(encode_modified_utf8("a\0"), vec![b'a', 0xC0, 0x80]);
+    assert_eq!(
+      encode_modified_utf8("🎮"),
+      vec![0xED, 0xA0, 0xBC, 0xED, 0xBE, 0xAE]
+    );
+    assert_eq!(
+      decode_modified_utf8(&[0xED, 0xA0, 0xBC, 0xED, 0xBE, 0xAE]),
+      "🎮"
+    );
+    // plain utf-8 from other tools
+    assert_eq!(decode_modified_utf8("🎮".as_bytes()), "🎮");
+    // 4 byte sequences above the unicode range
+    assert_eq!(
+      decode_modified_utf8(&[0xF7, 0xBF, 0xBF, 0xBF, b'a']),
+      "\u{FFFD}a"
+    );
+  }
+
+  #[test]
+  fn oversized_lengths() {
+    // a byte array claiming i32::MAX bytes followed by nothing
+    let mut data = vec![10, 0, 0, 7, 0, 1, b'a'];
+    data.extend_from_slice(&i32::MAX.to_be_bytes());
+    assert!(read_nbt(&data).is_err());
+
+    // same for a list of compounds
+    let mut data = vec![10, 0, 0, 9, 0, 1, b'a', 10];
+    data.extend_from_slice(&i32::MAX.to_be_bytes());
+    assert!(read_nbt(&data).is_err());
+  }
+
+  #[test]
+  fn string_round_trip() {
+    let names = ["Server", "Ünïcödé 日本", "Emoji 🎮🚀", "Null\0Byte", ""];
+    let root = Tag::Compound(
+      names
+        .iter()
+        .map(|n| (n.to_string(), Tag::String(n.to_string())))
+        .collect(),
+    );
+
+    let data = write_nbt("root", &root).unwrap();
+    let (name, tag) = read_nbt(&data).unwrap();
+    assert_eq!(name, "root");
+    assert_eq!(tag, root);
+  }
+}
@@ -0,0 +1,422 @@
+use std::{
+  net::IpAddr,
+  time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use chrono::Utc;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use tokio::{
+  io::{AsyncRead, AsyncReadExt, AsyncWriteExt},
+  net::TcpStream,
+  time::timeout,
+};
+
+use crate::utils::{
+  chat::{component_text, strip_formatting},
+  dns::resolve_srv,
+};
+
+const DEFAULT_PORT: u16 = 25565;
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+// the status json contains the favicon, so it can be quite large
+const MAX_PACKET_LEN: usize = 1024 * 1024;
+const FAVICON_PREFIX: &str = "data:image/png;base64,";
+const SRV_PREFIX: &str = "_minecraft._tcp.";
+// the last protocol version that understood the legacy ping
+const LEGACY_PROTOCOL: u8 = 74;
+const LEGACY_CHANNEL: &str = "MC|PingHost";
+
+#[derive(Error, Debug)]
+pub enum PingError {
+  #[error("InvalidAddress")]
+  InvalidAddress,
+  #[error("Timeout")]
+  Timeout,
+  #[error("InvalidResponse")]
+  InvalidResponse,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ServerStatus {
+  pub version: String,
+  pub protocol: i32,
+  pub players_online: i64,
+  pub players_max: i64,
+  // plain text without formatting codes
+  pub motd: String,
+  // base64 encoded png
+  pub favicon: Option<String>,
+  // round trip time in milliseconds
+  pub latency: u64,
+}
+
+#[derive(Deserialize)]
+struct StatusResponse {
+  version: Option<StatusVersion>,
+  players: Option<StatusPlayers>,
+  description: Option<Value>,
+  favicon: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StatusVersion {
+  name: String,
+  protocol: i32,
+}
+
+#[derive(Deserialize)]
+struct StatusPlayers {
+  max: i64,
+  online: i64,
+}
+
+/// Splits a server address into host and the port if one is given
+fn parse_address(address: &str) -> Result<(String, Option<u16>)> {
+  let address = address.trim();
+  let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+    // ipv6 addresses have to be enclosed in brackets to specify a port
+    let (host, rest) = rest.split_once(']').ok_or(PingError::InvalidAddress)?;
+    (host, rest.strip_prefix(':'))
+  } else if address.matches(':').count() == 1 {
+    let (host, port) = address.split_once(':').unwrap_or_default();
+    (host, Some(port))
+  } else {
+    (address, None)
+  };
+
+  if host.is_empty() {
+    return Err(PingError::InvalidAddress.into());
+  }
+  let port = match port {
+    Some(port) => Some(port.parse().map_err(|_| PingError::InvalidAddress)?),
+    None => None,
+  };
+
+  Ok((host.to_string(), port))
+}
+
+/// Requests the status of a server using the server list ping protocol.
+/// Falls back to the legacy ping for servers older than 1.7.
+pub async fn ping_server(address: &str) -> Result<ServerStatus> {
+  let (host, port) = match parse_address(address)? {
+    (host, Some(port)) => (host, port),
+    // like the game, srv records are only used if no port is given
+    (host, None) => resolve(host).await,
+  };
+  debug!("Pinging server {host}:{port}");
+
+  match timeout(PING_TIMEOUT, status(&host, port)).await {
+    Ok(Ok(status)) => Ok(status),
+    Ok(Err(err)) => {
+      debug!("Status request to {host}:{port} failed, trying legacy ping: {err:#}");
+      timeout(PING_TIMEOUT, legacy_status(&host, port))
+        .await
+        .map_err(|_| PingError::Timeout)?
+    }
+    Err(_) => Err(PingError::Timeout.into()),
+  }
+}
+
+async fn resolve(host: String) -> (String, u16) {
+  if host.parse::<IpAddr>().is_ok() {
+    return (host, DEFAULT_PORT);
+  }
+
+  match resolve_srv(&format!("{SRV_PREFIX}{host}")).await {
+    Ok(Some(target)) => target,
+    Ok(None) => (host, DEFAULT_PORT),
+    Err(err) => {
+      debug!("Failed to resolve srv record for {host}: {err:#}");
+      (host, DEFAULT_PORT)
+    }
+  }
+}
+
+async fn status(host: &str, port: u16) -> Result<ServerStatus> {
+  let mut stream = TcpStream::connect((host, port)).await?;
+
+  let mut handshake = Vec::new();
+  // the protocol version is irrelevant for status requests
+  write_var_int(&mut handshake, -1);
+  write_string(&mut handshake, host);
+  handshake.extend_from_slice(&port.to_be_bytes());
+  // next state status
+  write_var_int(&mut handshake, 1);
+  send_packet(&mut stream, 0x00, &handshake).await?;
+  send_packet(&mut stream, 0x00, &[]).await?;
+
+  let (id, data) = read_packet(&mut stream).await?;
+  if id != 0x00 {
+    return Err(PingError::InvalidResponse.into());
+  }
+  let mut reader = data.as_slice();
+  let len = read_var_int(&mut reader).await? as usize;
+  let json = reader.get(..len).ok_or(PingError::InvalidResponse)?;
+  let response: StatusResponse = serde_json::from_slice(json)?;
+
+  let start = Instant::now();
+  let payload = Utc::now().timestamp_millis();
+  send_packet(&mut stream, 0x01, &payload.to_be_bytes()).await?;
+  // some servers close the connection instead of answering the ping
+  let latency = match read_packet(&mut stream).await {
+    Ok((0x01, _)) => start.elapsed().as_millis() as u64,
+    _ => 0,
+  };
+
+  let (version, protocol) = response
+    .version
+    .map(|v| (v.name, v.protocol))
+    .unwrap_or_default();
+  let (players_online, players_max) = response
+    .players
+    .map(|p| (p.online, p.max))
+    .unwrap_or_default();
+
+  Ok(ServerStatus {
+    version: strip_formatting(&version),
+    protocol,
+    players_online,
+    players_max,
+    motd: response
+      .description
+      .map(|d| strip_formatting(&component_text(&d)))
+      .unwrap_or_default(),
+    favicon: response
+      .favicon
+      .and_then(|f| f.strip_prefix(FAVICON_PREFIX).map(|f| f.replace('\n', ""))),
+    latency,
+  })
+}
+
+/// The ping used by 1.6, older servers answer it as well but ignore the additional data
+async fn legacy_status(host: &str, port: u16) -> Result<ServerStatus> {
+  let start = Instant::now();
+  let mut stream = TcpStream::connect((host, port)).await?;
+
+  let host_utf16 = utf16_be(host);
+  let mut request = vec![0xFE, 0x01, 0xFA];
+  request.extend_from_slice(&(LEGACY_CHANNEL.len() as u16).to_be_bytes());
+  request.extend_from_slice(&utf16_be(LEGACY_CHANNEL));
+  request.extend_from_slice(&(7 + host_utf16.len() as u16).to_be_bytes());
+  request.push(LEGACY_PROTOCOL);
+  request.extend_from_slice(&(host.encode_utf16().count() as u16).to_be_bytes());
+  request.extend_from_slice(&host_utf16);
+  request.extend_from_slice(&(port as i32).to_be_bytes());
+  stream.write_all(&request).await?;
+
+  // the status is sent as the reason of a kick packet
+  if stream.read_u8().await? != 0xFF {
+    return Err(PingError::InvalidResponse.into());
+  }
+  let len = stream.read_u16().await? as usize;
+  let mut data = vec![0; len * 2];
+  stream.read_exact(&mut data).await?;
+  let latency = start.elapsed().as_millis() as u64;
+
+  let chars = data
+    .chunks_exact(2)
+    .map(|c| u16::from_be_bytes([c[0], c[1]]))
+    .collect::<Vec<_>>();
+  let response = String::from_utf16_lossy(&chars);
+
+  if let Some(fields) = response.strip_prefix("§1\0") {
+    // 1.4 to 1.6: protocol, version, motd, online and max players
+    let fields = fields.split('\0').collect::<Vec<_>>();
+    let [protocol, version, motd, online, max] = fields[..] else {
+      return Err(PingError::InvalidResponse.into());
+    };
+
+    Ok(ServerStatus {
+      version: strip_formatting(version),
+      protocol: protocol.parse().unwrap_or_default(),
+      players_online: online.parse().unwrap_or_default(),
+      players_max: max.parse().unwrap_or_default(),
+      motd: strip_formatting(motd),
+      favicon: None,
+      latency,
+    })
+  } else {
+    // beta 1.8 to 1.3: motd, online and max players separated by §
+    let mut fields = response.rsplitn(3, '§');
+    let (Some(max), Some(online), Some(motd)) = (fields.next(), fields.next(), fields.next())
+    else {
+      return Err(PingError::InvalidResponse.into());
+    };
+
+    Ok(ServerStatus {
+      version: String::new(),
+      protocol: 0,
+      players_online: online.parse().unwrap_or_default(),
+      players_max: max.parse().unwrap_or_default(),
+      motd: motd.to_string(),
+      favicon: None,
+      latency,
+    })
+  }
+}
+
+fn utf16_be(value: &str) -> Vec<u8> {
+  value.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
+
+async fn send_packet(stream: &mut TcpStream, id: i32, data: &[u8]) -> Result<()> {
+  let mut body = Vec::new();
+  write_var_int(&mut body, id);
+  body.extend_from_slice(data);
+
+  let mut packet = Vec::new();
+  write_var_int(&mut packet, body.len() as i32);
+  packet.extend_from_slice(&body);
+  stream.write_all(&packet).await?;
+
+  Ok(())
+}
+
+async fn read_packet(stream: &mut TcpStream) -> Result<(i32, Vec<u8>)> {
+  let len = read_var_int(stream).await? as usize;
+  if len == 0 || len > MAX_PACKET_LEN {
+    return Err(PingError::InvalidResponse.into());
+  }
+  let mut data = vec![0; len];
+  stream.read_exact(&mut data).await?;
+
+  let mut reader = data.as_slice();
+  let id = read_var_int(&mut reader).await?;
+  Ok((id, reader.to_vec()))
+}
+
+fn write_var_int(buf: &mut Vec<u8>, value: i32) {
+  let mut value = value as u32;
+  loop {
+    if value & !0x7F == 0 {
+      buf.push(value as u8);
+      return;
+    }
+    buf.push((value & 0x7F | 0x80) as u8);
+    value >>= 7;
+  }
+}
+
+async fn read_var_int(reader: &mut (impl AsyncRead + Unpin)) -> Result<i32> {
+  let mut value = 0u32;
+  for i in 0..5 {
+    let byte = reader.read_u8().await?;
+    value |= ((byte & 0x7F) as u32) << (7 * i);
+    if byte & 0x80 == 0 {
+      return Ok(value as i32);
+    }
+  }
+  Err(PingError::InvalidResponse.into())
+}
+
+fn write_string(buf: &mut Vec<u8>, value: &str) {
+  write_var_int(buf, value.len() as i32);
+  buf.extend_from_slice(value.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+  use tauri::async_runtime::block_on;
+  use tokio::net::TcpListener;
+
+  use super::*;
+
+  const STATUS_JSON: &str = r#"{"version":{"name":"1.21.4","protocol":769},"players":{"max":20,"online":3},"description":{"text":"§aHello","extra":[{"text":" World"}]},"favicon":"data:image/png;base64,aWNv\nbg=="}"#;
+
+  /// Answers a single status request like a 1.7+ server
+  async fn status_server(listener: TcpListener) -> Result<()> {
+    let (mut stream, _) = listener.accept().await?;
+
+    let (id, data) = read_packet(&mut stream).await?;
+    assert_eq!(id, 0x00);
+    // the next state is the last field of the handshake
+    assert_eq!(data.last(), Some(&1));
+    let (id, _) = read_packet(&mut stream).await?;
+    assert_eq!(id, 0x00);
+
+    let mut response = Vec::new();
+    write_string(&mut response, STATUS_JSON);
+    send_packet(&mut stream, 0x00, &response).await?;
+
+    let (id, payload) = read_packet(&mut stream).await?;
+    assert_eq!(id, 0x01);
+    send_packet(&mut stream, 0x01, &payload).await
+  }
+
+  /// Rejects the modern status request and answers the legacy ping like a 1.6 server
+  async fn legacy_server(listener: TcpListener) -> Result<()> {
+    loop {
+      let (mut stream, _) = listener.accept().await?;
+      if stream.read_u8().await? != 0xFE {
+        continue;
+      }
+
+      let response = utf16_be("§1\u{0}78\u{0}1.6.4\u{0}§cOld server\u{0}1\u{0}10");
+      let mut packet = vec![0xFF];
+      packet.extend_from_slice(&((response.len() / 2) as u16).to_be_bytes());
+      packet.extend_from_slice(&response);
+      stream.write_all(&packet).await?;
+      return Ok(());
+    }
+  }
+
+  #[test]
+  fn parses_addresses() {
+    let cases = [
+      ("example.com", Some(("example.com", None))),
+      ("example.com:25566", Some(("example.com", Some(25566)))),
+      ("127.0.0.1", Some(("127.0.0.1", None))),
+      ("[::1]:25565", Some(("::1", Some(25565)))),
+      ("::1", Some(("::1", None))),
+      ("example.com:port", None),
+      (":25565", None),
+    ];
+
+    for (address, expected) in cases {
+      let parsed = parse_address(address).ok();
+      let parsed = parsed.as_ref().map(|(h, p)| (h.as_str(), *p));
+      assert_eq!(parsed, expected, "{address}");
+    }
+  }
+
+  #[test]
+  fn pings_status_server() {
+    block_on(async {
+      let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+      let address = listener.local_addr().unwrap().to_string();
+      let server = tokio::spawn(status_server(listener));
+
+      let status = ping_server(&address).await.unwrap();
+      server.await.unwrap().unwrap();
+
+      assert_eq!(status.version, "1.21.4");
+      assert_eq!(status.protocol, 769);
+      assert_eq!(status.players_online, 3);
+      assert_eq!(status.players_max, 20);
+      assert_eq!(status.motd, "Hello World");
+      assert_eq!(status.favicon.as_deref(), Some("aWNvbg=="));
+    });
+  }
+
+  #[test]
+  fn pings_legacy_server() {
+    block_on(async {
+      let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+      let address = listener.local_addr().unwrap().to_string();
+      let server = tokio::spawn(legacy_server(listener));
+
+      let status = ping_server(&address).await.unwrap();
+      server.await.unwrap().unwrap();
+
+      assert_eq!(status.version, "1.6.4");
+      assert_eq!(status.protocol, 78);
+      assert_eq!(status.players_online, 1);
+      assert_eq!(status.players_max, 10);
+      assert_eq!(status.motd, "Old server");
+    });
+  }
+}
@@ -21,9 +21,15 @@ pub enum UpdateType {
   Profiles,
   ProfileLogs,
   ProfileQuickPlay,
+  ProfileBackups,
+  ProfileWorlds,
+  ProfileServers,
+  ProfilePacks,
+  ProfileMods,
   //instances
   Instances,
   InstanceLogs,
+  InstanceStats,
   //settings
   Settings,
   //offline
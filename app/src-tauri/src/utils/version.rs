@@ -0,0 +1,326 @@
+use std::cmp::Ordering;
+
+/// Compares versions part by part, numbers numerically and everything else as text.
+/// Missing parts count as zero and a pre-release (`1.0-beta`) is lower than its release.
+/// Build metadata after `+` is ignored.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+  let a = a.split('+').next().unwrap_or_default();
+  let b = b.split('+').next().unwrap_or_default();
+  let (a_release, a_pre) = a.split_once('-').map_or((a, None), |(r, p)| (r, Some(p)));
+  let (b_release, b_pre) = b.split_once('-').map_or((b, None), |(r, p)| (r, Some(p)));
+
+  compare_parts(a_release, b_release).then_with(|| match (a_pre, b_pre) {
+    (None, None) => Ordering::Equal,
+    (None, Some(_)) => Ordering::Greater,
+    (Some(_), None) => Ordering::Less,
+    (Some(a), Some(b)) => compare_parts(a, b),
+  })
+}
+
+fn compare_parts(a: &str, b: &str) -> Ordering {
+  let a = a.split(['.', '-']).collect::<Vec<_>>();
+  let b = b.split(['.', '-']).collect::<Vec<_>>();
+
+  for i in 0..a.len().max(b.len()) {
+    let a = a.get(i).copied().unwrap_or("0");
+    let b = b.get(i).copied().unwrap_or("0");
+    let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+      (Ok(a), Ok(b)) => a.cmp(&b),
+      // numbers are newer than text like `rc` or `beta`
+      (Ok(_), Err(_)) => Ordering::Greater,
+      (Err(_), Ok(_)) => Ordering::Less,
+      (Err(_), Err(_)) => a.cmp(b),
+    };
+    if ordering != Ordering::Equal {
+      return ordering;
+    }
+  }
+
+  Ordering::Equal
+}
+
+/// Checks a version against the predicates used by fabric and quilt,
+/// e.g. `>=1.2 <2`, `~1.2.3`, `^1.2`, `1.20.x` or `*`, alternatives are separated by `||`
+pub fn matches_fabric_predicate(predicate: &str, version: &str) -> bool {
+  predicate.split("||").any(|alternative| {
+    alternative
+      .split_whitespace()
+      .all(|p| matches_single_predicate(p, version))
+  })
+}
+
+fn matches_single_predicate(predicate: &str, version: &str) -> bool {
+  if predicate == "*" {
+    return true;
+  }
+
+  let (operator, required) = ["<=", ">=", "<", ">", "=", "~", "^"]
+    .into_iter()
+    .find_map(|op| predicate.strip_prefix(op).map(|v| (op, v)))
+    .unwrap_or(("", predicate));
+
+  // wildcards like 1.20.x only compare the parts before them
+  if let Some(prefix) = wildcard_prefix(required) {
+    let parts = prefix.len();
+    let prefix = prefix.join(".");
+    let version_prefix = version
+      .split(['.', '-', '+'])
+      .take(parts)
+      .collect::<Vec<_>>()
+      .join(".");
+    let ordering = compare_parts(&version_prefix, &prefix);
+    return match operator {
+      ">=" => ordering != Ordering::Less,
+      "<=" => ordering != Ordering::Greater,
+      ">" => ordering == Ordering::Greater,
+      "<" => ordering == Ordering::Less,
+      _ => ordering == Ordering::Equal,
+    };
+  }
+
+  let ordering = compare_versions(version, required);
+  match operator {
+    ">=" => ordering != Ordering::Less,
+    "<=" => ordering != Ordering::Greater,
+    ">" => ordering == Ordering::Greater,
+    "<" => ordering == Ordering::Less,
+    // same minor version
+    "~" => ordering != Ordering::Less && same_prefix(version, required, 2),
+    // same major version
+    "^" => ordering != Ordering::Less && same_prefix(version, required, 1),
+    _ => ordering == Ordering::Equal,
+  }
+}
+
+/// The parts before the first `x`, `X` or `*` part, e.g. `1.20` for `1.20.x`
+fn wildcard_prefix(version: &str) -> Option<Vec<&str>> {
+  let parts = version.split('.').collect::<Vec<_>>();
+  let index = parts.iter().position(|p| matches!(*p, "x" | "X" | "*"))?;
+  Some(parts[..index].to_vec())
+}
+
+fn same_prefix(a: &str, b: &str, parts: usize) -> bool {
+  let a = a.split(['.', '-', '+']).take(parts).collect::<Vec<_>>();
+  let b = b.split(['.', '-', '+']).take(parts).collect::<Vec<_>>();
+  compare_parts(&a.join("."), &b.join(".")) == Ordering::Equal
+}
+
+/// Converts a Minecraft version id to the semver form used by fabric and quilt mods,
+/// the same way fabric's `McVersionLookup` does, e.g. `1.20.2-pre1` to `1.20.2-beta.1`
+/// or `b1.7.3` to `1.0.0-beta.7.3`. Snapshots like `23w31a` need the release they lead up to.
+/// Returns `None` for ids without a known semver form.
+pub fn normalize_mc_version(id: &str, snapshot_release: Option<&str>) -> Option<String> {
+  let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+  let is_release = |s: &str| {
+    let parts = s.split('.').collect::<Vec<_>>();
+    (2..=3).contains(&parts.len()) && parts.iter().all(|p| is_number(p))
+  };
+
+  if is_release(id) {
+    return Some(id.to_string());
+  }
+
+  // 1.14 to 1.14.2 used `1.14 Pre-Release 1` instead of `1.14-pre1`
+  if let Some((release, pre)) = id.split_once(" Pre-Release ")
+    && is_release(release)
+    && is_number(pre)
+  {
+    return Some(format!("{release}-beta.{pre}"));
+  }
+  if let Some((release, suffix)) = id.split_once('-')
+    && is_release(release)
+  {
+    if let Some(pre) = suffix.strip_prefix("pre").filter(|p| is_number(p)) {
+      return Some(format!("{release}-beta.{pre}"));
+    }
+    if let Some(rc) = suffix.strip_prefix("rc").filter(|r| is_number(r)) {
+      return Some(format!("{release}-rc.{rc}"));
+    }
+    return None;
+  }
+
+  // weekly snapshots, e.g. 23w31a
+  if let Some((year, rest)) = id.split_once('w')
+    && is_number(year)
+    && year.len() == 2
+    && rest.len() == 3
+    && is_number(&rest[..2])
+    && rest.as_bytes()[2].is_ascii_lowercase()
+  {
+    let release = snapshot_release.filter(|r| is_release(r))?;
+    return Some(format!(
+      "{release}-alpha.{year}.{}.{}",
+      &rest[..2],
+      &rest[2..]
+    ));
+  }
+
+  for (prefix, pre) in [("b1.", "beta"), ("a1.", "alpha")] {
+    if let Some(rest) = id.strip_prefix(prefix) {
+      let rest = rest.replace(['_', '-'], ".");
+      if rest.split('.').all(|p| !p.is_empty()) {
+        return Some(format!("1.0.0-{pre}.{rest}"));
+      }
+    }
+  }
+
+  None
+}
+
+/// Checks a version against a maven version range as used by forge,
+/// e.g. `[1.2,2)`, `(,1.0]`, `[1.0]` or multiple ranges like `[1,2),[3,)`.
+/// A plain version is only a recommendation and always matches.
+pub fn matches_maven_range(range: &str, version: &str) -> bool {
+  let range = range.trim();
+  if range.is_empty() || range == "*" || !range.starts_with(['[', '(']) {
+    return true;
+  }
+
+  let mut start = 0;
+  let mut ranges = Vec::new();
+  for (i, c) in range.char_indices() {
+    match c {
+      '[' | '(' => start = i,
+      ']' | ')' => ranges.push(&range[start..=i]),
+      _ => (),
+    }
+  }
+
+  ranges.into_iter().any(|r| matches_single_range(r, version))
+}
+
+fn matches_single_range(range: &str, version: &str) -> bool {
+  let lower_inclusive = range.starts_with('[');
+  let upper_inclusive = range.ends_with(']');
+  let inner = &range[1..range.len() - 1];
+
+  let Some((lower, upper)) = inner.split_once(',') else {
+    // [1.0] is an exact version
+    return compare_versions(version, inner.trim()) == Ordering::Equal;
+  };
+  let (lower, upper) = (lower.trim(), upper.trim());
+
+  let lower_ok = lower.is_empty()
+    || match compare_versions(version, lower) {
+      Ordering::Greater => true,
+      Ordering::Equal => lower_inclusive,
+      Ordering::Less => false,
+    };
+  let upper_ok = upper.is_empty()
+    || match compare_versions(version, upper) {
+      Ordering::Less => true,
+      Ordering::Equal => upper_inclusive,
+      Ordering::Greater => false,
+    };
+
+  lower_ok && upper_ok
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn fabric_predicates() {
+    let cases = [
+      ("*", "1.20.1", true),
+      ("1.20.1", "1.20.1", true),
+      ("=1.20.1", "1.20.2", false),
+      (">=1.20", "1.20.1", true),
+      (">=1.20", "1.19.4", false),
+      (">=1.20 <1.21", "1.20.6", true),
+      (">=1.20 <1.21", "1.21", false),
+      ("<1.20 || >=1.21", "1.20.4", false),
+      ("<1.20 || >=1.21", "1.21.1", true),
+      ("~1.20.1", "1.20.4", true),
+      ("~1.20.1", "1.21", false),
+      ("^1.2.3", "1.9.0", true),
+      ("^1.2.3", "2.0.0", false),
+      ("1.20.x", "1.20.4", true),
+      ("1.20.x", "1.21", false),
+      ("1.x", "1.21.4", true),
+      (">=1.20.x", "1.20", true),
+      (">=1.20.x", "1.19.4", false),
+      ("<1.20.x", "1.19.4", true),
+      ("<1.20.x", "1.20.1", false),
+      ("<=1.20.x", "1.20.6", true),
+      (">1.20.x", "1.20.6", false),
+      (">1.20.x", "1.21", true),
+      // an x inside a part is not a wildcard
+      (">=0.4.0-experimental", "0.4.1", true),
+      (">=0.4.0-experimental", "0.3.9", false),
+      (">=1.20.2-alpha.23.31.a", "1.20.2-alpha.23.33.a", true),
+      (">=1.20.2", "1.20.2-alpha.23.31.a", false),
+      (">=1.0.0-beta.7.3", "1.0.0-beta.7.3", true),
+      ("1.0.0-beta.7.3", "1.0.0-beta.8.1", false),
+      (">=0.14.0", "0.16.9+build.1", true),
+    ];
+
+    for (predicate, version, expected) in cases {
+      assert_eq!(
+        matches_fabric_predicate(predicate, version),
+        expected,
+        "{predicate} with {version}"
+      );
+    }
+  }
+
+  #[test]
+  fn maven_ranges() {
+    let cases = [
+      ("", "47.2.0", true),
+      ("*", "47.2.0", true),
+      // plain versions are only recommendations
+      ("47.1.0", "46.0.0", true),
+      ("[47,)", "47.2.0", true),
+      ("[47,)", "46.0.14", false),
+      ("[1.20.1,1.21)", "1.20.1", true),
+      ("[1.20.1,1.21)", "1.21", false),
+      ("(1.20.1,1.21]", "1.20.1", false),
+      ("(1.20.1,1.21]", "1.21", true),
+      ("(,1.0]", "0.9", true),
+      ("(,1.0]", "1.0.1", false),
+      ("[1.0]", "1.0", true),
+      ("[1.0]", "1.0.1", false),
+      ("[1,2),[3,)", "2.5", false),
+      ("[1,2),[3,)", "3.1", true),
+      ("[20.4.0-beta,)", "20.4.80-beta", true),
+    ];
+
+    for (range, version, expected) in cases {
+      assert_eq!(
+        matches_maven_range(range, version),
+        expected,
+        "{range} with {version}"
+      );
+    }
+  }
+
+  #[test]
+  fn normalizes_mc_versions() {
+    let cases = [
+      ("1.20.1", None, Some("1.20.1")),
+      ("1.21", None, Some("1.21")),
+      ("1.20.2-pre1", None, Some("1.20.2-beta.1")),
+      ("1.20.1-rc1", None, Some("1.20.1-rc.1")),
+      ("1.14 Pre-Release 2", None, Some("1.14-beta.2")),
+      ("23w31a", Some("1.20.2"), Some("1.20.2-alpha.23.31.a")),
+      ("23w31a", None, None),
+      ("b1.7.3", None, Some("1.0.0-beta.7.3")),
+      ("b1.8.1", None, Some("1.0.0-beta.8.1")),
+      ("a1.2.6", None, Some("1.0.0-alpha.2.6")),
+      ("b1.1_02", None, Some("1.0.0-beta.1.02")),
+      ("1.RV-Pre1", None, None),
+      ("20w14infinite", Some("1.16"), None),
+      ("1.18_experimental-snapshot-1", None, None),
+    ];
+
+    for (id, release, expected) in cases {
+      assert_eq!(
+        normalize_mc_version(id, release).as_deref(),
+        expected,
+        "{id}"
+      );
+    }
+  }
+}
@@ -0,0 +1,123 @@
+use std::{
+  collections::HashMap,
+  path::{Component, Path, PathBuf},
+};
+
+use anyhow::Result;
+use async_zip::{
+  Compression, ZipEntryBuilder,
+  base::{read::mem, write::ZipFileWriter},
+  tokio::read::fs::ZipFileReader,
+};
+use log::debug;
+use tokio::fs::{self, File};
+use tokio_util::compat::Compat;
+
+pub type TokioZipWriter = ZipFileWriter<Compat<File>>;
+
+/// Adds the files relative to `base` below `prefix` to the zip.
+/// Files that can not be read, e.g. because they are in use, are skipped.
+pub async fn write_files_to_zip(
+  writer: &mut TokioZipWriter,
+  base: &Path,
+  files: &[PathBuf],
+  prefix: &str,
+) -> Result<()> {
+  for relative in files {
+    let data = match fs::read(base.join(relative)).await {
+      Ok(data) => data,
+      Err(e) => {
+        debug!("Skipping {} in zip: {e}", relative.display());
+        continue;
+      }
+    };
+
+    let name = format!("{prefix}{}", archive_name(relative));
+    writer
+      .write_entry_whole(
+        ZipEntryBuilder::new(name.into(), Compression::Deflate),
+        &data,
+      )
+      .await?;
+  }
+
+  Ok(())
+}
+
+/// Reads the entries of a zip accepted by `filter`
+pub async fn read_files_from_zip(
+  path: &Path,
+  filter: impl Fn(&str) -> bool,
+) -> Result<HashMap<String, Vec<u8>>> {
+  let zip = ZipFileReader::new(path).await?;
+  let mut files = HashMap::new();
+
+  // only open readers for the wanted entries, jars can contain thousands of files
+  for (i, entry) in zip.file().entries().iter().enumerate() {
+    let Ok(name) = entry.filename().as_str() else {
+      continue;
+    };
+    if !filter(name) {
+      continue;
+    }
+
+    let name = name.to_string();
+    let mut data = Vec::new();
+    zip
+      .reader_with_entry(i)
+      .await?
+      .read_to_end_checked(&mut data)
+      .await?;
+    files.insert(name, data);
+  }
+
+  Ok(files)
+}
+
+/// Same as [`read_files_from_zip`] for a zip that is already in memory, e.g. a nested jar
+pub async fn read_files_from_zip_data(
+  data: Vec<u8>,
+  filter: impl Fn(&str) -> bool,
+) -> Result<HashMap<String, Vec<u8>>> {
+  let zip = mem::ZipFileReader::new(data).await?;
+  let mut files = HashMap::new();
+
+  for (i, entry) in zip.file().entries().iter().enumerate() {
+    let Ok(name) = entry.filename().as_str() else {
+      continue;
+    };
+    if !filter(name) {
+      continue;
+    }
+
+    let name = name.to_string();
+    let mut data = Vec::new();
+    zip
+      .reader_with_entry(i)
+      .await?
+      .read_to_end_checked(&mut data)
+      .await?;
+    files.insert(name, data);
+  }
+
+  Ok(files)
+}
+
+/// Zip entries always use forward slashes
+pub fn archive_name(relative: &Path) -> String {
+  relative
+    .components()
+    .map(|c| c.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+/// Returns None for paths that would escape the directory they are extracted to
+pub fn safe_path(path: &str) -> Option<PathBuf> {
+  let path = PathBuf::from(path);
+  if path.as_os_str().is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+    return None;
+  }
+
+  Some(path)
+}
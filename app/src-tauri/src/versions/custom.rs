@@ -0,0 +1,160 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+use tokio::fs;
+
+use crate::{
+  path,
+  utils::file::read_parse_file,
+  versions::{
+    meta::{
+      java::{JavaVersion, Library},
+      minecraft::{Argument, AssetIndex, Downloads, Version, VersionType},
+    },
+    paths::MCVersionPath,
+  },
+};
+
+// versions that inherit from each other would otherwise be followed forever
+pub const MAX_INHERITANCE_DEPTH: usize = 16;
+
+#[derive(Error, Debug)]
+pub enum CustomVersionError {
+  #[error("InheritanceLoop")]
+  InheritanceLoop,
+}
+
+/// A version json that only contains what differs from the version it inherits from
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct PartialVersion {
+  id: String,
+  inherits_from: Option<String>,
+  arguments: Option<PartialArguments>,
+  asset_index: Option<AssetIndex>,
+  downloads: Option<Downloads>,
+  java_version: Option<JavaVersion>,
+  #[serde(default)]
+  libraries: Vec<Library>,
+  main_class: Option<String>,
+  minecraft_arguments: Option<String>,
+  r#type: Option<VersionType>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct PartialArguments {
+  #[serde(default)]
+  game: Vec<Argument>,
+  #[serde(default)]
+  jvm: Vec<Argument>,
+}
+
+/// Reads a version json and merges the versions it inherits from into it
+pub async fn resolve_version(data_dir: &PathBuf, id: &str) -> Result<Version> {
+  let mut children = Vec::new();
+  let mut current = id.to_string();
+
+  let mut version = loop {
+    if children.len() > MAX_INHERITANCE_DEPTH {
+      return Err(CustomVersionError::InheritanceLoop.into());
+    }
+
+    let path = MCVersionPath::new(data_dir, &current).version_manifest();
+    let data: Value = read_parse_file(&path).await?;
+    let partial: PartialVersion = serde_json::from_value(data.clone())?;
+    match partial.inherits_from.clone() {
+      Some(parent) => {
+        current = parent;
+        children.push(partial);
+      }
+      None => break serde_json::from_value::<Version>(data)?,
+    }
+  };
+
+  for child in children.into_iter().rev() {
+    inherit(&mut version, child);
+  }
+
+  Ok(version)
+}
+
+/// The version a custom version inherits from
+pub async fn parent_version(data_dir: &PathBuf, id: &str) -> Result<Option<String>> {
+  let path = MCVersionPath::new(data_dir, id).version_manifest();
+  let partial: PartialVersion = read_parse_file(&path).await?;
+  Ok(partial.inherits_from)
+}
+
+/// All version folders with a version json, including the downloaded mojang versions
+pub async fn local_versions(data_dir: &PathBuf) -> Result<Vec<String>> {
+  let dir = MCVersionPath::new(data_dir, "").version_root().clone();
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut versions = Vec::new();
+  let mut entries = fs::read_dir(&dir).await?;
+  while let Some(entry) = entries.next_entry().await? {
+    let Some(id) = entry.file_name().to_str().map(String::from) else {
+      continue;
+    };
+    if path!(&dir, &id, format!("{id}.json")).is_file() {
+      versions.push(id);
+    }
+  }
+  versions.sort();
+
+  Ok(versions)
+}
+
+fn inherit(version: &mut Version, child: PartialVersion) {
+  version.id = child.id;
+
+  // libraries of the child come first and replace other versions of the same library
+  let keys = child
+    .libraries
+    .iter()
+    .map(|l| library_key(&l.name))
+    .collect::<Vec<_>>();
+  version
+    .libraries
+    .retain(|l| !keys.contains(&library_key(&l.name)));
+  version.libraries.splice(0..0, child.libraries);
+
+  if let Some(arguments) = child.arguments {
+    version.arguments.game.extend(arguments.game);
+    version.arguments.jvm.extend(arguments.jvm);
+  }
+  if let Some(asset_index) = child.asset_index {
+    version.asset_index = Some(asset_index);
+  }
+  if let Some(downloads) = child.downloads {
+    version.downloads = Some(downloads);
+  }
+  if let Some(java_version) = child.java_version {
+    version.java_version = java_version;
+  }
+  if let Some(main_class) = child.main_class {
+    version.main_class = main_class;
+  }
+  if child.minecraft_arguments.is_some() {
+    version.minecraft_arguments = child.minecraft_arguments;
+  }
+  if let Some(r#type) = child.r#type {
+    version.r#type = r#type;
+  }
+}
+
+/// The maven name without the version, e.g. `org.ow2.asm:asm` or `org.lwjgl:lwjgl:natives-linux`
+fn library_key(name: &str) -> String {
+  name
+    .split(':')
+    .enumerate()
+    .filter(|(i, _)| *i != 2)
+    .map(|(_, part)| part)
+    .collect::<Vec<_>>()
+    .join(":")
+}
@@ -1,13 +1,14 @@
-use std::time::Instant;
+use std::{path::Path, time::Instant};
 
 use anyhow::Result;
 use log::debug;
 use reqwest::Client;
 use tauri::AppHandle;
+use tokio::fs;
 
 use crate::{
   path,
-  utils::{download::download_file, file::file_hash},
+  utils::{dir::copy_file_linked, download::download_file, file::file_hash, zip::safe_path},
   versions::{
     download::{check_pool, download_pool},
     event::DownloadCheckStatus,
@@ -70,3 +71,34 @@ pub async fn check_download_version_assets(
 
   Ok(())
 }
+
+/// Copies the assets from the object store to `target` under their actual names,
+/// as expected by versions with a virtual or resources asset index.
+/// Files that already exist with the right size are kept.
+pub async fn copy_assets_by_name(assets: &Assets, mc_path: &MCPath, target: &Path) -> Result<()> {
+  debug!("Copying assets by name to {}", target.display());
+  for (name, asset) in &assets.objects {
+    let Some(relative) = safe_path(name) else {
+      continue;
+    };
+    let dst = path!(target, relative);
+    if let Ok(meta) = fs::metadata(&dst).await
+      && meta.len() == asset.size as u64
+    {
+      continue;
+    }
+
+    let src = path!(
+      mc_path.assets_objects_path(),
+      &asset.hash[0..2],
+      &asset.hash
+    );
+    if let Some(parent) = dst.parent() {
+      fs::create_dir_all(parent).await?;
+    }
+    let _ = fs::remove_file(&dst).await;
+    copy_file_linked(&src, &dst).await?;
+  }
+
+  Ok(())
+}
@@ -18,6 +18,8 @@ use crate::{
     check_rule,
     download::{check_pool, download_pool},
     event::DownloadCheckStatus,
+    loader::util::download_maven_future,
+    maven::MavenArtifact,
     meta::minecraft::Version,
     paths::{JavaVersionPath, MCPath},
   },
@@ -34,10 +36,28 @@ pub async fn check_download_version_java_libraries(
   debug!("Collecting checks for java libraries");
   let mut futures_1 = Vec::new();
   let mut futures_2 = Vec::new();
+  let mut maven_futures = Vec::new();
   let mut libs = Vec::new();
 
   'l: for library in &version.libraries {
     let Some(downloads) = &library.downloads else {
+      // libraries of custom versions can name a maven repository instead,
+      // without one they have to be in the libraries folder already
+      if let Some(url) = &library.url
+        && !MavenArtifact::new(&library.name)?
+          .full_path(mc_path)
+          .exists()
+      {
+        maven_futures.push(download_maven_future(
+          mc_path.clone(),
+          library.name.clone(),
+          client.clone(),
+          url.as_str().trim_end_matches('/').to_string(),
+          None,
+          None,
+        ));
+      }
+      libs.push(library.name.clone());
       continue;
     };
 
@@ -171,6 +191,26 @@ pub async fn check_download_version_java_libraries(
     now.elapsed()
   );
 
+  debug!("Checking {} maven libraries", maven_futures.len());
+  let futures = check_pool(
+    maven_futures,
+    handle,
+    update_id,
+    DownloadCheckStatus::LibraryCheck,
+  )
+  .await?;
+  let mut downloads = Vec::with_capacity(futures.len());
+  for fut in futures {
+    downloads.push(fut.await?);
+  }
+  download_pool(
+    downloads,
+    handle.clone(),
+    update_id,
+    DownloadCheckStatus::LibraryDownload,
+  )
+  .await?;
+
   Ok(libs)
 }
 
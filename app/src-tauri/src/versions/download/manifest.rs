@@ -54,11 +54,11 @@ pub async fn check_assets_manifest(
   update_id: usize,
 ) -> Result<Assets> {
   emit_download_check_status(handle, DownloadCheckStatus::AssetsManifestCheck, update_id);
-  let assets_index = &info.asset_index;
-  let path = path!(
-    mc_path.assets_index_path(),
-    format!("{}.json", assets_index.id)
-  );
+  let Some(assets_index) = &info.asset_index else {
+    debug!("Version {} has no assets index", info.id);
+    return Ok(Assets::default());
+  };
+  let path = mc_path.assets_index(&assets_index.id);
 
   debug!("Checking assets manifest {}", assets_index.id);
   if !file_hash(&assets_index.sha1, &path).await? {
@@ -118,14 +118,24 @@ pub async fn check_java_manifest(
 pub async fn check_client(
   version: &Version,
   version_path: &MCVersionPath,
+  custom: bool,
   client: &Client,
   handle: &AppHandle,
   update_id: usize,
 ) -> Result<()> {
   emit_download_check_status(handle, DownloadCheckStatus::ClientCheck, update_id);
-  let download = &version.downloads.client;
   let path = version_path.client_jar();
 
+  // a local build would be replaced by the jar of the version it inherits from
+  if custom && path.exists() {
+    debug!("Using local client jar for version {}", version.id);
+    return Ok(());
+  }
+  let Some(downloads) = &version.downloads else {
+    return Err(DownloadError::NotFound.into());
+  };
+  let download = &downloads.client;
+
   debug!("Checking client jar for version {}", version.id);
   if !file_hash(&download.sha1, &path).await? {
     emit_download_check_status(
@@ -16,6 +16,7 @@ use thiserror::Error;
 use crate::{
   utils::future::FuturePool,
   versions::{
+    custom::resolve_version,
     download::{
       assets::check_download_version_assets,
       java::check_download_java_files,
@@ -31,7 +32,7 @@ use crate::{
   },
 };
 
-mod assets;
+pub mod assets;
 mod java;
 mod libraries;
 mod manifest;
@@ -44,8 +45,12 @@ pub enum DownloadError {
   NotSupported,
 }
 
+/// Checks all files of a version, `mc` is the mojang version it is based on
+/// which is `None` for custom versions that do not inherit from one
+#[allow(clippy::too_many_arguments)]
 pub async fn check_download_version(
-  mc: &ManifestVersion,
+  id: &str,
+  mc: Option<&ManifestVersion>,
   java: &PlatformVersion,
   data_dir: &PathBuf,
   client: &Client,
@@ -54,14 +59,21 @@ pub async fn check_download_version(
   loader_version: Option<Box<dyn LoaderVersion>>,
 ) -> Result<()> {
   let mc_path = MCPath::new(data_dir);
-  let version_path = MCVersionPath::new(data_dir, &mc.id);
+  let version_path = MCVersionPath::new(data_dir, id);
 
-  let version = check_version_manifest(mc, &version_path, client, handle, update_id).await?;
-  let java_path = JavaVersionPath::new(data_dir, version.java_version.component, mc.id.clone());
+  if let Some(mc) = mc {
+    let mc_version_path = MCVersionPath::new(data_dir, &mc.id);
+    check_version_manifest(mc, &mc_version_path, client, handle, update_id).await?;
+  }
+  let version = resolve_version(data_dir, id).await?;
+  // custom builds are not part of the manifest and can not be checked against a hash
+  let custom = mc.is_none_or(|mc| mc.id != id);
+
+  let java_path = JavaVersionPath::new(data_dir, version.java_version.component, id.to_string());
   let assets = check_assets_manifest(&version, &mc_path, client, handle, update_id).await?;
   let files = check_java_manifest(&version, java, &java_path, client, handle, update_id).await?;
 
-  check_client(&version, &version_path, client, handle, update_id).await?;
+  check_client(&version, &version_path, custom, client, handle, update_id).await?;
 
   check_download_version_assets(&assets, &mc_path, client, handle, update_id).await?;
   check_download_java_files(&files, client, &java_path, handle, update_id).await?;
@@ -110,8 +122,9 @@ pub async fn check_download_version(
     debug!("Running mod loader preprocess");
     emit_download_check_status(handle, DownloadCheckStatus::ModLoaderPreprocess, update_id);
 
+    let status = |status| emit_download_check_status(handle, status, update_id);
     loader
-      .preprocess(&version_path, &mc_path, java_path.bin_path())
+      .preprocess(&version_path, &mc_path, java_path.bin_path(), &status)
       .await?;
 
     emit_download_check_status(
@@ -36,6 +36,8 @@ pub enum DownloadCheckStatus {
   ModLoaderFilesDownloadInfo,
   ModLoaderFilesDownload(usize, usize),
   ModLoaderPreprocess,
+  // the last line printed by the running processor
+  ModLoaderPreprocessStep(usize, usize, String),
   ModLoaderPreprocessDone,
   Done,
 }
@@ -1,7 +1,7 @@
 use std::{
   collections::{HashMap, HashSet},
   ffi::OsString,
-  path::PathBuf,
+  path::{Path, PathBuf},
   process::Stdio,
 };
 
@@ -15,9 +15,14 @@ use crate::{
   utils::file::read_parse_file,
   versions::{
     check_feature,
+    custom::resolve_version,
+    download::assets::copy_assets_by_name,
     loader::{Arguments, ClasspathEntry, LoaderVersion},
     maven::MavenArtifact,
-    meta::{Features, minecraft::ArgumentValue},
+    meta::{
+      Features,
+      minecraft::{ArgumentValue, Assets},
+    },
     paths::{JavaVersionPath, MCPath, MCVersionPath, QUICK_PLAY},
   },
 };
@@ -48,6 +53,15 @@ pub struct LaunchArgs {
   pub jvm_settings: JvmSettings,
 }
 
+// pre 1.6 versions read their assets from this folder in the game dir
+const RESOURCES_DIR: &str = "resources";
+
+struct LaunchContext {
+  classpath: String,
+  // the assets folder for versions that read assets by name
+  game_assets: PathBuf,
+}
+
 pub enum QuickPlay {
   Singleplayer { world_name: String },
   Multiplayer { uri: String },
@@ -55,7 +69,7 @@ pub enum QuickPlay {
 }
 
 impl LaunchArgs {
-  fn replace_vars(&self, version: &Version, arg: &str, classpath: &str) -> String {
+  fn replace_vars(&self, version: &Version, arg: &str, ctx: &LaunchContext) -> String {
     let mut quick_singleplayer = String::new();
     let mut quick_multiplayer = String::new();
     let mut quick_realms = String::new();
@@ -82,6 +96,10 @@ impl LaunchArgs {
       .replace("${auth_uuid}", &self.player_uuid)
       .replace("${user_type}", &self.user_type)
       .replace("${auth_access_token}", &self.access_token)
+      .replace(
+        "${auth_session}",
+        &format!("token:{}:{}", self.access_token, self.player_uuid),
+      )
       .replace("${auth_xuid}", "0")
       .replace(
         "${game_directory}",
@@ -94,7 +112,11 @@ impl LaunchArgs {
         "${assets_root}",
         &mc_path.assets_path().display().to_string(),
       )
-      .replace("${assets_index_name}", &version.asset_index.id)
+      .replace(
+        "${assets_index_name}",
+        version.asset_index.as_ref().map_or("", |index| &index.id),
+      )
+      .replace("${game_assets}", &ctx.game_assets.display().to_string())
       .replace("${version_type}", &version.r#type.to_string())
       .replace("${launcher_version}", &self.launcher_version)
       .replace("${launcher_name}", &self.launcher_name)
@@ -109,7 +131,7 @@ impl LaunchArgs {
         .display()
         .to_string(),
       )
-      .replace("${classpath}", classpath)
+      .replace("${classpath}", &ctx.classpath)
       .replace("${quickPlayPath}", QUICK_PLAY)
       .replace("${quickPlaySingleplayer}", &quick_singleplayer)
       .replace("${quickPlayMultiplayer}", &quick_multiplayer)
@@ -146,7 +168,7 @@ pub async fn launch_minecraft_version(args: &LaunchArgs) -> Result<Child> {
     args.version
   );
   let version_path = MCVersionPath::new(&args.data_dir, &args.version);
-  let version: Version = read_parse_file(&version_path.version_manifest()).await?;
+  let version = resolve_version(&args.data_dir, &args.version).await?;
   let mc_path = MCPath::new(&args.data_dir);
 
   let java_path = JavaVersionPath::new(
@@ -154,10 +176,14 @@ pub async fn launch_minecraft_version(args: &LaunchArgs) -> Result<Child> {
     version.java_version.component,
     args.version.clone(),
   );
-  let classpath = args.classpath(&version, &mc_path, &version_path).await?;
+  let game_path = path!(&args.data_dir, &args.working_sub_dir);
+  let ctx = LaunchContext {
+    classpath: args.classpath(&version, &mc_path, &version_path).await?,
+    game_assets: game_assets(&version, &mc_path, &game_path).await?,
+  };
 
-  let mut jvm_args = jvm_args(args, &version, &classpath);
-  let mut game_args = game_args(args, &version, &classpath);
+  let mut jvm_args = jvm_args(args, &version, &ctx);
+  let mut game_args = game_args(args, &version, &ctx);
   let mut env_vars = HashMap::new();
 
   if let Some(loader) = &args.loader {
@@ -169,14 +195,18 @@ pub async fn launch_minecraft_version(args: &LaunchArgs) -> Result<Child> {
     } = loader.arguments(&version_path).await?;
 
     for arg in &loader_jvm_args {
-      jvm_args.push(args.replace_vars(&version, arg, &classpath));
+      jvm_args.push(args.replace_vars(&version, arg, &ctx));
     }
 
     if overwrite_game_args {
       game_args.clear();
     }
     for arg in &loader_game_args {
-      game_args.push(args.replace_vars(&version, arg, &classpath));
+      game_args.push(args.replace_vars(&version, arg, &ctx));
+    }
+    // legacy loaders replace the arguments with their own minecraftArguments
+    if overwrite_game_args {
+      game_args.extend(legacy_resolution_args(args));
     }
   }
 
@@ -191,7 +221,6 @@ pub async fn launch_minecraft_version(args: &LaunchArgs) -> Result<Child> {
     version.main_class.clone()
   };
 
-  let game_path = path!(&args.data_dir, &args.working_sub_dir);
   let jre_bin = java_path.bin_path();
 
   let mut command = Command::new(jre_bin);
@@ -200,6 +229,7 @@ pub async fn launch_minecraft_version(args: &LaunchArgs) -> Result<Child> {
   Command::creation_flags(&mut command, DETACHED_PROCESS);
 
   command
+    .stdin(Stdio::piped())
     .stdout(Stdio::piped())
     .stderr(Stdio::piped())
     .current_dir(game_path)
@@ -217,21 +247,65 @@ pub async fn launch_minecraft_version(args: &LaunchArgs) -> Result<Child> {
   Ok(command.spawn()?)
 }
 
-fn jvm_args(args: &LaunchArgs, version: &Version, classpath: &str) -> Vec<String> {
+/// Prepares the assets of versions that read them by name and returns the folder they are read from
+async fn game_assets(version: &Version, mc_path: &MCPath, game_path: &Path) -> Result<PathBuf> {
+  // only versions before 1.13 use a virtual or resources asset index
+  let (Some(_), Some(index)) = (&version.minecraft_arguments, &version.asset_index) else {
+    return Ok(mc_path.assets_path());
+  };
+
+  let index = &index.id;
+  let assets: Assets = read_parse_file(&mc_path.assets_index(index)).await?;
+  let target = if assets.map_to_resources {
+    path!(game_path, RESOURCES_DIR)
+  } else if assets.is_virtual {
+    mc_path.assets_virtual_path(index)
+  } else {
+    return Ok(mc_path.assets_path());
+  };
+
+  copy_assets_by_name(&assets, mc_path, &target).await?;
+  Ok(target)
+}
+
+fn legacy_resolution_args(args: &LaunchArgs) -> Vec<String> {
+  if !args.game_settings.use_custom {
+    return Vec::new();
+  }
+
+  vec![
+    "--width".into(),
+    args.game_settings.width.to_string(),
+    "--height".into(),
+    args.game_settings.height.to_string(),
+  ]
+}
+
+fn jvm_args(args: &LaunchArgs, version: &Version, ctx: &LaunchContext) -> Vec<String> {
   let mut jvm_args = Vec::new();
 
   for arg in &version.arguments.jvm {
     if let Argument::String(arg) = arg {
-      jvm_args.push(args.replace_vars(version, arg, classpath));
+      jvm_args.push(args.replace_vars(version, arg, ctx));
     }
   }
 
   jvm_args
 }
 
-fn game_args(args: &LaunchArgs, version: &Version, classpath: &str) -> Vec<String> {
+fn game_args(args: &LaunchArgs, version: &Version, ctx: &LaunchContext) -> Vec<String> {
   let mut game_args = Vec::new();
 
+  // before 1.13 the arguments are a single string without rules
+  if let Some(minecraft_arguments) = &version.minecraft_arguments {
+    for arg in minecraft_arguments.split_whitespace() {
+      game_args.push(args.replace_vars(version, arg, ctx));
+    }
+    game_args.extend(legacy_resolution_args(args));
+
+    return game_args;
+  }
+
   let mut features = Features {
     has_quick_plays_support: Some(true),
     has_custom_resolution: Some(args.game_settings.use_custom),
@@ -254,17 +328,17 @@ fn game_args(args: &LaunchArgs, version: &Version, classpath: &str) -> Vec<Strin
 
   for arg in &version.arguments.game {
     match arg {
-      Argument::String(s) => game_args.push(args.replace_vars(version, s, classpath)),
+      Argument::String(s) => game_args.push(args.replace_vars(version, s, ctx)),
       Argument::Object(arg) => {
         if arg.rules.iter().all(|rule| check_feature(rule, &features)) {
           match &arg.value {
             ArgumentValue::List(list) => {
               for s in list {
-                game_args.push(args.replace_vars(version, s, classpath));
+                game_args.push(args.replace_vars(version, s, ctx));
               }
             }
             ArgumentValue::String(s) => {
-              game_args.push(args.replace_vars(version, s, classpath));
+              game_args.push(args.replace_vars(version, s, ctx));
             }
           }
         }
@@ -12,8 +12,10 @@ use crate::{
     file::read_parse_file,
   },
   versions::{
+    event::DownloadCheckStatus,
     loader::{
-      Arguments, CheckFuture, ClasspathEntry, Loader, LoaderVersion, util::download_maven_future,
+      Arguments, CheckFuture, ClasspathEntry, Loader, LoaderVersion,
+      util::{download_maven_future, source_id},
     },
     paths::{MCPath, MCVersionPath},
   },
@@ -21,10 +23,17 @@ use crate::{
 
 const API_BASE_URL_FABRIC: &str = "https://meta.fabricmc.net/v2/versions";
 const API_BASE_URL_QUILT: &str = "https://meta.quiltmc.org/v3/versions";
+const API_BASE_URL_LEGACY_FABRIC: &str = "https://meta.legacyfabric.net/v2/versions";
+const API_BASE_URL_BABRIC: &str = "https://meta.babric.glass-launcher.net/v2/versions";
 const MAVEN_BASE_URL_FABRIC: &str = "https://maven.fabricmc.net";
 const MAVEN_BASE_URL_QUILT: &str = "https://maven.quiltmc.org/repository/release";
+const MAVEN_BASE_URL_LEGACY_FABRIC: &str = "https://maven.legacyfabric.net";
+const MAVEN_BASE_URL_BABRIC: &str = "https://maven.glass-launcher.net/babric";
 const INDEX_FILE_NAME_FABRIC: &str = "fabric";
 const INDEX_FILE_NAME_QUILT: &str = "quilt";
+const INDEX_FILE_NAME_LEGACY_FABRIC: &str = "legacy-fabric";
+const INDEX_FILE_NAME_BABRIC: &str = "babric";
+const INDEX_FILE_NAME_CUSTOM: &str = "custom-fabric";
 
 pub struct FabricLikeLoader {
   base_url: String,
@@ -46,6 +55,20 @@ impl FabricLikeLoader {
     }
   }
 
+  pub fn legacy_fabric() -> Self {
+    Self {
+      base_url: API_BASE_URL_LEGACY_FABRIC.to_string(),
+      index_file_name: INDEX_FILE_NAME_LEGACY_FABRIC.to_string(),
+    }
+  }
+
+  pub fn babric() -> Self {
+    Self {
+      base_url: API_BASE_URL_BABRIC.to_string(),
+      index_file_name: INDEX_FILE_NAME_BABRIC.to_string(),
+    }
+  }
+
   fn loader(&self, version_path: &MCVersionPath) -> PathBuf {
     let filename = format!("{}-loader.json", self.index_file_name);
     path!(version_path.version_root(), filename)
@@ -92,7 +115,8 @@ impl Loader for FabricLikeLoader {
     stable: bool,
   ) -> Result<Vec<String>> {
     let path = self.loader(version_path);
-    if self.index_file_name == INDEX_FILE_NAME_FABRIC {
+    // only quilt lists its loader versions without a stable flag
+    if self.index_file_name != INDEX_FILE_NAME_QUILT {
       let versions = read_parse_file::<Vec<GameVersionMeta>>(&path)
         .await?
         .into_iter()
@@ -131,6 +155,8 @@ pub struct FabricLikeLoaderVersion {
   base_url: String,
   maven_base_url: String,
   meta_file_name: String,
+  // used instead of the meta of `base_url` for forks without their own loader type
+  meta_url: Option<String>,
 }
 
 impl FabricLikeLoaderVersion {
@@ -141,6 +167,7 @@ impl FabricLikeLoaderVersion {
       loader_version,
       base_url: API_BASE_URL_FABRIC.to_string(),
       maven_base_url: MAVEN_BASE_URL_FABRIC.to_string(),
+      meta_url: None,
     }
   }
 
@@ -151,9 +178,55 @@ impl FabricLikeLoaderVersion {
       loader_version,
       base_url: API_BASE_URL_QUILT.to_string(),
       maven_base_url: MAVEN_BASE_URL_QUILT.to_string(),
+      meta_url: None,
+    }
+  }
+
+  pub fn legacy_fabric(mc_version: String, loader_version: String) -> Self {
+    Self {
+      meta_file_name: format!("{}-{}.json", INDEX_FILE_NAME_LEGACY_FABRIC, loader_version),
+      mc_version,
+      loader_version,
+      base_url: API_BASE_URL_LEGACY_FABRIC.to_string(),
+      maven_base_url: MAVEN_BASE_URL_LEGACY_FABRIC.to_string(),
+      meta_url: None,
+    }
+  }
+
+  pub fn babric(mc_version: String, loader_version: String) -> Self {
+    Self {
+      meta_file_name: format!("{}-{}.json", INDEX_FILE_NAME_BABRIC, loader_version),
+      mc_version,
+      loader_version,
+      base_url: API_BASE_URL_BABRIC.to_string(),
+      maven_base_url: MAVEN_BASE_URL_BABRIC.to_string(),
+      meta_url: None,
     }
   }
 
+  /// Uses the meta json of a single loader version, e.g. `<meta>/v2/versions/loader/<mc>/<loader>`.
+  /// Libraries without their own url are downloaded from the fabric maven.
+  pub fn custom(mc_version: String, meta_url: String) -> Self {
+    Self {
+      meta_file_name: format!("{}-{}.json", INDEX_FILE_NAME_CUSTOM, source_id(&meta_url)),
+      mc_version,
+      loader_version: meta_url.clone(),
+      base_url: API_BASE_URL_FABRIC.to_string(),
+      maven_base_url: MAVEN_BASE_URL_FABRIC.to_string(),
+      meta_url: Some(meta_url),
+    }
+  }
+
+  fn meta_url(&self) -> Result<Url> {
+    Ok(match &self.meta_url {
+      Some(url) => Url::parse(url)?,
+      None => Url::parse(&format!(
+        "{}/loader/{}/{}",
+        self.base_url, self.mc_version, self.loader_version
+      ))?,
+    })
+  }
+
   fn meta_path(&self, version_path: &MCVersionPath) -> PathBuf {
     path!(version_path.base_path(), &self.meta_file_name)
   }
@@ -169,10 +242,7 @@ impl LoaderVersion for FabricLikeLoaderVersion {
     // fabric does not specify libraries also specified by vanilla, so we can skip them
     _: &[String],
   ) -> Result<Vec<CheckFuture>> {
-    let url = Url::parse(&format!(
-      "{}/loader/{}/{}",
-      self.base_url, self.mc_version, self.loader_version
-    ))?;
+    let url = self.meta_url()?;
     let path = self.meta_path(version_path);
     let meta: FabricVersionMeta = download_and_parse_file_no_hash_force(client, &path, url).await?;
 
@@ -221,7 +291,13 @@ impl LoaderVersion for FabricLikeLoaderVersion {
     Ok(futures)
   }
 
-  async fn preprocess(&self, _: &MCVersionPath, _: &MCPath, _: PathBuf) -> Result<()> {
+  async fn preprocess(
+    &self,
+    _: &MCVersionPath,
+    _: &MCPath,
+    _: PathBuf,
+    _: &(dyn Fn(DownloadCheckStatus) + Send + Sync),
+  ) -> Result<()> {
     // Fabric versions do not require preprocessing
     Ok(())
   }
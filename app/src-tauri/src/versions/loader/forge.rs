@@ -3,7 +3,6 @@ use std::{
   collections::{HashMap, HashSet},
   ffi::OsString,
   path::{Path, PathBuf},
-  process::Stdio,
 };
 
 use anyhow::Result;
@@ -11,23 +10,23 @@ use log::debug;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tauri::Url;
-use tokio::{fs, process::Command};
+use tokio::fs;
 
-#[cfg(all(not(debug_assertions), target_os = "windows"))]
-use crate::versions::DETACHED_PROCESS;
 use crate::{
   path,
   utils::{
     download::download_file_no_hash_force,
-    file::{read_parse_file, read_parse_xml_file},
+    file::{file_hash, read_parse_file, read_parse_xml_file},
   },
   versions::{
     SEPARATOR,
+    event::DownloadCheckStatus,
     loader::{
       CheckFuture, ClasspathEntry, Loader, LoaderVersion,
       util::{
-        compare_mc_versions, download_maven_future, extract_and_save_file_from_zip,
-        extract_file_from_zip, main_class_from_jar,
+        compare_mc_versions, copy_local_source, download_maven_future,
+        extract_and_save_file_from_zip, extract_file_from_zip, is_remote_source,
+        main_class_from_jar, maven_source_url, run_processor, source_id,
       },
     },
     maven::MavenArtifact,
@@ -44,7 +43,15 @@ const INSTALLER_URL_NEOFORGE: &str = "https://maven.neoforged.net/releases/net/n
 const MAVEN_BASE_URL_FORGE: &str = "https://maven.minecraftforge.net";
 const MAVEN_BASE_URL_NEOFORGE: &str = "https://maven.neoforged.net";
 const INDEX_FILE_NAME_FORGE: &str = "forge";
+// old install profiles still point to the maven on the retired files host
+const LEGACY_MAVEN_URL_FORGE: &str = "files.minecraftforge.net/maven";
+// legacy forge refuses to start if the game jar or its patches do not match the expected hashes
+const LEGACY_JVM_ARGS: [&str; 2] = [
+  "-Dfml.ignoreInvalidMinecraftCertificates=true",
+  "-Dfml.ignorePatchDiscrepancies=true",
+];
 const INDEX_FILE_NAME_NEOFORGE: &str = "neoforge";
+const INDEX_FILE_NAME_CUSTOM: &str = "custom-installer";
 
 pub struct ForgeLikeLoader {
   index_url: String,
@@ -176,6 +183,8 @@ pub struct ForgeLikeLoaderVersion {
   installer_base_url: String,
   index_file_name: String,
   maven_base_url: String,
+  // url or local path of an installer that is not listed in an index
+  custom_installer: Option<String>,
 }
 
 impl ForgeLikeLoaderVersion {
@@ -186,6 +195,7 @@ impl ForgeLikeLoaderVersion {
       installer_base_url: INSTALLER_URL_FORGE.to_string(),
       index_file_name: INDEX_FILE_NAME_FORGE.to_string(),
       maven_base_url: MAVEN_BASE_URL_FORGE.to_string(),
+      custom_installer: None,
     }
   }
 
@@ -196,11 +206,27 @@ impl ForgeLikeLoaderVersion {
       installer_base_url: INSTALLER_URL_NEOFORGE.to_string(),
       index_file_name: INDEX_FILE_NAME_NEOFORGE.to_string(),
       maven_base_url: MAVEN_BASE_URL_NEOFORGE.to_string(),
+      custom_installer: None,
+    }
+  }
+
+  /// Installs any forge style installer jar, e.g. of a fork or a local build.
+  /// The installer can also be given by its maven coordinates, see [`maven_source_url`].
+  pub fn custom(mc_version: String, installer: String) -> Self {
+    Self {
+      mc_version,
+      loader_version: source_id(&installer),
+      installer_base_url: installer.clone(),
+      index_file_name: INDEX_FILE_NAME_CUSTOM.to_string(),
+      maven_base_url: MAVEN_BASE_URL_FORGE.to_string(),
+      custom_installer: Some(installer),
     }
   }
 
   async fn loader_version(&self, version_path: &MCVersionPath) -> Result<String> {
-    Ok(if self.index_file_name == INDEX_FILE_NAME_FORGE {
+    Ok(if self.custom_installer.is_some() {
+      self.loader_version.clone()
+    } else if self.index_file_name == INDEX_FILE_NAME_FORGE {
       let loader = ForgeLikeLoader::forge();
       let path = loader.index(version_path);
       let versions = read_parse_file::<VersionIndex>(&path)
@@ -223,18 +249,11 @@ impl ForgeLikeLoaderVersion {
       let loader = ForgeLikeLoader::neoforge();
       let neoforge = loader.neoforge_version_lists(version_path).await?;
 
-      let mc_version_parts = self.mc_version.split('.').collect::<Vec<_>>();
-      // first part of neoforge version are the major and minor version of Minecraft
-      // e.g., "1.16.5" => "16.5", "1.16" => "16.0"
-      let mc_version_part = if mc_version_parts.len() > 2 {
-        format!("{}.{}", mc_version_parts[1], mc_version_parts[2])
-      } else {
-        format!("{}.0", mc_version_parts[1])
-      };
+      let version = neoforge_version(&self.mc_version, &self.loader_version);
 
       neoforge
         .into_iter()
-        .find(|v| v.contains(&format!("{mc_version_part}.{}", self.loader_version)))
+        .find(|v| v.contains(&version))
         .ok_or_else(|| {
           anyhow::anyhow!(
             "Loader version {} not found for Minecraft version {}",
@@ -290,9 +309,24 @@ impl LoaderVersion for ForgeLikeLoaderVersion {
   ) -> Result<Vec<CheckFuture>> {
     let installer_path = self.installer_path(version_path).await?;
 
-    let url = Url::parse(&self.installer_url(version_path).await?)?;
     let path = installer_path.join(INSTALLER_PATH);
-    download_file_no_hash_force(client, &path, url).await?;
+    let maven_url = self
+      .custom_installer
+      .as_deref()
+      .and_then(|installer| maven_source_url(installer, &self.maven_base_url))
+      .transpose()?;
+    match (&self.custom_installer, maven_url) {
+      (_, Some(url)) => {
+        download_file_no_hash_force(client, &path, url).await?;
+      }
+      (Some(installer), None) if !is_remote_source(installer) => {
+        copy_local_source(installer, &path).await?;
+      }
+      _ => {
+        let url = Url::parse(&self.installer_url(version_path).await?)?;
+        download_file_no_hash_force(client, &path, url).await?;
+      }
+    }
 
     let profile_path = installer_path.join(INSTALLER_PROFILE_PATH);
     extract_and_save_file_from_zip(&path, INSTALLER_PROFILE_PATH, &profile_path).await?;
@@ -371,6 +405,7 @@ impl LoaderVersion for ForgeLikeLoaderVersion {
     version_path: &MCVersionPath,
     mc_path: &MCPath,
     jre_bin: PathBuf,
+    status: &(dyn Fn(DownloadCheckStatus) + Send + Sync),
   ) -> Result<()> {
     let installer_path = self.installer_path(version_path).await?;
     let profile_path = installer_path.join(INSTALLER_PROFILE_PATH);
@@ -387,11 +422,39 @@ impl LoaderVersion for ForgeLikeLoaderVersion {
       }
       default_data(&mut data, &self.mc_version, version_path, mc_path);
 
-      for processor in profile.processors {
-        if let Some(sides) = processor.sides
-          && !sides.contains(&"client".to_string())
-        {
-          continue; // Skip processors that are not for the client side
+      let processors = profile
+        .processors
+        .into_iter()
+        // Skip processors that are not for the client side
+        .filter(|p| {
+          p.sides
+            .as_ref()
+            .is_none_or(|sides| sides.contains(&"client".to_string()))
+        })
+        .collect::<Vec<_>>();
+      let total = processors.len();
+
+      for (i, processor) in processors.into_iter().enumerate() {
+        status(DownloadCheckStatus::ModLoaderPreprocessStep(
+          i,
+          total,
+          String::new(),
+        ));
+
+        let mut outputs = Vec::new();
+        for (file, hash) in processor.outputs.unwrap_or_default() {
+          outputs.push((
+            PathBuf::from(resolve_arg(file, &data, mc_path)?),
+            resolve_arg(hash, &data, mc_path)?,
+          ));
+        }
+        // processors without outputs can not be checked and always run
+        if !outputs.is_empty() && mismatched_output(&outputs).await?.is_none() {
+          debug!(
+            "Skipping processor {} as its outputs are up to date",
+            processor.jar
+          );
+          continue;
         }
 
         let jar_path = MavenArtifact::new(&processor.jar)?.full_path(mc_path);
@@ -408,59 +471,26 @@ impl LoaderVersion for ForgeLikeLoaderVersion {
 
         let mut args = Vec::new();
         for arg in processor.args {
-          let arg = if arg.starts_with("{") && arg.ends_with("}") {
-            let arg_name = &arg[1..arg.len() - 1];
-            if let Some(value) = data.get(arg_name) {
-              value.client.clone()
-            } else {
-              return Err(anyhow::anyhow!(
-                "Argument {} not found in profile data",
-                arg_name
-              ));
-            }
-          } else {
-            arg
-          };
-
-          if arg.starts_with("[") && arg.ends_with("]") {
-            let arg = &arg[1..arg.len() - 1];
-            let path = MavenArtifact::new(arg)?.full_path(mc_path);
-            args.push(path.to_string_lossy().into_owned());
-          } else {
-            args.push(arg);
-          }
+          args.push(resolve_arg(arg, &data, mc_path)?);
         }
 
-        let mut command = Command::new(&jre_bin);
-
-        command
-          .current_dir(&installer_path)
-          .stdout(Stdio::piped())
-          .stderr(Stdio::piped())
-          .arg("-cp")
-          .arg(classpath)
-          .arg(&main_class)
-          .args(&args);
-
-        #[cfg(all(not(debug_assertions), target_os = "windows"))]
-        Command::creation_flags(&mut command, DETACHED_PROCESS);
-
-        debug!("Running processor command: {command:?}");
-
-        let command = command.spawn()?;
-
-        let output = command.wait_with_output().await?;
-
-        debug!("Processor command finished with status: {}", output.status);
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        debug!("Processor stdout: {}", stdout);
-        debug!("Processor stderr: {}", stderr);
-
-        if !output.status.success() {
+        run_processor(
+          &jre_bin,
+          &installer_path,
+          classpath,
+          &main_class,
+          &args,
+          &|line| status(DownloadCheckStatus::ModLoaderPreprocessStep(i, total, line)),
+        )
+        .await?;
+
+        if let Some(path) = mismatched_output(&outputs).await? {
+          // remove the broken file so it is created again on the next try
+          let _ = fs::remove_file(path).await;
           return Err(anyhow::anyhow!(
-            "Processor command failed with status: {}",
-            output.status
+            "Processor {} created {} with an unexpected hash",
+            processor.jar,
+            path.display()
           ));
         }
       }
@@ -513,9 +543,10 @@ impl LoaderVersion for ForgeLikeLoaderVersion {
         game_args.extend(game);
       }
     } else if let Some(minecraft_args) = version_json.minecraft_arguments {
+      jvm_args.extend(LEGACY_JVM_ARGS.map(String::from));
       game_args.extend(
         minecraft_args
-          .split(' ')
+          .split_whitespace()
           .map(String::from)
           .collect::<Vec<_>>(),
       );
@@ -541,6 +572,44 @@ async fn try_extract_lib_from_zip(mc_path: &MCPath, library: &Library, zip: &Pat
   Ok(())
 }
 
+/// Replaces a `{DATA}` reference with its value, resolves `[maven]` names to their path
+/// and removes the quotes of `'literals'`
+fn resolve_arg(arg: String, data: &HashMap<String, DataEntry>, mc_path: &MCPath) -> Result<String> {
+  let arg = if arg.starts_with("{") && arg.ends_with("}") {
+    let arg_name = &arg[1..arg.len() - 1];
+    if let Some(value) = data.get(arg_name) {
+      value.client.clone()
+    } else {
+      return Err(anyhow::anyhow!(
+        "Argument {} not found in profile data",
+        arg_name
+      ));
+    }
+  } else {
+    arg
+  };
+
+  if arg.starts_with("[") && arg.ends_with("]") {
+    let arg = &arg[1..arg.len() - 1];
+    let path = MavenArtifact::new(arg)?.full_path(mc_path);
+    Ok(path.to_string_lossy().into_owned())
+  } else if arg.len() >= 2 && arg.starts_with('\'') && arg.ends_with('\'') {
+    Ok(arg[1..arg.len() - 1].to_string())
+  } else {
+    Ok(arg)
+  }
+}
+
+/// Returns the first output that is missing or does not match its sha1
+async fn mismatched_output(outputs: &[(PathBuf, String)]) -> Result<Option<&PathBuf>> {
+  for (path, hash) in outputs {
+    if !file_hash(hash, path).await? {
+      return Ok(Some(path));
+    }
+  }
+  Ok(None)
+}
+
 fn default_data(
   data: &mut HashMap<String, DataEntry>,
   mc_version: &str,
@@ -605,7 +674,7 @@ fn forge_version_pair(version_string: &str) -> Result<(String, String)> {
   Ok((mc_version, forge_version))
 }
 
-fn neoforge_version_pair(version_string: &str) -> Result<(String, String)> {
+pub fn neoforge_version_pair(version_string: &str) -> Result<(String, String)> {
   // format: x.y.z where x is x.y is the Minecraft version and z is the NeoForge version
   // the mc version is presented as "1.x.y" and the NeoForge version is presented as "z"
   // e.g., "16.5.30" => ("1.16.5", "30")
@@ -621,6 +690,20 @@ fn neoforge_version_pair(version_string: &str) -> Result<(String, String)> {
   Ok((mc_version, neoforge_version))
 }
 
+/// The full NeoForge version from the Minecraft version and the NeoForge part
+pub fn neoforge_version(mc_version: &str, loader_version: &str) -> String {
+  let mc_version_parts = mc_version.split('.').collect::<Vec<_>>();
+  // first part of neoforge version are the major and minor version of Minecraft
+  // e.g., "1.16.5" => "16.5", "1.16" => "16.0"
+  let mc_version_part = match mc_version_parts.as_slice() {
+    [_, minor, patch, ..] => format!("{minor}.{patch}"),
+    [_, minor] => format!("{minor}.0"),
+    _ => mc_version.to_string(),
+  };
+
+  format!("{mc_version_part}.{loader_version}")
+}
+
 type VersionIndex = HashMap<String, Vec<String>>;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -753,11 +836,11 @@ impl ForgeVersionManifestOld {
       .into_iter()
       .map(|lib| {
         let maven = MavenArtifact::new(&lib.name)?;
-        let base_url = lib
-          .url
-          .as_ref()
-          .map(|u| u.as_str())
-          .unwrap_or(Self::MINECRAFT_MAVEN);
+        let base_url = match &lib.url {
+          Some(url) if url.as_str().contains(LEGACY_MAVEN_URL_FORGE) => MAVEN_BASE_URL_FORGE,
+          Some(url) => url.as_str().trim_end_matches('/'),
+          None => Self::MINECRAFT_MAVEN,
+        };
 
         let url = Some(maven.url(base_url)?);
         let path = maven.path().to_string_lossy().into_owned();
@@ -789,3 +872,62 @@ impl ForgeVersionManifestOld {
     })
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn resolves_args() {
+    let mc_path = MCPath::new(&path!("minecraft"));
+    let data = HashMap::from([
+      (
+        "SIDE".to_string(),
+        DataEntry {
+          client: "client".to_string(),
+          server: "server".to_string(),
+        },
+      ),
+      (
+        "MOJMAPS_SHA".to_string(),
+        DataEntry {
+          client: "'a1b2c3'".to_string(),
+          server: "'d4e5f6'".to_string(),
+        },
+      ),
+      (
+        "MOJMAPS".to_string(),
+        DataEntry {
+          client: "[net.minecraft:client:1.20.1:mappings@txt]".to_string(),
+          server: "[net.minecraft:server:1.20.1:mappings@txt]".to_string(),
+        },
+      ),
+    ]);
+    let mappings = MavenArtifact::new("net.minecraft:client:1.20.1:mappings@txt")
+      .unwrap()
+      .full_path(&mc_path)
+      .to_string_lossy()
+      .into_owned();
+
+    let cases = [
+      ("--task", "--task"),
+      ("{SIDE}", "client"),
+      ("'literal'", "literal"),
+      ("''", ""),
+      // a single quote is not a quoted literal
+      ("'", "'"),
+      ("{MOJMAPS_SHA}", "a1b2c3"),
+      ("{MOJMAPS}", &mappings),
+      ("[net.minecraft:client:1.20.1:mappings@txt]", &mappings),
+    ];
+
+    for (arg, expected) in cases {
+      assert_eq!(
+        resolve_arg(arg.to_string(), &data, &mc_path).unwrap(),
+        expected,
+        "{arg}"
+      );
+    }
+    assert!(resolve_arg("{MISSING}".to_string(), &data, &mc_path).is_err());
+  }
+}
@@ -0,0 +1,291 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use anyhow::Result;
+use log::debug;
+use md5::{Digest, Md5};
+use reqwest::Client;
+use serde::Deserialize;
+use tauri::Url;
+use tokio::fs;
+
+use crate::{
+  path,
+  utils::{
+    download::download_file_no_hash_force, file::read_parse_file, version::compare_versions,
+  },
+  versions::{
+    event::DownloadCheckStatus,
+    loader::{
+      Arguments, CheckFuture, ClasspathEntry, Loader, LoaderVersion, util::download_maven_future,
+    },
+    maven::MavenArtifact,
+    paths::{MCPath, MCVersionPath},
+  },
+};
+
+const INDEX_URL: &str = "https://dl.liteloader.com/versions/versions.json";
+const INDEX_FILE_NAME: &str = "liteloader";
+const ARTEFACT: &str = "com.mumfrey:liteloader";
+// the index lists the newest build a second time under this key
+const LATEST_KEY: &str = "latest";
+const MAVEN_BASE_URL_MINECRAFT: &str = "https://libraries.minecraft.net";
+const MAIN_CLASS: &str = "net.minecraft.launchwrapper.Launch";
+const TWEAK_CLASS: &str = "com.mumfrey.liteloader.launch.LiteLoaderTweaker";
+
+pub struct LiteLoader;
+
+impl LiteLoader {
+  fn index(version_path: &MCVersionPath) -> PathBuf {
+    let filename = format!("{}-index.json", INDEX_FILE_NAME);
+    path!(version_path.version_root(), filename)
+  }
+
+  async fn builds(version_path: &MCVersionPath) -> Result<HashMap<String, LiteLoaderMcVersion>> {
+    Ok(
+      read_parse_file::<LiteLoaderIndex>(&Self::index(version_path))
+        .await?
+        .versions,
+    )
+  }
+}
+
+#[async_trait::async_trait]
+impl Loader for LiteLoader {
+  async fn download_metadata(&self, client: &Client, version_path: &MCVersionPath) -> Result<()> {
+    let url = Url::parse(INDEX_URL)?;
+    let path = Self::index(version_path);
+    download_file_no_hash_force(client, &path, url).await?;
+
+    Ok(())
+  }
+
+  async fn supported_versions(&self, version_path: &MCVersionPath, _: bool) -> Result<Vec<String>> {
+    Ok(
+      Self::builds(version_path)
+        .await?
+        .into_iter()
+        .filter(|(_, version)| !version.builds().is_empty())
+        .map(|(mc_version, _)| mc_version)
+        .collect(),
+    )
+  }
+
+  async fn loader_versions_for_mc_version(
+    &self,
+    mc_version: &str,
+    version_path: &MCVersionPath,
+    _: bool,
+  ) -> Result<Vec<String>> {
+    let builds = Self::builds(version_path).await?;
+    let mut versions = builds
+      .get(mc_version)
+      .map(|version| {
+        version
+          .builds()
+          .into_iter()
+          .map(|build| build.version.clone())
+          .collect::<Vec<_>>()
+      })
+      .unwrap_or_default();
+    versions.sort_by(|a, b| compare_versions(b, a));
+
+    Ok(versions)
+  }
+}
+
+#[derive(Deserialize)]
+struct LiteLoaderIndex {
+  versions: HashMap<String, LiteLoaderMcVersion>,
+}
+
+#[derive(Deserialize)]
+struct LiteLoaderMcVersion {
+  repo: Option<LiteLoaderRepo>,
+  // only release builds are listed here, snapshots are served by a ci server
+  #[serde(default)]
+  artefacts: HashMap<String, HashMap<String, LiteLoaderBuild>>,
+}
+
+impl LiteLoaderMcVersion {
+  fn builds(&self) -> Vec<&LiteLoaderBuild> {
+    self
+      .artefacts
+      .get(ARTEFACT)
+      .map(|builds| {
+        builds
+          .iter()
+          .filter(|(key, _)| key.as_str() != LATEST_KEY)
+          .map(|(_, build)| build)
+          .collect()
+      })
+      .unwrap_or_default()
+  }
+}
+
+#[derive(Deserialize)]
+struct LiteLoaderRepo {
+  url: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct LiteLoaderBuild {
+  version: String,
+  file: String,
+  md5: String,
+  tweak_class: Option<String>,
+  #[serde(default)]
+  libraries: Vec<LiteLoaderLibrary>,
+}
+
+#[derive(Deserialize, Clone)]
+struct LiteLoaderLibrary {
+  name: String,
+  url: Option<String>,
+}
+
+pub struct LiteLoaderVersion {
+  mc_version: String,
+  loader_version: String,
+}
+
+impl LiteLoaderVersion {
+  pub fn new(mc_version: String, loader_version: String) -> Self {
+    Self {
+      mc_version,
+      loader_version,
+    }
+  }
+
+  /// The build and the url of the repository it is served from
+  async fn build(&self, version_path: &MCVersionPath) -> Result<(LiteLoaderBuild, String)> {
+    let builds = LiteLoader::builds(version_path).await?;
+    builds
+      .get(&self.mc_version)
+      .and_then(|version| {
+        let build = version
+          .builds()
+          .into_iter()
+          .find(|build| build.version == self.loader_version)?;
+        let repo = version.repo.as_ref()?;
+        Some((build.clone(), repo.url.clone()))
+      })
+      .ok_or_else(|| {
+        anyhow::anyhow!(
+          "Loader version {} not found for Minecraft version {}",
+          self.loader_version,
+          self.mc_version
+        )
+      })
+  }
+
+  fn library(&self) -> String {
+    format!("{}:{}", ARTEFACT, self.loader_version)
+  }
+}
+
+#[async_trait::async_trait]
+impl LoaderVersion for LiteLoaderVersion {
+  async fn download(
+    &self,
+    client: &Client,
+    version_path: &MCVersionPath,
+    mc_path: &MCPath,
+    existing_libs: &[String],
+  ) -> Result<Vec<CheckFuture>> {
+    let (build, repo) = self.build(version_path).await?;
+
+    let path = MavenArtifact::new(&self.library())?.full_path(mc_path);
+    if !fs::read(&path)
+      .await
+      .is_ok_and(|data| md5_hex(&data) == build.md5)
+    {
+      let url = Url::parse(&format!(
+        "{}/{}/{}/{}",
+        repo.trim_end_matches('/'),
+        ARTEFACT.replace([':', '.'], "/"),
+        self.mc_version,
+        build.file
+      ))?;
+      debug!("Downloading LiteLoader {}", self.loader_version);
+      let data = download_file_no_hash_force(client, &path, url).await?;
+      if md5_hex(&data) != build.md5 {
+        let _ = fs::remove_file(&path).await;
+        anyhow::bail!(
+          "LiteLoader {} does not match its checksum",
+          self.loader_version
+        );
+      }
+    }
+
+    let mut futures = Vec::new();
+    for library in build.libraries {
+      if existing_libs.contains(&library.name) {
+        continue;
+      }
+      // some of the listed repositories no longer answer plain http requests
+      let base_url = library
+        .url
+        .map_or(MAVEN_BASE_URL_MINECRAFT.to_string(), |url| {
+          url
+            .replacen("http://", "https://", 1)
+            .trim_end_matches('/')
+            .to_string()
+        });
+      futures.push(download_maven_future(
+        mc_path.clone(),
+        library.name,
+        client.clone(),
+        base_url,
+        None,
+        None,
+      ));
+    }
+
+    Ok(futures)
+  }
+
+  async fn preprocess(
+    &self,
+    _: &MCVersionPath,
+    _: &MCPath,
+    _: PathBuf,
+    _: &(dyn Fn(DownloadCheckStatus) + Send + Sync),
+  ) -> Result<()> {
+    Ok(())
+  }
+
+  async fn classpath(
+    &self,
+    version_path: &MCVersionPath,
+    mc_path: &MCPath,
+  ) -> Result<Vec<ClasspathEntry>> {
+    let (build, _) = self.build(version_path).await?;
+
+    let mut libs = vec![ClasspathEntry::from_name(&self.library(), mc_path)?];
+    for library in build.libraries {
+      libs.push(ClasspathEntry::from_name(&library.name, mc_path)?);
+    }
+
+    Ok(libs)
+  }
+
+  async fn main_class(&self, _: &MCVersionPath) -> Result<String> {
+    Ok(MAIN_CLASS.to_string())
+  }
+
+  async fn arguments(&self, version_path: &MCVersionPath) -> Result<Arguments> {
+    let (build, _) = self.build(version_path).await?;
+    let tweak_class = build.tweak_class.unwrap_or(TWEAK_CLASS.to_string());
+
+    Ok(Arguments::new(
+      Vec::new(),
+      vec!["--tweakClass".to_string(), tweak_class],
+      false,
+    ))
+  }
+}
+
+fn md5_hex(data: &[u8]) -> String {
+  hex::encode(Md5::digest(data))
+}
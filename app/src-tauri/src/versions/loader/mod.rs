@@ -7,9 +7,12 @@ use serde::{Deserialize, Serialize};
 use crate::{
   utils::download::DownloadFileSizeFuture,
   versions::{
+    event::DownloadCheckStatus,
     loader::{
       fabric::{FabricLikeLoader, FabricLikeLoaderVersion},
       forge::{ForgeLikeLoader, ForgeLikeLoaderVersion},
+      liteloader::{LiteLoader, LiteLoaderVersion},
+      optifine::OptiFineLoaderVersion,
     },
     maven::MavenArtifact,
     paths::{MCPath, MCVersionPath},
@@ -18,7 +21,9 @@ use crate::{
 
 pub mod fabric;
 pub mod forge;
-mod util;
+pub mod liteloader;
+pub mod optifine;
+pub mod util;
 
 type DownloadFuture =
   Pin<Box<dyn Future<Output = Result<(DownloadFileSizeFuture, usize)>> + Send + 'static>>;
@@ -51,6 +56,7 @@ pub trait LoaderVersion: Send + Sync + 'static {
     version_path: &MCVersionPath,
     mc_path: &MCPath,
     jre_bin: PathBuf,
+    status: &(dyn Fn(DownloadCheckStatus) + Send + Sync),
   ) -> Result<()>;
   async fn classpath(
     &self,
@@ -94,23 +100,55 @@ pub trait Loader: Send + Sync + 'static {
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LoaderType {
   Vanilla,
   Fabric,
   Quilt,
   Forge,
   NeoForge,
+  LegacyFabric,
+  Babric,
+  LiteLoader,
+  // installed from an installer the user downloaded from optifine.net
+  OptiFine,
+  // installed from a forge style installer jar or fabric style meta url stored as the loader version
+  CustomForge,
+  CustomFabric,
 }
 
 impl LoaderType {
+  /// The name shown in the ui
+  pub fn name(self) -> &'static str {
+    match self {
+      LoaderType::Vanilla => "Vanilla",
+      LoaderType::Fabric => "Fabric",
+      LoaderType::Quilt => "Quilt",
+      LoaderType::Forge => "Forge",
+      LoaderType::NeoForge => "NeoForge",
+      LoaderType::LegacyFabric => "LegacyFabric",
+      LoaderType::Babric => "Babric",
+      LoaderType::LiteLoader => "LiteLoader",
+      LoaderType::OptiFine => "OptiFine",
+      LoaderType::CustomForge => "CustomForge",
+      LoaderType::CustomFabric => "CustomFabric",
+    }
+  }
+
   pub fn loader(self) -> Option<Box<dyn Loader>> {
     match self {
       LoaderType::Fabric => Some(Box::new(FabricLikeLoader::fabric())),
       LoaderType::Quilt => Some(Box::new(FabricLikeLoader::quilt())),
       LoaderType::Forge => Some(Box::new(ForgeLikeLoader::forge())),
       LoaderType::NeoForge => Some(Box::new(ForgeLikeLoader::neoforge())),
-      LoaderType::Vanilla => None,
+      LoaderType::LegacyFabric => Some(Box::new(FabricLikeLoader::legacy_fabric())),
+      LoaderType::Babric => Some(Box::new(FabricLikeLoader::babric())),
+      LoaderType::LiteLoader => Some(Box::new(LiteLoader)),
+      // custom loaders and optifine have no index of their versions
+      LoaderType::Vanilla
+      | LoaderType::OptiFine
+      | LoaderType::CustomForge
+      | LoaderType::CustomFabric => None,
     }
   }
 
@@ -136,16 +174,48 @@ impl LoaderType {
         mc_version,
         loader_version,
       ))),
+      LoaderType::LegacyFabric => Some(Box::new(FabricLikeLoaderVersion::legacy_fabric(
+        mc_version,
+        loader_version,
+      ))),
+      LoaderType::Babric => Some(Box::new(FabricLikeLoaderVersion::babric(
+        mc_version,
+        loader_version,
+      ))),
+      LoaderType::LiteLoader => Some(Box::new(LiteLoaderVersion::new(mc_version, loader_version))),
+      LoaderType::OptiFine => Some(Box::new(OptiFineLoaderVersion::new(
+        mc_version,
+        loader_version,
+      ))),
+      LoaderType::CustomForge => Some(Box::new(ForgeLikeLoaderVersion::custom(
+        mc_version,
+        loader_version,
+      ))),
+      LoaderType::CustomFabric => Some(Box::new(FabricLikeLoaderVersion::custom(
+        mc_version,
+        loader_version,
+      ))),
       LoaderType::Vanilla => None,
     }
   }
 
+  /// Loaders installed from an installer or meta url given by the user instead of a listed version
+  pub fn is_custom(self) -> bool {
+    matches!(
+      self,
+      LoaderType::OptiFine | LoaderType::CustomForge | LoaderType::CustomFabric
+    )
+  }
+
   pub fn mod_loaders() -> Vec<Box<dyn Loader>> {
     vec![
       LoaderType::Fabric.loader().unwrap(),
       LoaderType::Quilt.loader().unwrap(),
       LoaderType::Forge.loader().unwrap(),
       LoaderType::NeoForge.loader().unwrap(),
+      LoaderType::LegacyFabric.loader().unwrap(),
+      LoaderType::Babric.loader().unwrap(),
+      LoaderType::LiteLoader.loader().unwrap(),
     ]
   }
 }
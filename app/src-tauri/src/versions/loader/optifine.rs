@@ -0,0 +1,210 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use anyhow::Result;
+use reqwest::Client;
+use tokio::fs;
+
+use crate::{
+  path,
+  versions::{
+    event::DownloadCheckStatus,
+    loader::{
+      Arguments, CheckFuture, ClasspathEntry, LoaderVersion,
+      util::{
+        copy_local_source, download_maven_future, extract_and_save_file_from_zip,
+        extract_file_from_zip, is_remote_source, run_processor, source_id,
+      },
+    },
+    maven::MavenArtifact,
+    paths::{MCPath, MCVersionPath},
+  },
+};
+
+// optifine.net only serves the installers behind an ad page and publishes no checksums,
+// so the installer has to be downloaded there by the user and is never fetched from a mirror
+const INSTALLER_DIR_NAME: &str = "optifine";
+const INSTALLER_PATH: &str = "installer.jar";
+const INSTALLER_CHECK_PATH: &str = "optifine/Patcher.class";
+// newer installers ship their own launchwrapper which also runs on java 9+
+const LAUNCHWRAPPER_OF_PATH: &str = "launchwrapper-of.txt";
+const LAUNCHWRAPPER: &str = "net.minecraft:launchwrapper:1.12";
+const MAVEN_BASE_URL_MINECRAFT: &str = "https://libraries.minecraft.net";
+const PATCHER_MAIN_CLASS: &str = "optifine.Patcher";
+const MAIN_CLASS: &str = "net.minecraft.launchwrapper.Launch";
+const TWEAK_CLASS: &str = "optifine.OptiFineTweaker";
+
+pub struct OptiFineLoaderVersion {
+  mc_version: String,
+  // local path of an installer downloaded from optifine.net
+  installer: String,
+}
+
+impl OptiFineLoaderVersion {
+  pub fn new(mc_version: String, installer: String) -> Self {
+    Self {
+      mc_version,
+      installer,
+    }
+  }
+
+  fn installer_path(&self, version_path: &MCVersionPath) -> PathBuf {
+    path!(
+      version_path.base_path(),
+      format!("{}-{}", INSTALLER_DIR_NAME, source_id(&self.installer)),
+      INSTALLER_PATH
+    )
+  }
+
+  /// Copies the installer selected by the user and makes sure it fits the version
+  async fn copy_installer(&self, version_path: &MCVersionPath) -> Result<PathBuf> {
+    if is_remote_source(&self.installer) {
+      anyhow::bail!(
+        "OptiFine installers have to be downloaded from optifine.net and selected as a file"
+      );
+    }
+
+    let path = self.installer_path(version_path);
+    copy_local_source(&self.installer, &path).await?;
+    if extract_file_from_zip(&path, INSTALLER_CHECK_PATH)
+      .await
+      .is_err()
+    {
+      let _ = fs::remove_file(&path).await;
+      anyhow::bail!("{} is not an OptiFine installer", self.installer);
+    }
+
+    let file_name = PathBuf::from(&self.installer)
+      .file_name()
+      .unwrap_or_default()
+      .to_string_lossy()
+      .into_owned();
+    if let Some(mc_version) = installer_mc_version(&file_name)
+      && mc_version != self.mc_version
+    {
+      anyhow::bail!(
+        "OptiFine installer {file_name} is for Minecraft {mc_version} instead of {}",
+        self.mc_version
+      );
+    }
+
+    Ok(path)
+  }
+
+  /// The jar created by the patcher from the vanilla jar and the installer
+  fn library(&self) -> String {
+    format!(
+      "optifine:OptiFine:{}_{}",
+      self.mc_version,
+      source_id(&self.installer)
+    )
+  }
+
+  async fn launchwrapper(&self, version_path: &MCVersionPath) -> String {
+    let installer = self.installer_path(version_path);
+    match extract_file_from_zip(&installer, LAUNCHWRAPPER_OF_PATH).await {
+      Ok(data) => format!(
+        "optifine:launchwrapper-of:{}",
+        String::from_utf8_lossy(&data).trim()
+      ),
+      Err(_) => LAUNCHWRAPPER.to_string(),
+    }
+  }
+}
+
+#[async_trait::async_trait]
+impl LoaderVersion for OptiFineLoaderVersion {
+  async fn download(
+    &self,
+    client: &Client,
+    version_path: &MCVersionPath,
+    mc_path: &MCPath,
+    _: &[String],
+  ) -> Result<Vec<CheckFuture>> {
+    let path = self.copy_installer(version_path).await?;
+
+    let launchwrapper = self.launchwrapper(version_path).await;
+    let mut futures = Vec::new();
+    if launchwrapper == LAUNCHWRAPPER {
+      futures.push(download_maven_future(
+        mc_path.clone(),
+        launchwrapper,
+        client.clone(),
+        MAVEN_BASE_URL_MINECRAFT.to_string(),
+        None,
+        None,
+      ));
+    } else {
+      // the jar is stored next to launchwrapper-of.txt in the installer
+      let library_path = MavenArtifact::new(&launchwrapper)?.full_path(mc_path);
+      let file_name = library_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+      extract_and_save_file_from_zip(&path, &file_name, &library_path).await?;
+    }
+
+    Ok(futures)
+  }
+
+  async fn preprocess(
+    &self,
+    version_path: &MCVersionPath,
+    mc_path: &MCPath,
+    jre_bin: PathBuf,
+    status: &(dyn Fn(DownloadCheckStatus) + Send + Sync),
+  ) -> Result<()> {
+    let installer_path = self.installer_path(version_path);
+    let library_path = MavenArtifact::new(&self.library())?.full_path(mc_path);
+    if let Some(parent) = library_path.parent() {
+      fs::create_dir_all(parent).await?;
+    }
+
+    let args = [
+      version_path.client_jar().to_string_lossy().into_owned(),
+      installer_path.to_string_lossy().into_owned(),
+      library_path.to_string_lossy().into_owned(),
+    ];
+    let working_dir = installer_path.parent().unwrap_or(version_path.base_path());
+    run_processor(
+      &jre_bin,
+      working_dir,
+      OsString::from(&installer_path),
+      PATCHER_MAIN_CLASS,
+      &args,
+      &|line| status(DownloadCheckStatus::ModLoaderPreprocessStep(0, 1, line)),
+    )
+    .await
+  }
+
+  async fn classpath(
+    &self,
+    version_path: &MCVersionPath,
+    mc_path: &MCPath,
+  ) -> Result<Vec<ClasspathEntry>> {
+    let launchwrapper = self.launchwrapper(version_path).await;
+    Ok(vec![
+      ClasspathEntry::from_name(&self.library(), mc_path)?,
+      ClasspathEntry::from_name(&launchwrapper, mc_path)?,
+    ])
+  }
+
+  async fn main_class(&self, _: &MCVersionPath) -> Result<String> {
+    Ok(MAIN_CLASS.to_string())
+  }
+
+  async fn arguments(&self, _: &MCVersionPath) -> Result<Arguments> {
+    Ok(Arguments::new(
+      Vec::new(),
+      vec!["--tweakClass".to_string(), TWEAK_CLASS.to_string()],
+      false,
+    ))
+  }
+}
+
+/// The game version of an installer named like the ones from optifine.net,
+/// e.g. `OptiFine_1.20.1_HD_U_I6.jar` or `preview_OptiFine_1.21.4_HD_U_J3_pre2.jar`
+fn installer_mc_version(file_name: &str) -> Option<&str> {
+  let name = file_name.strip_prefix("preview_").unwrap_or(file_name);
+  name.strip_prefix("OptiFine_")?.split('_').next()
+}
@@ -1,11 +1,21 @@
-use std::path::Path;
+use std::{ffi::OsString, path::Path, process::Stdio, time::Duration};
 
 use anyhow::Result;
 use async_zip::tokio::read::fs::ZipFileReader;
+use log::debug;
 use reqwest::Client;
+use sha1::{Digest, Sha1};
 use tauri::Url;
-use tokio::fs;
+use tokio::{
+  fs,
+  io::{AsyncBufRead, AsyncBufReadExt, BufReader, Split},
+  process::Command,
+  select,
+  time::{Instant, sleep_until},
+};
 
+#[cfg(all(not(debug_assertions), target_os = "windows"))]
+use crate::versions::DETACHED_PROCESS;
 use crate::{
   utils::{
     download::{DownloadFileSizeFuture, download_file_size},
@@ -18,6 +28,10 @@ use crate::{
   },
 };
 
+const MAVEN_SOURCE_PREFIX: &str = "maven:";
+// processors can print thousands of lines, only the latest one is shown in this interval
+const PROCESSOR_OUTPUT_INTERVAL: Duration = Duration::from_millis(100);
+
 pub fn download_maven_future(
   mc_path: MCPath,
   name: String,
@@ -132,3 +146,138 @@ pub async fn main_class_from_jar(jar_path: &Path) -> Result<String> {
   }
   main_class.ok_or_else(|| anyhow::anyhow!("Main-Class not found"))
 }
+
+/// Runs a java program of a loader installer, e.g. the forge processors or the optifine patcher.
+/// The latest line the program printed is passed to `output` at most every 100ms.
+pub async fn run_processor(
+  jre_bin: &Path,
+  working_dir: &Path,
+  classpath: OsString,
+  main_class: &str,
+  args: &[String],
+  output: &(dyn Fn(String) + Send + Sync),
+) -> Result<()> {
+  let mut command = Command::new(jre_bin);
+
+  command
+    .current_dir(working_dir)
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .arg("-cp")
+    .arg(classpath)
+    .arg(main_class)
+    .args(args);
+
+  #[cfg(all(not(debug_assertions), target_os = "windows"))]
+  Command::creation_flags(&mut command, DETACHED_PROCESS);
+
+  debug!("Running processor command: {command:?}");
+
+  let mut child = command.spawn()?;
+  let mut stdout = child.stdout.take().map(|s| BufReader::new(s).split(b'\n'));
+  let mut stderr = child.stderr.take().map(|s| BufReader::new(s).split(b'\n'));
+
+  let mut pending = None;
+  let mut next_output = Instant::now();
+
+  // read both pipes at the same time so neither of them fills up and blocks the processor
+  while stdout.is_some() || stderr.is_some() {
+    let (line, name) = select! {
+      Some(line) = next_line(&mut stdout) => (line, "stdout"),
+      Some(line) = next_line(&mut stderr) => (line, "stderr"),
+      _ = sleep_until(next_output), if pending.is_some() => {
+        output(pending.take().unwrap_or_default());
+        next_output = Instant::now() + PROCESSOR_OUTPUT_INTERVAL;
+        continue;
+      }
+      else => break,
+    };
+
+    match line {
+      Some(line) => {
+        debug!("Processor {name}: {line}");
+        if Instant::now() >= next_output {
+          output(line);
+          next_output = Instant::now() + PROCESSOR_OUTPUT_INTERVAL;
+        } else {
+          pending = Some(line);
+        }
+      }
+      None if name == "stdout" => stdout = None,
+      None => stderr = None,
+    }
+  }
+
+  if let Some(line) = pending {
+    output(line);
+  }
+
+  let status = child.wait().await?;
+  debug!("Processor command finished with status: {}", status);
+
+  if !status.success() {
+    return Err(anyhow::anyhow!(
+      "Processor command failed with status: {}",
+      status
+    ));
+  }
+
+  Ok(())
+}
+
+/// Returns `None` if the pipe is already closed and `Some(None)` once it closes
+async fn next_line<R: AsyncBufRead + Unpin>(
+  reader: &mut Option<Split<R>>,
+) -> Option<Option<String>> {
+  let reader = reader.as_mut()?;
+  match reader.next_segment().await {
+    Ok(Some(line)) => Some(Some(String::from_utf8_lossy(&line).trim_end().to_string())),
+    Ok(None) | Err(_) => Some(None),
+  }
+}
+
+/// A short file name for loaders installed from a custom installer or meta url
+pub fn source_id(source: &str) -> String {
+  let hash = hex::encode(Sha1::digest(source.as_bytes()));
+  hash[..16].to_string()
+}
+
+/// Custom sources can also be a local file, e.g. a locally built installer
+pub fn is_remote_source(source: &str) -> bool {
+  Url::parse(source).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Resolves an installer given as `maven:<group>:<artifact>:<version>[@<repository url>]`
+/// to its url, the classifier defaults to `installer`
+pub fn maven_source_url(source: &str, default_repository: &str) -> Option<Result<Url>> {
+  let source = source.strip_prefix(MAVEN_SOURCE_PREFIX)?;
+  let (coordinate, repository) = match source.split_once('@') {
+    Some((coordinate, repository)) if is_remote_source(repository) => (coordinate, repository),
+    _ => (source, default_repository),
+  };
+
+  let coordinate = if coordinate.split(':').count() == 3 {
+    format!("{coordinate}:installer")
+  } else {
+    coordinate.to_string()
+  };
+  Some(
+    MavenArtifact::new(&coordinate)
+      .and_then(|artifact| artifact.url(repository.trim_end_matches('/'))),
+  )
+}
+
+/// Copies a local source into the cache, the cached copy is used once the source is gone
+pub async fn copy_local_source(source: &str, target: &Path) -> Result<()> {
+  if !Path::new(source).exists() && target.exists() {
+    debug!("Source {source} no longer exists, using the cached copy");
+    return Ok(());
+  }
+
+  if let Some(parent) = target.parent() {
+    fs::create_dir_all(parent).await?;
+  }
+  fs::copy(source, target).await?;
+
+  Ok(())
+}
@@ -1,5 +1,6 @@
 use std::{collections::HashMap, fmt::Display};
 
+use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, value::Serializer};
@@ -9,6 +10,9 @@ use super::{
   Rule,
   java::{JavaVersion, Library},
 };
+use crate::versions::{loader::util::extract_file_from_zip, paths::MCVersionPath};
+
+const JAR_VERSION: &str = "version.json";
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -61,12 +65,15 @@ pub struct Version {
   pub id: String,
   #[serde(default)]
   pub arguments: Arguments,
-  pub asset_index: AssetIndex,
-  pub downloads: Downloads,
+  // custom versions that do not inherit from a mojang version may ship without these
+  pub asset_index: Option<AssetIndex>,
+  pub downloads: Option<Downloads>,
   #[serde(default)]
   pub java_version: JavaVersion,
   pub libraries: Vec<Library>,
   pub main_class: String,
+  // used instead of `arguments` before 1.13
+  pub minecraft_arguments: Option<String>,
   pub r#type: VersionType,
 }
 
@@ -166,10 +173,16 @@ pub enum ArgumentValue {
   List(Vec<String>),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Assets {
   pub objects: HashMap<String, Asset>,
+  // 1.6 reads the assets by name from `assets/virtual/<index>`
+  #[serde(default, rename = "virtual")]
+  pub is_virtual: bool,
+  // before 1.6 the assets are read from the `resources` folder of the game dir
+  #[serde(default, rename = "map_to_resources")]
+  pub map_to_resources: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -178,3 +191,40 @@ pub struct Asset {
   pub hash: String,
   pub size: usize,
 }
+
+/// The `version.json` inside the client jar
+#[derive(Deserialize, Debug, Clone)]
+pub struct JarVersion {
+  pub world_version: i32,
+  pub pack_version: Option<PackVersion>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum PackVersion {
+  Single(i32),
+  // since 24w14a
+  Split { resource: i32 },
+  // since 25w31a, pack formats have a minor version
+  Major { resource_major: i32 },
+}
+
+impl JarVersion {
+  /// The file is only available since 18w47b
+  pub async fn read(version_path: &MCVersionPath) -> Result<Option<Self>> {
+    let Ok(data) = extract_file_from_zip(&version_path.client_jar(), JAR_VERSION).await else {
+      return Ok(None);
+    };
+    Ok(Some(serde_json::from_slice(&data)?))
+  }
+}
+
+impl PackVersion {
+  pub fn resource(&self) -> i32 {
+    match self {
+      PackVersion::Single(v) => *v,
+      PackVersion::Split { resource } => *resource,
+      PackVersion::Major { resource_major } => *resource_major,
+    }
+  }
+}
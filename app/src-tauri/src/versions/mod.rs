@@ -3,12 +3,13 @@ use meta::{Action, Arch, Os, OsName, Rule};
 use crate::versions::meta::Features;
 
 pub mod commands;
+pub mod custom;
 mod download;
 pub mod event;
 pub mod launch;
 pub mod loader;
 mod maven;
-mod meta;
+pub mod meta;
 pub mod paths;
 pub mod store;
 
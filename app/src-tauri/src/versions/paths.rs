@@ -9,6 +9,7 @@ const LIBRARY_DIR: &str = "lib";
 const ASSETS_DIR: &str = "assets";
 const ASSETS_OBJECTS_DIR: &str = "objects";
 const ASSETS_INDEX_DIR: &str = "indexes";
+const ASSETS_VIRTUAL_DIR: &str = "virtual";
 
 pub const QUICK_PLAY: &str = "quick_play.json";
 const MANIFEST_NAME: &str = "manifest.json";
@@ -94,6 +95,14 @@ impl MCPath {
   pub fn assets_index_path(&self) -> PathBuf {
     path!(&self.assets_path(), ASSETS_INDEX_DIR)
   }
+
+  pub fn assets_index(&self, id: &str) -> PathBuf {
+    path!(self.assets_index_path(), format!("{id}.json"))
+  }
+
+  pub fn assets_virtual_path(&self, id: &str) -> PathBuf {
+    path!(&self.assets_path(), ASSETS_VIRTUAL_DIR, id)
+  }
 }
 
 #[derive(Clone)]
@@ -122,6 +131,10 @@ impl MCVersionPath {
     &self.base_path
   }
 
+  pub fn version(&self) -> &str {
+    &self.version
+  }
+
   pub fn version_manifest(&self) -> PathBuf {
     path!(&self.base_path, format!("{}.json", self.version))
   }
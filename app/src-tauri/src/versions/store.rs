@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Instant};
 
 use anyhow::Result;
 use log::info;
@@ -15,8 +15,10 @@ use crate::{
     download::{download_and_parse_file_no_hash, download_and_parse_file_no_hash_force},
     file::file_hash,
     updater::{UpdateType, default_client, update_data},
+    version::normalize_mc_version,
   },
   versions::{
+    custom::{CustomVersionError, MAX_INHERITANCE_DEPTH, local_versions, parent_version},
     download::check_download_version,
     event::{DownloadCheckStatus, emit_download_check_status},
     loader::LoaderType,
@@ -29,7 +31,7 @@ use super::{
   download::DownloadError,
   meta::{
     java::JavaVersions,
-    minecraft::{Manifest, VersionType},
+    minecraft::{LatestVersion, Manifest, ManifestVersion, VersionType},
   },
 };
 
@@ -132,6 +134,11 @@ impl McVersionStore {
     loader: LoaderType,
     loader_version: Option<String>,
   ) -> Result<bool> {
+    // without an installer a custom loader would silently launch vanilla
+    if loader.is_custom() && loader_version.is_none() {
+      return Err(DownloadError::NotFound.into());
+    }
+
     let notify = Arc::new(Notify::new());
     let mut notifies = self.cancel_notify.lock().await;
     notifies.insert(id, notify.clone());
@@ -141,12 +148,7 @@ impl McVersionStore {
     info!("Checking/Downloading minecraft version {version} with download id {id}");
     let data_dir = self.handle.path().app_data_dir()?;
 
-    let mc = self
-      .mc_manifest
-      .versions
-      .iter()
-      .find(|v| v.id == version)
-      .ok_or(DownloadError::NotFound)?;
+    let mc = self.manifest_version(&data_dir, version).await?;
 
     #[cfg(target_os = "linux")]
     let java = &self.java_manifest.linux;
@@ -160,6 +162,7 @@ impl McVersionStore {
     let mut download_finished = false;
     select! {
       result = check_download_version(
+        version,
         mc,
         java,
         &data_dir,
@@ -197,12 +200,11 @@ impl McVersionStore {
 
   pub async fn check_meta(&self, version: &str, id: usize) -> Result<bool> {
     let data_dir = self.handle.path().app_data_dir()?;
-    let manifest_version = self
-      .mc_manifest
-      .versions
-      .iter()
-      .find(|v| v.id == version)
-      .ok_or(DownloadError::NotFound)?;
+    let Some(manifest_version) = self.manifest_version(&data_dir, version).await? else {
+      // completely local versions have nothing to check against
+      emit_download_check_status(&self.handle, DownloadCheckStatus::Done, id);
+      return Ok(true);
+    };
 
     let path = MCVersionPath::new(&data_dir, &manifest_version.id).version_manifest();
     let ok = file_hash(&manifest_version.sha1, &path).await?;
@@ -214,7 +216,8 @@ impl McVersionStore {
   }
 
   pub async fn list_versions(&self, loader: &LoaderType) -> Result<Vec<String>> {
-    let stable = !self.handle.app_settings()?.minecraft.show_snapshots;
+    let settings = self.handle.app_settings()?.minecraft;
+    let stable = !settings.show_snapshots;
 
     if let Some(loader) = loader.loader() {
       let mc_versions = self
@@ -238,16 +241,29 @@ impl McVersionStore {
 
       Ok(supported_versions)
     } else {
-      Ok(
+      let data_dir = self.handle.path().app_data_dir()?;
+      // custom versions are listed first as they are only there if someone added them
+      let mut versions = local_versions(&data_dir)
+        .await?
+        .into_iter()
+        .filter(|id| !self.mc_manifest.versions.iter().any(|v| &v.id == id))
+        .collect::<Vec<_>>();
+
+      // old versions are the ones mojang still lists, builds only kept by archives are not offered
+      versions.extend(
         self
           .mc_manifest
           .versions
           .iter()
-          .filter(|v| v.r#type == VersionType::Release || !stable)
-          .map(|v| &v.id)
-          .cloned()
-          .collect(),
-      )
+          .filter(|v| match v.r#type {
+            VersionType::Release => true,
+            VersionType::Snapshot => settings.show_snapshots,
+            VersionType::OldBeta | VersionType::OldAlpha => settings.show_old_versions,
+          })
+          .map(|v| v.id.clone()),
+      );
+
+      Ok(versions)
     }
   }
 
@@ -269,6 +285,57 @@ impl McVersionStore {
     }
   }
 
+  /// Finds the mojang version a version is based on by following `inheritsFrom` of custom versions.
+  /// Returns `None` for versions that are completely local.
+  async fn manifest_version(
+    &self,
+    data_dir: &PathBuf,
+    version: &str,
+  ) -> Result<Option<&ManifestVersion>> {
+    let mut current = version.to_string();
+    for _ in 0..=MAX_INHERITANCE_DEPTH {
+      if let Some(mc) = self.mc_manifest.versions.iter().find(|v| v.id == current) {
+        return Ok(Some(mc));
+      }
+      if !MCVersionPath::new(data_dir, &current)
+        .version_manifest()
+        .exists()
+      {
+        return Err(DownloadError::NotFound.into());
+      }
+
+      match parent_version(data_dir, &current).await? {
+        Some(parent) => current = parent,
+        None => return Ok(None),
+      }
+    }
+
+    Err(CustomVersionError::InheritanceLoop.into())
+  }
+
+  /// The version in the semver form fabric and quilt mods use in their dependencies.
+  /// Snapshots are assigned to the first release after them.
+  pub async fn mod_version(&self, data_dir: &PathBuf, version: &str) -> Option<String> {
+    let mc = self
+      .manifest_version(data_dir, version)
+      .await
+      .ok()
+      .flatten()?;
+    let release = self
+      .mc_manifest
+      .versions
+      .iter()
+      .filter(|v| v.r#type == VersionType::Release && v.release_time > mc.release_time)
+      .min_by_key(|v| v.release_time)
+      .map(|v| v.id.as_str());
+
+    normalize_mc_version(&mc.id, release)
+  }
+
+  pub fn latest(&self) -> &LatestVersion {
+    &self.mc_manifest.latest
+  }
+
   pub fn handle(&self) -> &AppHandle {
     &self.handle
   }
@@ -13,9 +13,15 @@ export enum UpdateType {
   Profiles = 'Profiles',
   ProfileLogs = 'ProfileLogs',
   ProfileQuickPlay = 'ProfileQuickPlay',
+  ProfileBackups = 'ProfileBackups',
+  ProfileWorlds = 'ProfileWorlds',
+  ProfileServers = 'ProfileServers',
+  ProfilePacks = 'ProfilePacks',
+  ProfileMods = 'ProfileMods',
   //Instances
   Instances = 'Instances',
   InstanceLogs = 'InstanceLogs',
+  InstanceStats = 'InstanceStats',
   //Settings
   Settings = 'Settings',
   //Offline
@@ -0,0 +1,127 @@
+import { invoke } from '@tauri-apps/api/core';
+import { parseError } from './profile.svelte';
+
+export interface BackupRetention {
+  keep_last: number;
+  keep_daily: number;
+  keep_weekly: number;
+}
+
+export interface BackupSettings {
+  folders: string[];
+  before_launch: boolean;
+  before_remove: boolean;
+  retention: BackupRetention;
+}
+
+export enum BackupReason {
+  Manual = 'manual',
+  Launch = 'launch',
+  Remove = 'remove',
+  Downgrade = 'downgrade'
+}
+
+export interface BackupInfo {
+  profile_id: string;
+  profile_name: string;
+  created_at: string;
+  reason: BackupReason;
+  folders: string[];
+  size: number;
+}
+
+export enum ChangeKind {
+  Added = 'added',
+  Modified = 'modified',
+  Removed = 'removed'
+}
+
+export interface RestoreChange {
+  path: string;
+  kind: ChangeKind;
+}
+
+export const profile_backup = async (profile: string) => {
+  try {
+    await invoke<BackupInfo>('profile_backup', {
+      profile
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const profile_backup_list = async (profile: string) => {
+  try {
+    return await invoke<BackupInfo[]>('profile_backup_list', {
+      profile
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const profile_backup_list_removed = async () => {
+  try {
+    return await invoke<BackupInfo[]>('profile_backup_list_removed');
+  } catch {
+    return undefined;
+  }
+};
+
+export const profile_backup_remove = async (
+  profile: string,
+  timestamp: string
+) => {
+  try {
+    await invoke('profile_backup_remove', {
+      profile,
+      timestamp
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const profile_backup_settings_set = async (
+  profile: string,
+  settings: BackupSettings
+) => {
+  try {
+    await invoke('profile_backup_settings_set', {
+      profile,
+      settings
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const profile_restore_preview = async (
+  profile: string,
+  timestamp: string
+) => {
+  try {
+    return await invoke<RestoreChange[]>('profile_restore_preview', {
+      profile,
+      timestamp
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const profile_restore = async (profile: string, timestamp: string) => {
+  try {
+    await invoke('profile_restore', {
+      profile,
+      timestamp
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
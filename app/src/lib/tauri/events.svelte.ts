@@ -47,6 +47,7 @@ export type VersionCheckStatus =
   | 'ModLoaderFilesDownloadInfo'
   | { ModLoaderFilesDownload: [number, number] }
   | 'ModLoaderPreprocess'
+  | { ModLoaderPreprocessStep: [number, number, string] }
   | 'ModLoaderPreprocessDone'
   | 'Done';
 
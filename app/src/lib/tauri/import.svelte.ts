@@ -0,0 +1,55 @@
+import { invoke } from '@tauri-apps/api/core';
+import {
+  parseError,
+  type GameSettings,
+  type JvmSettings,
+  type LoaderType
+} from './profile.svelte';
+
+export enum ImportSource {
+  Vanilla = 'vanilla',
+  MultiMc = 'multi_mc',
+  AtLauncher = 'at_launcher'
+}
+
+export interface ImportCandidate {
+  source: ImportSource;
+  name: string;
+  game_dir: string;
+  version: string;
+  loader: LoaderType;
+  loader_version?: string;
+  jvm?: JvmSettings;
+  game?: GameSettings;
+  // base64 encoded png
+  icon?: string;
+}
+
+export const profile_import_scan = async (
+  source: ImportSource,
+  path: string
+): Promise<ImportCandidate[] | undefined> => {
+  try {
+    return await invoke('profile_import_scan', {
+      source,
+      path
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const profile_import = async (
+  candidates: ImportCandidate[],
+  link: boolean
+) => {
+  try {
+    await invoke('profile_import', {
+      candidates,
+      link
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
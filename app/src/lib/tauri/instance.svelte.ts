@@ -8,14 +8,23 @@ import { toast } from 'svelte-sonner';
 
 export interface InstanceInfo {
   id: string;
+  pid: number;
   launched_at: string;
   profile_name: string;
   profile_id: string;
   version: string;
   loader: LoaderType;
   loader_version?: string;
+  attached: boolean;
+  state: InstanceState;
 }
 
+export type InstanceState =
+  | 'Running'
+  | 'Stopping'
+  | { Exited: number | null }
+  | 'Killed';
+
 const instance_list_call = async (): Promise<InstanceInfo[] | undefined> => {
   try {
     return await invoke('instance_list');
@@ -39,6 +48,25 @@ export const instance_logs = async (
   }
 };
 
+export interface InstanceSample {
+  timestamp: string;
+  cpu: number;
+  memory: number;
+  threads?: number;
+  uptime: number;
+}
+
+export const instance_stats = async (
+  profile: string,
+  id: string
+): Promise<InstanceSample[] | undefined> => {
+  try {
+    return await invoke('instance_stats', { id, profile });
+  } catch {
+    return undefined;
+  }
+};
+
 export const instance_stop = async (
   profile: string,
   id: string
@@ -51,8 +79,24 @@ export const instance_stop = async (
   }
 };
 
-interface InstanceCrash {
+export enum CrashCause {
+  WrongJavaVersion = 'WrongJavaVersion',
+  MissingModDependency = 'MissingModDependency',
+  MixinFailure = 'MixinFailure',
+  OutOfMemory = 'OutOfMemory',
+  GlDriver = 'GlDriver',
+  Unknown = 'Unknown'
+}
+
+export interface CrashInfo {
   profile_name: string;
+  profile_id: string;
+  launched_at: string;
+  exit_code?: number;
+  cause: CrashCause;
+  log_file?: string;
+  crash_report?: string;
+  jvm_crash_log?: string;
 }
 
 export const listen_instance_crash = async () => {
@@ -61,7 +105,7 @@ export const listen_instance_crash = async () => {
   }
   return await listen(INSTANCE_CRASH_EVENT, (event) => {
     // oxlint-disable-next-line no-unsafe-type-assertion
-    const { profile_name } = event.payload as InstanceCrash;
+    const { profile_name } = event.payload as CrashInfo;
     toast.warning(`An instance of profile ${profile_name} has crashed.`);
   });
 };
@@ -1,5 +1,27 @@
 import { invoke } from '@tauri-apps/api/core';
 import { parseError } from './profile.svelte';
+import type { QuickPlayType } from './quick-play.svelte';
+
+export interface PlayStats {
+  // all durations in seconds
+  total_playtime: number;
+  session_count: number;
+  average_session: number;
+  crash_count: number;
+  last_session?: string;
+}
+
+export interface QuickPlayStats {
+  id: string;
+  name?: string;
+  type: QuickPlayType;
+  stats: PlayStats;
+}
+
+export interface ProfileStats {
+  total: PlayStats;
+  quick_play: QuickPlayStats[];
+}
 
 export const profile_runs_list = async (profile: string) => {
   try {
@@ -35,3 +57,13 @@ export const profile_logs = async (
     return undefined;
   }
 };
+
+export const profile_stats = async (profile: string) => {
+  try {
+    return await invoke<ProfileStats>('profile_stats', {
+      profile
+    });
+  } catch {
+    return undefined;
+  }
+};
@@ -0,0 +1,231 @@
+import { invoke } from '@tauri-apps/api/core';
+import { type LoaderType, parseError } from './profile.svelte';
+
+export enum DependencyKind {
+  Required = 'required',
+  Optional = 'optional',
+  Incompatible = 'incompatible'
+}
+
+export interface ModDependency {
+  id: string;
+  version?: string;
+  kind: DependencyKind;
+}
+
+export interface ModInfo {
+  // ends with .disabled if the mod is disabled
+  file: string;
+  enabled: boolean;
+  id: string;
+  name: string;
+  version: string;
+  description?: string;
+  authors: string[];
+  // base64 encoded png
+  icon?: string;
+  dependencies: ModDependency[];
+  loaders: LoaderType[];
+  loader?: LoaderType;
+  provides: ProvidedMod[];
+  loader_mismatch: boolean;
+}
+
+export interface ProvidedMod {
+  id: string;
+  version: string;
+}
+
+export type ModIssue =
+  | {
+      type: 'missing_dependency';
+      file: string;
+      mod_id: string;
+      dependency: string;
+      version?: string;
+    }
+  | {
+      type: 'wrong_dependency_version';
+      file: string;
+      mod_id: string;
+      dependency: string;
+      version: string;
+      found: string[];
+    }
+  | {
+      type: 'incompatible';
+      file: string;
+      mod_id: string;
+      other: string;
+      version?: string;
+    }
+  | { type: 'duplicate_mod'; mod_id: string; files: string[] }
+  | { type: 'wrong_loader'; file: string; mod_id: string }
+  | {
+      type: 'unknown_game_version';
+      file: string;
+      mod_id: string;
+      version: string;
+      found: string;
+    };
+
+export interface BisectStep {
+  started_at: string;
+  enabled: string[];
+  disabled: string[];
+  crashed?: boolean;
+}
+
+export interface Bisect {
+  original: string[];
+  suspects: string[];
+  steps: BisectStep[];
+  result?: string;
+}
+
+export interface ModChangelog {
+  version_number: string;
+  changelog?: string;
+  date_published: string;
+}
+
+export interface ModUpdate {
+  file: string;
+  project_id: string;
+  current_version: string;
+  version_id: string;
+  version_number: string;
+  // newest first
+  changelogs: ModChangelog[];
+}
+
+export interface ModUpdateTarget {
+  file: string;
+  version_id: string;
+}
+
+export interface ModReport {
+  issues: ModIssue[];
+  has_errors: boolean;
+}
+
+export const mod_list = async (profile: string) => {
+  try {
+    return await invoke<ModInfo[]>('mod_list', {
+      profile
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const mod_check = async (profile: string) => {
+  try {
+    return await invoke<ModReport>('mod_check', {
+      profile
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const profile_mods_check_updates = async (profile: string) => {
+  try {
+    return await invoke<ModUpdate[]>('profile_mods_check_updates', {
+      profile
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const profile_mods_update = async (
+  profile: string,
+  updates: ModUpdateTarget[]
+) => {
+  try {
+    await invoke('profile_mods_update', {
+      profile,
+      updates
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const profile_mods_set_pinned = async (
+  profile: string,
+  file: string,
+  pinned: boolean
+) => {
+  try {
+    await invoke('profile_mods_set_pinned', {
+      profile,
+      file,
+      pinned
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const mod_set_enabled = async (
+  profile: string,
+  files: string[],
+  enabled: boolean
+) => {
+  try {
+    return await invoke<string[]>('mod_set_enabled', {
+      profile,
+      files,
+      enabled
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const mod_bisect_get = async (profile: string) => {
+  try {
+    return await invoke<Bisect | null>('mod_bisect_get', {
+      profile
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const mod_bisect_start = async (profile: string) => {
+  try {
+    return await invoke<Bisect>('mod_bisect_start', {
+      profile
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+};
+
+// without crashed the last run of the current step is used
+export const mod_bisect_report = async (profile: string, crashed?: boolean) => {
+  try {
+    return await invoke<Bisect>('mod_bisect_report', {
+      profile,
+      crashed
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+};
+
+export const mod_bisect_stop = async (profile: string) => {
+  try {
+    await invoke('mod_bisect_stop', {
+      profile
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
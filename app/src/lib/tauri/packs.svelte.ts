@@ -0,0 +1,107 @@
+import { invoke } from '@tauri-apps/api/core';
+import { parseError } from './profile.svelte';
+
+export enum PackType {
+  Resource = 'resource',
+  Shader = 'shader'
+}
+
+export enum PackCompatibility {
+  Compatible = 'compatible',
+  TooOld = 'too_old',
+  TooNew = 'too_new',
+  Unknown = 'unknown'
+}
+
+export interface PackInfo {
+  id: string;
+  type: PackType;
+  description?: string;
+  pack_format?: number;
+  compatibility: PackCompatibility;
+  enabled: boolean;
+  size: number;
+  // base64 encoded png
+  icon?: string;
+}
+
+export const pack_list = async (profile: string, type: PackType) => {
+  try {
+    return await invoke<PackInfo[]>('pack_list', {
+      profile,
+      type
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const pack_set_enabled = async (
+  profile: string,
+  type: PackType,
+  pack: string,
+  enabled: boolean
+) => {
+  try {
+    await invoke('pack_set_enabled', {
+      profile,
+      type,
+      pack,
+      enabled
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const pack_import = async (
+  profile: string,
+  type: PackType,
+  source: string
+) => {
+  try {
+    return await invoke<string>('pack_import', {
+      profile,
+      type,
+      source
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const pack_install = async (
+  profile: string,
+  type: PackType,
+  project: string,
+  version?: string
+) => {
+  try {
+    return await invoke<string>('pack_install', {
+      profile,
+      type,
+      project,
+      version
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const pack_remove = async (
+  profile: string,
+  type: PackType,
+  pack: string
+) => {
+  try {
+    await invoke('pack_remove', {
+      profile,
+      type,
+      pack
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
@@ -10,7 +10,11 @@ import { listen } from '@tauri-apps/api/event';
 import { browser } from '$app/environment';
 import { toast } from '@profidev/pleiades/components/util/general';
 import type { QuickPlayInfo } from './quick-play.svelte';
+import type { BackupSettings } from './backup.svelte';
+import type { ModInfo } from './mods.svelte';
+import { world_newer_get } from './worlds.svelte';
 import DownloadNotificationCancel from '$lib/components/profile/DownloadNotificationCancel.svelte';
+import LaunchWithoutBackup from '$lib/components/profile/LaunchWithoutBackup.svelte';
 import type { ComponentProps } from 'svelte';
 import DownloadNotification from '$lib/components/profile/DownloadNotification.svelte';
 import { b_to_mb } from '$lib/util.svelte';
@@ -29,6 +33,35 @@ export interface Profile {
   game?: GameSettings;
   use_local_jvm: boolean;
   jvm?: JvmSettings;
+  group?: string;
+  tags: string[];
+  sort_index: number;
+  pinned: boolean;
+  backup: BackupSettings;
+  // jar names of mods that are never updated
+  pinned_mods: string[];
+  // switch to newer stable loader versions on launch
+  auto_update_loader: boolean;
+}
+
+export interface ProfileFilter {
+  group?: string;
+  tags?: string[];
+  search?: string;
+}
+
+export enum ProfileSortKey {
+  Manual = 'manual',
+  Name = 'name',
+  LastPlayed = 'last_played',
+  Created = 'created',
+  Version = 'version',
+  Loader = 'loader'
+}
+
+export interface ProfileSort {
+  key: ProfileSortKey;
+  descending?: boolean;
 }
 
 export interface ProfileUpdate {
@@ -39,6 +72,7 @@ export interface ProfileUpdate {
   game?: GameSettings;
   use_local_jvm: boolean;
   jvm?: JvmSettings;
+  auto_update_loader?: boolean;
 }
 
 export interface GameSettings {
@@ -63,12 +97,20 @@ export enum LoaderType {
   Fabric = 'Fabric',
   Quilt = 'Quilt',
   Forge = 'Forge',
-  NeoForge = 'NeoForge'
+  NeoForge = 'NeoForge',
+  LegacyFabric = 'LegacyFabric',
+  Babric = 'Babric',
+  LiteLoader = 'LiteLoader',
+  OptiFine = 'OptiFine',
+  CustomForge = 'CustomForge',
+  CustomFabric = 'CustomFabric'
 }
 
 export const ModdedLoaderType = {
+  Babric: LoaderType.Babric,
   Fabric: LoaderType.Fabric,
   Forge: LoaderType.Forge,
+  LegacyFabric: LoaderType.LegacyFabric,
   NeoForge: LoaderType.NeoForge,
   Quilt: LoaderType.Quilt
 } as const;
@@ -76,6 +118,10 @@ export const ModdedLoaderType = {
 export enum ProfileError {
   InvalidImage = 'InvalidImage',
   NotFound = 'NotFound',
+  WorldNewer = 'WorldNewer',
+  UnsupportedVersion = 'UnsupportedVersion',
+  InvalidLoaderVersion = 'InvalidLoaderVersion',
+  ModIssues = 'ModIssues',
   Other = 'Other'
 }
 
@@ -104,6 +150,30 @@ export const profile_create = async (data: {
   return undefined;
 };
 
+export interface DuplicateOptions {
+  saves: boolean;
+  screenshots: boolean;
+  logs: boolean;
+  mods: boolean;
+}
+
+export const profile_duplicate = async (
+  profile: string,
+  name: string,
+  options: DuplicateOptions
+) => {
+  try {
+    await invoke('profile_duplicate', {
+      profile,
+      name,
+      options
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
 export const profile_update = async (profile: ProfileUpdate) => {
   try {
     await invoke('profile_update', {
@@ -115,6 +185,49 @@ export const profile_update = async (profile: ProfileUpdate) => {
   return undefined;
 };
 
+// returns the enabled mods the new loader can not load
+export const profile_switch_loader_check = async (
+  profile: string,
+  loader: LoaderType
+) => {
+  try {
+    return await invoke<ModInfo[]>('profile_switch_loader_check', {
+      profile,
+      loader
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const profile_switch_loader = async (
+  profile: string,
+  loader: LoaderType,
+  loaderVersion?: string
+) => {
+  try {
+    await invoke('profile_switch_loader', {
+      profile,
+      loader,
+      loaderVersion
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+// returns the newer stable loader version if there is one
+export const profile_loader_update_check = async (profile: string) => {
+  try {
+    return await invoke<string | null>('profile_loader_update_check', {
+      profile
+    });
+  } catch {
+    return undefined;
+  }
+};
+
 export const profile_get_icon = async (profile: string) => {
   try {
     return await invoke<string | undefined>('profile_get_icon', {
@@ -162,23 +275,97 @@ export const profile_remove = async (profile: string) => {
   return undefined;
 };
 
-const profile_list_call = async (): Promise<Profile[] | undefined> => {
+export const profile_list_filtered = async (
+  filter?: ProfileFilter,
+  sort?: ProfileSort
+): Promise<Profile[] | undefined> => {
   try {
-    return await invoke('profile_list');
+    return await invoke('profile_list', {
+      filter,
+      sort
+    });
   } catch {
     return undefined;
   }
 };
+
+const profile_list_call = async (): Promise<Profile[] | undefined> =>
+  profile_list_filtered();
 export const profile_list = create_data_state(
   profile_list_call,
   UpdateType.Profiles
 );
 
+const profile_groups_list_call = async (): Promise<string[] | undefined> => {
+  try {
+    return await invoke('profile_groups_list');
+  } catch {
+    return undefined;
+  }
+};
+export const profile_groups_list = create_data_state(
+  profile_groups_list_call,
+  UpdateType.Profiles
+);
+
+export const profile_move_group = async (
+  profiles: string[],
+  group?: string
+) => {
+  try {
+    await invoke('profile_move_group', {
+      profiles,
+      group
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const profile_reorder = async (profiles: string[]) => {
+  try {
+    await invoke('profile_reorder', {
+      profiles
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const profile_set_tags = async (profile: string, tags: string[]) => {
+  try {
+    await invoke('profile_set_tags', {
+      profile,
+      tags
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const profile_set_pinned = async (profile: string, pinned: boolean) => {
+  try {
+    await invoke('profile_set_pinned', {
+      profile,
+      pinned
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
 export const profile_launch = async (
   profile: string,
   name: string,
   active?: string,
-  quickPlay?: QuickPlayInfo
+  quickPlay?: QuickPlayInfo,
+  force?: boolean,
+  backup?: boolean,
+  skipModCheck?: boolean
 ) => {
   if (active === undefined || active === '') {
     toast.warning('No active account set');
@@ -190,7 +377,10 @@ export const profile_launch = async (
     'profile_launch',
     `Launching profile ${name}`,
     `Failed to launch profile ${name}`,
-    quickPlay
+    quickPlay,
+    force,
+    backup,
+    skipModCheck
   );
 };
 
@@ -213,7 +403,10 @@ const launch_repair = async (
   cmd: string,
   message: string,
   err: string,
-  quickPlay?: QuickPlayInfo
+  quickPlay?: QuickPlayInfo,
+  force?: boolean,
+  backup?: boolean,
+  skipModCheck?: boolean
 ) => {
   const id = Math.round(Math.random() * 1_000_000);
   try {
@@ -226,11 +419,74 @@ const launch_repair = async (
     await invoke(cmd, {
       id,
       profile,
-      quickPlay
+      quickPlay,
+      force,
+      backup,
+      skipModCheck
     });
-  } catch {
+  } catch (error: any) {
     check_message.delete(id);
 
+    if (quickPlay && parseError(error) === ProfileError.WorldNewer) {
+      const launch = (backup: boolean) => {
+        const _ = launch_repair(
+          profile,
+          cmd,
+          message,
+          err,
+          quickPlay,
+          true,
+          backup,
+          skipModCheck
+        );
+      };
+      const newer = await world_newer_get(profile, quickPlay.id);
+      const versions = newer
+        ? ` (${newer.world_version}) than ${newer.game_version}`
+        : '';
+      toast.warning(
+        `World ${quickPlay.name} was saved in a newer version${versions}, opening it may corrupt it`,
+        {
+          cancel: (internal: any, props: any) =>
+            LaunchWithoutBackup(internal, {
+              ...props,
+              onclick: () => launch(false)
+            }),
+          duration: undefined,
+          id,
+          action: {
+            label: 'Backup & Launch',
+            onClick: () => launch(true)
+          }
+        }
+      );
+      return;
+    }
+
+    if (parseError(error) === ProfileError.ModIssues) {
+      toast.warning('Some mods have missing dependencies or conflicts', {
+        cancel: undefined,
+        duration: undefined,
+        id,
+        action: {
+          label: 'Launch anyway',
+          onClick: () => {
+            const _ = launch_repair(
+              profile,
+              cmd,
+              message,
+              err,
+              quickPlay,
+              force,
+              backup,
+              true
+            );
+          }
+        }
+      });
+      return;
+    }
+
     toast.error(err, {
       cancel: undefined,
       duration: undefined,
@@ -349,6 +605,15 @@ const get_message = (
       'Downloading mod loader files',
       true
     );
+  } else if ('ModLoaderPreprocessStep' in event) {
+    const [done, total, line] = event.ModLoaderPreprocessStep;
+    return message_props(
+      [done, total],
+      line
+        ? `Running mod loader processors: ${line}`
+        : 'Running mod loader processors',
+      false
+    );
   }
 
   return undefined;
@@ -0,0 +1,101 @@
+import { invoke } from '@tauri-apps/api/core';
+import { parseError } from './profile.svelte';
+
+export interface ServerEntry {
+  name: string;
+  ip: string;
+  // base64 encoded png
+  icon?: string;
+  accept_textures?: boolean;
+  hidden: boolean;
+}
+
+export interface ServerStatus {
+  version: string;
+  protocol: number;
+  players_online: number;
+  players_max: number;
+  motd: string;
+  // base64 encoded png
+  favicon?: string;
+  // in milliseconds
+  latency: number;
+}
+
+export const server_list = async (profile: string) => {
+  try {
+    return await invoke<ServerEntry[]>('server_list', {
+      profile
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const server_add = async (profile: string, server: ServerEntry) => {
+  try {
+    await invoke('server_add', {
+      profile,
+      server
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const server_update = async (
+  profile: string,
+  index: number,
+  server: ServerEntry
+) => {
+  try {
+    await invoke('server_update', {
+      profile,
+      index,
+      server
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const server_move = async (
+  profile: string,
+  from: number,
+  to: number
+) => {
+  try {
+    await invoke('server_move', {
+      profile,
+      from,
+      to
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const server_remove = async (profile: string, index: number) => {
+  try {
+    await invoke('server_remove', {
+      profile,
+      index
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const server_ping = async (address: string) => {
+  try {
+    return await invoke<ServerStatus>('server_ping', {
+      address
+    });
+  } catch {
+    return undefined;
+  }
+};
@@ -12,6 +12,8 @@ export interface Settings {
 
 export interface MinecraftSettings {
   show_snapshots: boolean;
+  show_old_versions: boolean;
+  stop_timeout_secs: number;
   game_settings: GameSettings;
   jvm_settings: JvmSettings;
 }
@@ -0,0 +1,124 @@
+import { invoke } from '@tauri-apps/api/core';
+import { parseError } from './profile.svelte';
+
+export enum GameMode {
+  Survival = 'survival',
+  Creative = 'creative',
+  Adventure = 'adventure',
+  Spectator = 'spectator'
+}
+
+export enum Difficulty {
+  Peaceful = 'peaceful',
+  Easy = 'easy',
+  Normal = 'normal',
+  Hard = 'hard'
+}
+
+export interface WorldInfo {
+  id: string;
+  name: string;
+  game_mode?: GameMode;
+  hardcore: boolean;
+  difficulty?: Difficulty;
+  last_played?: string;
+  data_version?: number;
+  version_name?: string;
+  seed?: number;
+  size: number;
+  // base64 encoded png
+  icon?: string;
+}
+
+export interface NewerWorld {
+  world_version: string;
+  game_version: string;
+}
+
+export const world_list = async (profile: string) => {
+  try {
+    return await invoke<WorldInfo[]>('world_list', {
+      profile
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const world_info_get = async (profile: string, world: string) => {
+  try {
+    return await invoke<WorldInfo>('world_info_get', {
+      profile,
+      world
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const world_newer_get = async (profile: string, world: string) => {
+  try {
+    return await invoke<NewerWorld | null>('world_newer_get', {
+      profile,
+      world
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const world_export = async (
+  profile: string,
+  world: string,
+  target: string
+) => {
+  try {
+    await invoke('world_export', {
+      profile,
+      world,
+      target
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};
+
+export const world_import = async (profile: string, source: string) => {
+  try {
+    return await invoke<string>('world_import', {
+      profile,
+      source
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const world_copy = async (
+  profile: string,
+  world: string,
+  target: string
+) => {
+  try {
+    return await invoke<string>('world_copy', {
+      profile,
+      world,
+      target
+    });
+  } catch {
+    return undefined;
+  }
+};
+
+export const world_remove = async (profile: string, world: string) => {
+  try {
+    await invoke('world_remove', {
+      profile,
+      world
+    });
+  } catch (error: any) {
+    return parseError(error);
+  }
+  return undefined;
+};